@@ -0,0 +1,445 @@
+use crate::gameboy::JoypadButton;
+use piston::input::Key;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// How far an axis has to move from the centre to count as pressed
+const AXIS_THRESHOLD: f64 = 0.5;
+
+/// Emulator controls which aren't Gameboy buttons
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Hotkey {
+    Pause,
+    FastForward,
+    Screenshot,
+}
+
+/// What an input is bound to
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Action {
+    Button(JoypadButton),
+    Hotkey(Hotkey),
+}
+
+/// The names of the actions in the config file
+const ACTIONS: [(&str, Action); 11] = [
+    ("right", Action::Button(JoypadButton::Right)),
+    ("left", Action::Button(JoypadButton::Left)),
+    ("up", Action::Button(JoypadButton::Up)),
+    ("down", Action::Button(JoypadButton::Down)),
+    ("a", Action::Button(JoypadButton::A)),
+    ("b", Action::Button(JoypadButton::B)),
+    ("select", Action::Button(JoypadButton::Select)),
+    ("start", Action::Button(JoypadButton::Start)),
+    ("pause", Action::Hotkey(Hotkey::Pause)),
+    ("fast_forward", Action::Hotkey(Hotkey::FastForward)),
+    ("screenshot", Action::Hotkey(Hotkey::Screenshot)),
+];
+
+const DEFAULT_KEYS: [(Action, Key); 11] = [
+    (Action::Button(JoypadButton::Right), Key::Right),
+    (Action::Button(JoypadButton::Left), Key::Left),
+    (Action::Button(JoypadButton::Up), Key::Up),
+    (Action::Button(JoypadButton::Down), Key::Down),
+    (Action::Button(JoypadButton::A), Key::Z),
+    (Action::Button(JoypadButton::B), Key::X),
+    (Action::Button(JoypadButton::Select), Key::Backspace),
+    (Action::Button(JoypadButton::Start), Key::Return),
+    (Action::Hotkey(Hotkey::Pause), Key::P),
+    (Action::Hotkey(Hotkey::FastForward), Key::Tab),
+    (Action::Hotkey(Hotkey::Screenshot), Key::F12),
+];
+
+/// A button or one direction of an axis on any controller
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ControllerInput {
+    Button(u8),
+    Axis { axis: u8, positive: bool },
+}
+
+/// Reasons the config file can't be used
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(std::io::Error),
+    Syntax { line: usize, message: &'static str },
+    UnknownSection { line: usize, name: String },
+    UnknownAction { line: usize, name: String },
+    UnknownKey { line: usize, name: String },
+    UnknownControllerInput { line: usize, name: String },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io(err) => write!(f, "{}", err),
+            ConfigError::Syntax { line, message } => write!(f, "line {}: {}", line, message),
+            ConfigError::UnknownSection { line, name } => write!(
+                f,
+                "line {}: unknown section [{}]. Expected [keyboard] or [controller]",
+                line, name
+            ),
+            ConfigError::UnknownAction { line, name } => write!(
+                f,
+                "line {}: unknown action \"{}\". Expected one of {}",
+                line,
+                name,
+                ACTIONS.map(|(name, _)| name).join(", ")
+            ),
+            ConfigError::UnknownKey { line, name } => write!(
+                f,
+                "line {}: unknown key \"{}\". Keys use piston's names like \"Z\", \"D1\" or \"Return\"",
+                line, name
+            ),
+            ConfigError::UnknownControllerInput { line, name } => write!(
+                f,
+                "line {}: unknown controller input \"{}\". Expected \"ButtonN\", \"AxisN+\" or \"AxisN-\"",
+                line, name
+            ),
+        }
+    }
+}
+
+impl From<std::io::Error> for ConfigError {
+    fn from(err: std::io::Error) -> Self {
+        ConfigError::Io(err)
+    }
+}
+
+enum Section {
+    Keyboard,
+    Controller,
+}
+
+/// Which keys & controller inputs are bound to each action.
+///
+/// The config file is a small subset of TOML. Each binding replaces the default
+/// for that action & can be a single name or a list:
+///
+/// ```toml
+/// [keyboard]
+/// a = "Z"
+/// start = ["Return", "Space"]
+///
+/// [controller]
+/// a = "Button0"
+/// left = "Axis0-"
+/// ```
+pub struct Config {
+    keyboard: HashMap<Action, Vec<Key>>,
+    controller: HashMap<Action, Vec<ControllerInput>>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            keyboard: DEFAULT_KEYS
+                .iter()
+                .map(|&(action, key)| (action, vec![key]))
+                .collect(),
+            controller: HashMap::new(),
+        }
+    }
+}
+
+impl Config {
+    /// $XDG_CONFIG_HOME/rust-gb/config.toml, falling back to ~/.config
+    pub fn default_path() -> Option<PathBuf> {
+        let config_home = std::env::var_os("XDG_CONFIG_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+
+        Some(config_home.join("rust-gb").join("config.toml"))
+    }
+
+    pub fn load(path: &Path) -> Result<Config, ConfigError> {
+        Config::parse(&fs::read_to_string(path)?)
+    }
+
+    pub fn parse(text: &str) -> Result<Config, ConfigError> {
+        let mut config = Config::default();
+        let mut section = None;
+
+        for (index, line) in text.lines().enumerate() {
+            let line_number = index + 1;
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                section = match name.trim() {
+                    "keyboard" => Some(Section::Keyboard),
+                    "controller" => Some(Section::Controller),
+                    name => {
+                        return Err(ConfigError::UnknownSection {
+                            line: line_number,
+                            name: name.to_string(),
+                        });
+                    }
+                };
+                continue;
+            }
+
+            let Some((name, value)) = line.split_once('=') else {
+                return Err(ConfigError::Syntax {
+                    line: line_number,
+                    message: "expected a section or action = \"input\"",
+                });
+            };
+
+            let name = name.trim();
+            let Some(action) = parse_action(name) else {
+                return Err(ConfigError::UnknownAction {
+                    line: line_number,
+                    name: name.to_string(),
+                });
+            };
+
+            let Some(values) = parse_values(value.trim()) else {
+                return Err(ConfigError::Syntax {
+                    line: line_number,
+                    message: "expected a quoted name or a list of quoted names",
+                });
+            };
+
+            match section {
+                Some(Section::Keyboard) => {
+                    let keys = values
+                        .iter()
+                        .map(|name| {
+                            parse_key(name).ok_or_else(|| ConfigError::UnknownKey {
+                                line: line_number,
+                                name: name.to_string(),
+                            })
+                        })
+                        .collect::<Result<_, _>>()?;
+                    config.keyboard.insert(action, keys);
+                }
+                Some(Section::Controller) => {
+                    let inputs = values
+                        .iter()
+                        .map(|name| {
+                            parse_controller_input(name).ok_or_else(|| {
+                                ConfigError::UnknownControllerInput {
+                                    line: line_number,
+                                    name: name.to_string(),
+                                }
+                            })
+                        })
+                        .collect::<Result<_, _>>()?;
+                    config.controller.insert(action, inputs);
+                }
+                None => {
+                    return Err(ConfigError::Syntax {
+                        line: line_number,
+                        message: "bindings must be inside [keyboard] or [controller]",
+                    });
+                }
+            }
+        }
+
+        Ok(config)
+    }
+
+    pub fn key_action(&self, key: Key) -> Option<Action> {
+        self.keyboard
+            .iter()
+            .find(|(_, keys)| keys.contains(&key))
+            .map(|(&action, _)| action)
+    }
+
+    pub fn controller_action(&self, input: ControllerInput) -> Option<Action> {
+        self.controller
+            .iter()
+            .find(|(_, inputs)| inputs.contains(&input))
+            .map(|(&action, _)| action)
+    }
+}
+
+/// Both directions of an axis & whether each is held at the position
+pub fn axis_inputs(axis: u8, position: f64) -> [(ControllerInput, bool); 2] {
+    [
+        (
+            ControllerInput::Axis {
+                axis,
+                positive: true,
+            },
+            position > AXIS_THRESHOLD,
+        ),
+        (
+            ControllerInput::Axis {
+                axis,
+                positive: false,
+            },
+            position < -AXIS_THRESHOLD,
+        ),
+    ]
+}
+
+fn parse_action(name: &str) -> Option<Action> {
+    ACTIONS
+        .iter()
+        .find(|(action_name, _)| *action_name == name)
+        .map(|&(_, action)| action)
+}
+
+/// Either "name" or ["name", ...]
+fn parse_values(value: &str) -> Option<Vec<&str>> {
+    match value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+        Some(list) => list
+            .split(',')
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+            .map(parse_string)
+            .collect(),
+        None => parse_string(value).map(|name| vec![name]),
+    }
+}
+
+fn parse_string(value: &str) -> Option<&str> {
+    value.strip_prefix('"').and_then(|v| v.strip_suffix('"'))
+}
+
+/// Keys use the names of piston's `Key` variants, ignoring case. "Enter" is also accepted.
+fn parse_key(name: &str) -> Option<Key> {
+    if name.eq_ignore_ascii_case("enter") {
+        return Some(Key::Return);
+    }
+
+    // Piston keys are numbered by their SDL key codes
+    (0x01..=0x7F)
+        .chain(0x4000_0039..=0x4000_011A)
+        .map(Key::from)
+        .find(|key| *key != Key::Unknown && format!("{:?}", key).eq_ignore_ascii_case(name))
+}
+
+/// ButtonN, AxisN+ or AxisN-
+fn parse_controller_input(name: &str) -> Option<ControllerInput> {
+    let name = name.to_ascii_lowercase();
+
+    if let Some(button) = name.strip_prefix("button") {
+        return button.parse().ok().map(ControllerInput::Button);
+    }
+
+    let axis = name.strip_prefix("axis")?;
+    let (axis, positive) = if let Some(axis) = axis.strip_suffix('+') {
+        (axis, true)
+    } else {
+        (axis.strip_suffix('-')?, false)
+    };
+
+    axis.parse()
+        .ok()
+        .map(|axis| ControllerInput::Axis { axis, positive })
+}
+
+#[test]
+fn defaults_are_used_for_actions_not_in_the_file() {
+    let config = Config::parse(
+        "# Use WASD to move\n\
+         [keyboard]\n\
+         up = \"W\"\n\
+         left = [\"a\", \"Left\"]\n\
+         start = \"Enter\" # Same as Return\n",
+    )
+    .unwrap();
+
+    assert_eq!(
+        config.key_action(Key::W),
+        Some(Action::Button(JoypadButton::Up))
+    );
+    assert_eq!(config.key_action(Key::Up), None);
+    assert_eq!(
+        config.key_action(Key::A),
+        Some(Action::Button(JoypadButton::Left))
+    );
+    assert_eq!(
+        config.key_action(Key::Left),
+        Some(Action::Button(JoypadButton::Left))
+    );
+    assert_eq!(
+        config.key_action(Key::Return),
+        Some(Action::Button(JoypadButton::Start))
+    );
+    assert_eq!(
+        config.key_action(Key::Z),
+        Some(Action::Button(JoypadButton::A))
+    );
+    assert_eq!(
+        config.key_action(Key::F12),
+        Some(Action::Hotkey(Hotkey::Screenshot))
+    );
+}
+
+#[test]
+fn controller_buttons_and_axes_can_be_bound() {
+    let config = Config::parse(
+        "[controller]\n\
+         a = \"Button0\"\n\
+         left = \"Axis0-\"\n\
+         fast_forward = \"axis5+\"\n",
+    )
+    .unwrap();
+
+    assert_eq!(
+        config.controller_action(ControllerInput::Button(0)),
+        Some(Action::Button(JoypadButton::A))
+    );
+    assert_eq!(
+        config.controller_action(ControllerInput::Axis {
+            axis: 0,
+            positive: false
+        }),
+        Some(Action::Button(JoypadButton::Left))
+    );
+    assert_eq!(
+        config.controller_action(ControllerInput::Axis {
+            axis: 5,
+            positive: true
+        }),
+        Some(Action::Hotkey(Hotkey::FastForward))
+    );
+
+    let [(right, right_held), (left, left_held)] = axis_inputs(0, -0.9);
+    assert!(!right_held && left_held);
+    assert_eq!(config.controller_action(right), None);
+    assert_eq!(
+        config.controller_action(left),
+        Some(Action::Button(JoypadButton::Left))
+    );
+}
+
+#[test]
+fn unknown_names_are_errors_with_the_line() {
+    let error = Config::parse("[keyboard]\n\nsave_state = \"F5\"")
+        .err()
+        .unwrap();
+    assert_eq!(
+        error.to_string(),
+        "line 3: unknown action \"save_state\". Expected one of right, left, up, down, \
+         a, b, select, start, pause, fast_forward, screenshot"
+    );
+
+    let error = Config::parse("[keyboard]\na = \"Kwerty\"").err().unwrap();
+    assert!(matches!(error, ConfigError::UnknownKey { line: 2, .. }));
+
+    let error = Config::parse("[joystick]").err().unwrap();
+    assert!(matches!(error, ConfigError::UnknownSection { line: 1, .. }));
+
+    let error = Config::parse("[controller]\na = \"Trigger\"")
+        .err()
+        .unwrap();
+    assert!(matches!(
+        error,
+        ConfigError::UnknownControllerInput { line: 2, .. }
+    ));
+
+    let error = Config::parse("a = \"Z\"").err().unwrap();
+    assert!(matches!(error, ConfigError::Syntax { line: 1, .. }));
+
+    let error = Config::parse("[keyboard]\na = Z").err().unwrap();
+    assert!(matches!(error, ConfigError::Syntax { line: 2, .. }));
+}
@@ -1,22 +1,41 @@
-use super::Channel;
 use super::{timer::TickResult, Timer};
+use super::{
+    Channel, FrameSequencer, NoiseChannel, WaveChannel, SQUARE_1_REGISTERS, SQUARE_2_REGISTERS,
+};
+use crate::gameboy::bus::Bus;
+use crate::gameboy::Labels;
+
+/// A sample for each speaker after the NR51 panning & NR50 master volume
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct StereoFrame {
+    pub left: i16,
+    pub right: i16,
+}
 
 #[allow(clippy::upper_case_acronyms)]
 pub struct ALU<'a> {
-    audio_callback: Box<dyn FnMut(i16) + 'a>,
+    audio_callback: Box<dyn FnMut(StereoFrame) + 'a>,
     sample_timer: Timer,
+    /// NR52 bit 7
+    powered: bool,
+    frame_sequencer: FrameSequencer,
     square_channel_1: Channel,
+    square_channel_2: Channel,
+    wave_channel: WaveChannel,
+    noise_channel: NoiseChannel,
 }
 
 const SAMPLE_RATE: i32 = 44100; // Hz
 const CYCLES_PER_SECOND: i32 = 4194304;
 const CYCLES_PER_SAMPLE: i32 = CYCLES_PER_SECOND / SAMPLE_RATE;
-// const CYCLES_PER_LENGTH: i32 = CYCLES_PER_SECOND / 256;
+
+/// On the DMG the length counters can still be loaded while the APU is off
+const LENGTH_REGISTERS: [u16; 4] = [0xFF11, 0xFF16, 0xFF1B, 0xFF20];
 
 impl<'a> ALU<'a> {
     pub fn new<F>(audio_callback: F) -> ALU<'a>
     where
-        F: FnMut(i16) + 'a,
+        F: FnMut(StereoFrame) + 'a,
     {
         let mut sample_timer = Timer::new();
         sample_timer.start(CYCLES_PER_SAMPLE);
@@ -24,17 +43,159 @@ impl<'a> ALU<'a> {
         ALU {
             audio_callback: Box::new(audio_callback),
             sample_timer,
-            square_channel_1: Channel::new(),
+            powered: false,
+            frame_sequencer: FrameSequencer::new(),
+            square_channel_1: Channel::new(SQUARE_1_REGISTERS).with_sweep(),
+            square_channel_2: Channel::new(SQUARE_2_REGISTERS),
+            wave_channel: WaveChannel::new(),
+            noise_channel: NoiseChannel::new(),
         }
     }
 
-    pub fn tick(&mut self, tick: u32, memory: &mut [u8]) {
-        self.square_channel_1.tick(tick, memory);
+    /// Run the channels for a number of cycles.
+    /// `frame_clocks` is the number of times the timer clocked the frame sequencer.
+    pub fn tick(&mut self, tick: u32, frame_clocks: u32, memory: &mut Bus) {
+        if self.powered {
+            for _ in 0..frame_clocks {
+                self.clock_frame_sequencer(memory);
+            }
+        }
+
+        self.square_channel_1.tick(tick);
+        self.square_channel_2.tick(tick);
+        self.wave_channel.tick(tick, memory);
+        self.noise_channel.tick(tick);
+        self.update_status(memory);
 
         // if the cycles are less than 0 then emit a value, reset the count
         if self.sample_timer.tick(tick) == TickResult::Ticked {
-            let volume = self.square_channel_1.get_volume();
-            self.audio_callback.as_mut()(volume);
+            let frame = self.mix(memory);
+            self.audio_callback.as_mut()(frame);
+        }
+    }
+
+    /// Called after the CPU writes one of the sound registers
+    pub fn register_written(&mut self, address: u16, value: u8, memory: &mut Bus) {
+        if address == Labels::SOUND_ON {
+            self.power_written(value, memory);
+        } else if self.powered || LENGTH_REGISTERS.contains(&address) {
+            self.channel_register_written(address, value, memory);
+        }
+
+        self.update_status(memory);
+    }
+
+    fn channel_register_written(&mut self, address: u16, value: u8, memory: &mut Bus) {
+        let frame_sequencer = &self.frame_sequencer;
+        match address {
+            0xFF10..=0xFF14 => {
+                self.square_channel_1
+                    .register_written(address, value, frame_sequencer, memory)
+            }
+            0xFF16..=0xFF19 => {
+                self.square_channel_2
+                    .register_written(address, value, frame_sequencer, memory)
+            }
+            0xFF1A..=0xFF1E => {
+                self.wave_channel
+                    .register_written(address, value, frame_sequencer, memory)
+            }
+            0xFF20..=0xFF23 => {
+                self.noise_channel
+                    .register_written(address, value, frame_sequencer, memory)
+            }
+            _ => {}
+        }
+    }
+
+    fn power_written(&mut self, value: u8, memory: &mut Bus) {
+        let powered = (value & 0b1000_0000) != 0;
+
+        if self.powered && !powered {
+            // Powering off clears all the sound registers & stops the channels.
+            // Wave RAM is left alone.
+            for address in Labels::SOUND_START..=Labels::SOUND_PANNING {
+                memory.write_raw(address, 0x00);
+            }
+            self.square_channel_1 = Channel::new(SQUARE_1_REGISTERS).with_sweep();
+            self.square_channel_2 = Channel::new(SQUARE_2_REGISTERS);
+            self.wave_channel = WaveChannel::new();
+            self.noise_channel = NoiseChannel::new();
+        } else if !self.powered && powered {
+            // The frame sequencer starts again from step 0
+            self.frame_sequencer = FrameSequencer::new();
+        }
+
+        self.powered = powered;
+    }
+
+    /// The bottom 4 bits of NR52 read back which channels are playing
+    fn update_status(&self, memory: &mut Bus) {
+        let status = [
+            self.square_channel_1.is_enabled(),
+            self.square_channel_2.is_enabled(),
+            self.wave_channel.is_enabled(),
+            self.noise_channel.is_enabled(),
+        ]
+        .iter()
+        .enumerate()
+        .fold(0, |status, (channel, &enabled)| {
+            status | ((enabled as u8) << channel)
+        });
+
+        let sound_on = memory.read_raw(Labels::SOUND_ON);
+        memory.write_raw(Labels::SOUND_ON, (sound_on & 0b1111_0000) | status);
+    }
+
+    /// Pan each channel to the left & right with NR51, then scale by the NR50 volumes
+    fn mix(&self, memory: &Bus) -> StereoFrame {
+        let volumes = [
+            self.square_channel_1.get_volume(),
+            self.square_channel_2.get_volume(),
+            self.wave_channel.get_volume(memory),
+            self.noise_channel.get_volume(),
+        ];
+
+        // Bits 4-7 send channels 1-4 to the left & bits 0-3 send them to the right
+        let panning = memory.read_raw(Labels::SOUND_PANNING);
+        let mut left = 0;
+        let mut right = 0;
+        for (channel, volume) in volumes.iter().enumerate() {
+            if panning & (0b0001_0000 << channel) != 0 {
+                left += volume;
+            }
+            if panning & (0b0000_0001 << channel) != 0 {
+                right += volume;
+            }
+        }
+
+        // Master volumes of 0-7 scale the output from 1/8 up to full
+        let master_volume = memory.read_raw(Labels::SOUND_VOLUME);
+        let left_volume = ((master_volume & 0b0111_0000) >> 4) as i16 + 1;
+        let right_volume = (master_volume & 0b0000_0111) as i16 + 1;
+
+        StereoFrame {
+            left: left * left_volume / 8,
+            right: right * right_volume / 8,
+        }
+    }
+
+    fn clock_frame_sequencer(&mut self, memory: &mut Bus) {
+        let step = self.frame_sequencer.clock();
+
+        if step.length {
+            self.square_channel_1.clock_length();
+            self.square_channel_2.clock_length();
+            self.wave_channel.clock_length();
+            self.noise_channel.clock_length();
+        }
+        if step.sweep {
+            self.square_channel_1.clock_sweep(memory);
+        }
+        if step.envelope {
+            self.square_channel_1.clock_envelope();
+            self.square_channel_2.clock_envelope();
+            self.noise_channel.clock_envelope();
         }
     }
 }
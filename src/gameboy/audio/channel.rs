@@ -1,92 +1,210 @@
+use crate::gameboy::bus::Bus;
+
 use super::{
     timer::{TickResult, Timer},
-    {get_duty, DutyCycle},
+    Envelope, FrameSequencer, LengthCounter, Sweep, {get_duty, DutyCycle},
 };
 
-const CYCLES_PER_SECOND: i32 = 4194304;
-const CYCLES_PER_PERIOD: i32 = CYCLES_PER_SECOND / 64;
+/// NR10. Channel 1's sweep
+pub const NR10: u16 = 0xFF10;
+/// NR11. Square channel 1 is controlled by NR11-NR14
+pub const SQUARE_1_REGISTERS: u16 = 0xFF11;
+/// NR21. Square channel 2 is controlled by NR21-NR24
+pub const SQUARE_2_REGISTERS: u16 = 0xFF16;
 
+/// A square wave channel. Channels 1 & 2 work the same but read different registers.
 pub struct Channel {
+    registers: u16,
     channel_timer: Timer,
-    period_timer: Timer, // 64Hz timer which reduces the period counter once hits 0. Actually the frame sequencer
-    frequency: i32,      // Defines how quickly we move through the duty cycle
-    volume: i32,         // The volume is reduced by 1 every time period counter hits 0
+    envelope: Envelope,
+    frequency: i32, // Defines how quickly we move through the duty cycle
     duty: DutyCycle,
     duty_position: u8,
     enabled: bool,
+    length_counter: LengthCounter,
+    /// Only channel 1 has a sweep
+    sweep: Option<Sweep>,
 }
 
 impl Channel {
-    pub fn new() -> Self {
+    /// Create a channel controlled by the 4 registers starting at `registers`
+    pub fn new(registers: u16) -> Self {
         Self {
+            registers,
             channel_timer: Timer::new(),
-            period_timer: Timer::new(),
+            envelope: Envelope::new(),
             frequency: 0,
-            volume: 0,
             duty: DutyCycle::Zero,
             duty_position: 0,
             enabled: false,
+            length_counter: LengthCounter::new(64),
+            sweep: None,
         }
     }
 
-    pub fn tick(&mut self, dt: u32, memory: &mut [u8]) {
-        // Set enabled from mem and trigger the channel
-        if (memory[0xFF14] & 0b1000_0000) != 0 {
-            self.trigger(memory);
+    /// Add the frequency sweep controlled by NR10
+    pub fn with_sweep(mut self) -> Self {
+        self.sweep = Some(Sweep::new());
+        self
+    }
+
+    /// NRx1: Duty & length
+    fn length_duty_register(&self) -> u16 {
+        self.registers
+    }
 
-            // Turn the channel on
-            self.enabled = true;
+    /// NRx2: Starting volume, envelope direction & period
+    fn envelope_register(&self) -> u16 {
+        self.registers + 1
+    }
 
-            // Reset the trigger
-            memory[0xFF14] &= 0b0111_1111;
-        }
+    /// NRx3: Bottom 8 bits of the frequency
+    fn frequency_register(&self) -> u16 {
+        self.registers + 2
+    }
+
+    /// NRx4: Trigger, length enable & top 3 bits of the frequency
+    fn control_register(&self) -> u16 {
+        self.registers + 3
+    }
 
+    pub fn tick(&mut self, dt: u32) {
         // If enabled start counting the timers
         if self.enabled {
-            // If the period timer has ticked reduce volume
-            if self.period_timer.tick(dt) == TickResult::Ticked {
-                self.volume -= 1;
-                self.volume = self.volume.max(0);
-            }
-
             // If the channel timer ticks increase the duty position
             if self.channel_timer.tick(dt) == TickResult::Ticked {
                 self.duty_position = (self.duty_position + 1) % 8;
             }
         }
+    }
 
-        if self.volume == 0 {
-            // This doesn't seem to make a difference but means there is less
-            // processing going on when no sound is playing.
-            // Unable to unit test
+    /// React to the CPU writing one of the channel's registers
+    pub fn register_written(
+        &mut self,
+        address: u16,
+        value: u8,
+        frame_sequencer: &FrameSequencer,
+        memory: &mut Bus,
+    ) {
+        if address == NR10 {
+            if let Some(sweep) = &mut self.sweep
+                && !sweep.nr10_written(value)
+            {
+                self.enabled = false;
+            }
+        } else if address == self.length_duty_register() {
+            self.length_counter.load(value & 0b0011_1111);
+        } else if address == self.envelope_register() {
+            // Turning the DAC off turns the channel off
+            if !Envelope::dac_enabled(value) {
+                self.enabled = false;
+            }
+        } else if address == self.frequency_register() {
+            self.update_frequency(memory);
+        } else if address == self.control_register() {
+            self.update_frequency(memory);
+
+            let next_clocks_length = frame_sequencer.next_clocks_length();
+            let length_enabled = (value & 0b0100_0000) != 0;
+            if self
+                .length_counter
+                .set_enabled(length_enabled, next_clocks_length)
+            {
+                self.enabled = false;
+            }
+
+            if (value & 0b1000_0000) != 0 {
+                self.trigger(next_clocks_length, memory);
+
+                // Reset the trigger
+                memory.write_raw(self.control_register(), value & 0b0111_1111);
+            }
+        }
+    }
+
+    pub fn clock_length(&mut self) {
+        if self.length_counter.clock() {
             self.enabled = false;
         }
     }
 
-    pub fn get_volume(&self) -> i16 {
-        self.volume as i16 * get_duty(self.duty, self.duty_position) as i16
+    pub fn clock_envelope(&mut self) {
+        self.envelope.clock();
     }
 
-    pub fn trigger(&mut self, memory: &[u8]) {
-        self.frequency = Self::get_frequency(memory);
-        self.volume = ((memory[0xFF12] & 0b1111_0000) >> 4) as i32;
+    /// Sweep the frequency, writing it back to NRx3 & NRx4
+    pub fn clock_sweep(&mut self, memory: &mut Bus) {
+        let Some(sweep) = &mut self.sweep else {
+            return;
+        };
 
-        // Set period timer to max * counter + enable timer
-        let period_counter = (memory[0xFF12] & 0b0000_0111) as i32;
-        if period_counter != 0 {
-            self.period_timer.start(CYCLES_PER_PERIOD * period_counter);
+        let result = sweep.clock(memory.read_raw(NR10));
+
+        if let Some(frequency) = result.frequency {
+            let control = memory.read_raw(self.control_register());
+            memory.write_raw(self.frequency_register(), frequency as u8);
+            memory.write_raw(
+                self.control_register(),
+                (control & 0b1111_1000) | (frequency >> 8) as u8,
+            );
+            self.update_frequency(memory);
+        }
+        if result.overflow {
+            self.enabled = false;
         }
+    }
+
+    /// Whether the channel is playing, as read back through NR52
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn get_volume(&self) -> i16 {
+        if !self.enabled {
+            return 0;
+        }
+
+        self.envelope.volume() as i16 * get_duty(self.duty, self.duty_position) as i16
+    }
+
+    pub fn trigger(&mut self, next_clocks_length: bool, memory: &Bus) {
+        // Turn the channel on
+        self.enabled = true;
+
+        self.frequency = self.get_frequency(memory);
+
+        let envelope = memory.read_raw(self.envelope_register());
+        self.envelope.trigger(envelope);
 
         // Set the channel timer from the frequency
         self.channel_timer.start((2048 - self.frequency) * 4);
 
-        // Set the duty
-        self.duty = DutyCycle::from((memory[0xFF11] & 0b1100_0000) >> 6);
+        // Set the duty & length
+        let length_duty = memory.read_raw(self.length_duty_register());
+        self.duty = DutyCycle::from((length_duty & 0b1100_0000) >> 6);
+        self.length_counter.trigger(next_clocks_length);
+
+        if let Some(sweep) = &mut self.sweep
+            && !sweep.trigger(self.frequency, memory.read_raw(NR10))
+        {
+            self.enabled = false;
+        }
+
+        // The channel can only start if its DAC is on
+        if !Envelope::dac_enabled(envelope) {
+            self.enabled = false;
+        }
+    }
+
+    /// A new frequency is used from the next time the channel timer elapses
+    fn update_frequency(&mut self, memory: &Bus) {
+        self.frequency = self.get_frequency(memory);
+        self.channel_timer.set_length((2048 - self.frequency) * 4);
     }
 
-    fn get_frequency(memory: &[u8]) -> i32 {
-        let freq_lsb = (memory[0xFF13]) as i32;
-        let freq_msb = (memory[0xFF14] & 0b0000_0111) as i32;
+    fn get_frequency(&self, memory: &Bus) -> i32 {
+        let freq_lsb = (memory.read_raw(self.frequency_register())) as i32;
+        let freq_msb = (memory.read_raw(self.control_register()) & 0b0000_0111) as i32;
         (freq_msb << 8) | freq_lsb
     }
 }
@@ -0,0 +1,54 @@
+/// The volume envelope shared by the square & noise channels.
+/// Every period of 1/64th of a second the volume moves towards 0 or 15.
+pub struct Envelope {
+    volume: i32,
+    increases: bool,
+    period: u8,
+    counter: u8,
+}
+
+impl Envelope {
+    pub fn new() -> Self {
+        Self {
+            volume: 0,
+            increases: false,
+            period: 0,
+            counter: 0,
+        }
+    }
+
+    /// Restart from the starting volume, direction & period in NRx2
+    pub fn trigger(&mut self, nrx2: u8) {
+        self.volume = ((nrx2 & 0b1111_0000) >> 4) as i32;
+        self.increases = (nrx2 & 0b0000_1000) != 0;
+        self.period = nrx2 & 0b0000_0111;
+        self.counter = self.period;
+    }
+
+    /// Clocked at 64Hz by the frame sequencer. A period of 0 stops the envelope.
+    pub fn clock(&mut self) {
+        if self.period == 0 {
+            return;
+        }
+
+        self.counter -= 1;
+        if self.counter == 0 {
+            self.counter = self.period;
+
+            if self.increases {
+                self.volume = (self.volume + 1).min(15);
+            } else {
+                self.volume = (self.volume - 1).max(0);
+            }
+        }
+    }
+
+    pub fn volume(&self) -> i32 {
+        self.volume
+    }
+
+    /// The channel's DAC is off when the top 5 bits of NRx2 are clear
+    pub fn dac_enabled(nrx2: u8) -> bool {
+        (nrx2 & 0b1111_1000) != 0
+    }
+}
@@ -0,0 +1,47 @@
+/// Which of the channels' units are clocked by a step of the frame sequencer
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FrameStep {
+    /// 256Hz
+    pub length: bool,
+    /// 128Hz
+    pub sweep: bool,
+    /// 64Hz
+    pub envelope: bool,
+}
+
+/// Steps through 8 steps at 512Hz, each time DIV bit 4 falls.
+///
+/// | Step | Length | Sweep | Envelope |
+/// | ---- | ------ | ----- | -------- |
+/// | 0    | Clock  |       |          |
+/// | 2    | Clock  | Clock |          |
+/// | 4    | Clock  |       |          |
+/// | 6    | Clock  | Clock |          |
+/// | 7    |        |       | Clock    |
+pub struct FrameSequencer {
+    /// The step which runs next
+    step: u8,
+}
+
+impl FrameSequencer {
+    pub fn new() -> Self {
+        Self { step: 0 }
+    }
+
+    pub fn clock(&mut self) -> FrameStep {
+        let step = self.step;
+        self.step = (self.step + 1) % 8;
+
+        FrameStep {
+            length: step.is_multiple_of(2),
+            sweep: step == 2 || step == 6,
+            envelope: step == 7,
+        }
+    }
+
+    /// Whether the next step clocks the length counters. Enabling or triggering
+    /// a length counter when it doesn't gives it an extra clock.
+    pub fn next_clocks_length(&self) -> bool {
+        self.step.is_multiple_of(2)
+    }
+}
@@ -0,0 +1,93 @@
+/// Turns a channel off after a number of 1/256ths of a second when enabled by NRx4.6
+pub struct LengthCounter {
+    /// 64 for the square & noise channels & 256 for the wave channel
+    max_length: u16,
+    length: u16,
+    enabled: bool,
+}
+
+impl LengthCounter {
+    pub fn new(max_length: u16) -> Self {
+        Self {
+            max_length,
+            length: 0,
+            enabled: false,
+        }
+    }
+
+    /// Writing the length register reloads the counter
+    pub fn load(&mut self, length_data: u8) {
+        self.length = self.max_length - length_data as u16;
+    }
+
+    /// Called when NRx4 is written. Returns true if the length ran out.
+    ///
+    /// Enabling the counter when the next frame sequencer step doesn't clock it
+    /// gives it an extra clock.
+    pub fn set_enabled(&mut self, enabled: bool, next_clocks_length: bool) -> bool {
+        let was_enabled = std::mem::replace(&mut self.enabled, enabled);
+
+        if !was_enabled && enabled && !next_clocks_length && self.length > 0 {
+            self.length -= 1;
+            return self.length == 0;
+        }
+        false
+    }
+
+    /// Triggering with a length of 0 reloads the maximum, which loses a clock
+    /// the same way as enabling the counter
+    pub fn trigger(&mut self, next_clocks_length: bool) {
+        if self.length == 0 {
+            self.length = self.max_length;
+            if self.enabled && !next_clocks_length {
+                self.length -= 1;
+            }
+        }
+    }
+
+    /// Count down. Returns true once the length runs out.
+    pub fn clock(&mut self) -> bool {
+        if self.enabled && self.length > 0 {
+            self.length -= 1;
+            return self.length == 0;
+        }
+        false
+    }
+}
+
+#[test]
+fn enabling_the_length_between_clocks_gives_an_extra_clock() {
+    let mut length_counter = LengthCounter::new(64);
+    length_counter.load(62);
+
+    // The next step clocks the length so nothing happens yet
+    assert!(!length_counter.set_enabled(true, true));
+    assert_eq!(length_counter.length, 2);
+
+    length_counter.set_enabled(false, false);
+    assert!(!length_counter.set_enabled(true, false));
+    assert_eq!(length_counter.length, 1);
+
+    // Running out this way turns the channel off
+    length_counter.set_enabled(false, false);
+    assert!(length_counter.set_enabled(true, false));
+    assert_eq!(length_counter.length, 0);
+}
+
+#[test]
+fn triggering_with_a_length_of_0_reloads_the_maximum() {
+    let mut length_counter = LengthCounter::new(256);
+    length_counter.trigger(true);
+    assert_eq!(length_counter.length, 256);
+
+    // Enabled between clocks the reload loses a clock
+    let mut length_counter = LengthCounter::new(64);
+    length_counter.set_enabled(true, true);
+    length_counter.trigger(false);
+    assert_eq!(length_counter.length, 63);
+
+    // Other lengths are kept
+    length_counter.load(60);
+    length_counter.trigger(false);
+    assert_eq!(length_counter.length, 4);
+}
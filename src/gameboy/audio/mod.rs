@@ -1,10 +1,22 @@
 mod alu;
 mod channel;
 mod duty_cycle;
+mod envelope;
+mod frame_sequencer;
+mod length_counter;
+mod noise_channel;
+mod sweep;
 mod timer;
+mod wave_channel;
 
 use channel::*;
 use duty_cycle::*;
+use envelope::*;
+use frame_sequencer::*;
+use length_counter::*;
+use noise_channel::*;
+use sweep::*;
 use timer::*;
+use wave_channel::*;
 
-pub use alu::ALU;
+pub use alu::{StereoFrame, ALU};
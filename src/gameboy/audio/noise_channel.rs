@@ -0,0 +1,173 @@
+use crate::gameboy::bus::Bus;
+
+use super::timer::Timer;
+use super::{Envelope, FrameSequencer, LengthCounter};
+
+const NR41: u16 = 0xFF20;
+const NR42: u16 = 0xFF21;
+const NR43: u16 = 0xFF22;
+const NR44: u16 = 0xFF23;
+
+/// Cycles between LFSR clocks for each divisor code before the clock shift
+const DIVISORS: [i32; 8] = [8, 16, 32, 48, 64, 80, 96, 112];
+
+/// Channel 4 plays pseudo random noise from a linear feedback shift register
+pub struct NoiseChannel {
+    channel_timer: Timer,
+    envelope: Envelope,
+    /// 15 bits. The channel outputs when bit 0 is 0.
+    lfsr: u16,
+    /// Feedback is also put in bit 6 making a 7 bit LFSR with a more tonal sound
+    short_mode: bool,
+    enabled: bool,
+    length_counter: LengthCounter,
+}
+
+impl NoiseChannel {
+    pub fn new() -> Self {
+        Self {
+            channel_timer: Timer::new(),
+            envelope: Envelope::new(),
+            lfsr: 0,
+            short_mode: false,
+            enabled: false,
+            length_counter: LengthCounter::new(64),
+        }
+    }
+
+    pub fn tick(&mut self, dt: u32) {
+        if self.enabled {
+            for _ in 0..self.channel_timer.tick_many(dt) {
+                self.clock_lfsr();
+            }
+        }
+    }
+
+    /// React to the CPU writing one of the channel's registers
+    pub fn register_written(
+        &mut self,
+        address: u16,
+        value: u8,
+        frame_sequencer: &FrameSequencer,
+        memory: &mut Bus,
+    ) {
+        match address {
+            NR41 => self.length_counter.load(value & 0b0011_1111),
+            // Turning the DAC off turns the channel off
+            NR42 if !Envelope::dac_enabled(value) => self.enabled = false,
+            NR44 => {
+                let next_clocks_length = frame_sequencer.next_clocks_length();
+                let length_enabled = (value & 0b0100_0000) != 0;
+                if self
+                    .length_counter
+                    .set_enabled(length_enabled, next_clocks_length)
+                {
+                    self.enabled = false;
+                }
+
+                if (value & 0b1000_0000) != 0 {
+                    self.trigger(next_clocks_length, memory);
+
+                    // Reset the trigger
+                    memory.write_raw(NR44, value & 0b0111_1111);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    pub fn clock_length(&mut self) {
+        if self.length_counter.clock() {
+            self.enabled = false;
+        }
+    }
+
+    pub fn clock_envelope(&mut self) {
+        self.envelope.clock();
+    }
+
+    /// Whether the channel is playing, as read back through NR52
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn get_volume(&self) -> i16 {
+        if !self.enabled || self.lfsr & 0b1 != 0 {
+            return 0;
+        }
+
+        self.envelope.volume() as i16
+    }
+
+    pub fn trigger(&mut self, next_clocks_length: bool, memory: &Bus) {
+        // The channel can only start if its DAC is on
+        let envelope = memory.read_raw(NR42);
+        self.enabled = Envelope::dac_enabled(envelope);
+
+        self.envelope.trigger(envelope);
+        self.lfsr = 0x7FFF;
+
+        // The period is the divisor shifted left by the clock shift
+        let polynomial = memory.read_raw(NR43);
+        let shift = (polynomial & 0b1111_0000) >> 4;
+        let divisor = DIVISORS[(polynomial & 0b0000_0111) as usize];
+        self.short_mode = (polynomial & 0b0000_1000) != 0;
+
+        // Clock shifts of 14 & 15 stop the LFSR
+        self.channel_timer = Timer::new();
+        if shift < 14 {
+            self.channel_timer.start(divisor << shift);
+        }
+
+        self.length_counter.trigger(next_clocks_length);
+    }
+
+    /// Shift right, feeding back the XOR of the bottom 2 bits into bit 14 (& bit 6 in short mode)
+    fn clock_lfsr(&mut self) {
+        let feedback = (self.lfsr & 0b1) ^ ((self.lfsr >> 1) & 0b1);
+        self.lfsr = (self.lfsr >> 1) | (feedback << 14);
+
+        if self.short_mode {
+            self.lfsr = (self.lfsr & !(1 << 6)) | (feedback << 6);
+        }
+    }
+}
+
+#[test]
+fn lfsr_feeds_back_the_xor_of_the_bottom_bits() {
+    let mut channel = NoiseChannel::new();
+
+    channel.lfsr = 0b000_0000_0000_0001;
+    channel.clock_lfsr();
+    assert_eq!(channel.lfsr, 0b100_0000_0000_0000);
+
+    channel.lfsr = 0b000_0000_0000_0011;
+    channel.clock_lfsr();
+    assert_eq!(channel.lfsr, 0b000_0000_0000_0001);
+
+    channel.short_mode = true;
+    channel.lfsr = 0b000_0000_0100_0010;
+    channel.clock_lfsr();
+    assert_eq!(channel.lfsr, 0b100_0000_0110_0001);
+}
+
+#[test]
+fn short_mode_repeats_every_127_clocks() {
+    let mut channel = NoiseChannel::new();
+    channel.short_mode = true;
+    channel.lfsr = 0x7FFF;
+
+    // Run long enough for the top bits to stop mattering
+    for _ in 0..15 {
+        channel.clock_lfsr();
+    }
+
+    let start = channel.lfsr & 0x7F;
+    let period = (1..=127)
+        .find(|_| {
+            channel.clock_lfsr();
+            channel.lfsr & 0x7F == start
+        })
+        .unwrap();
+    assert_eq!(period, 127);
+}
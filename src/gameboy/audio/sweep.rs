@@ -0,0 +1,107 @@
+/// The largest frequency that fits in NRx3 & NRx4
+const MAX_FREQUENCY: i32 = 2047;
+
+/// What a sweep clock did to channel 1
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct SweepResult {
+    /// The new frequency to write back to NRx3 & NRx4
+    pub frequency: Option<i32>,
+    /// The frequency went over 2047 & the channel turns off
+    pub overflow: bool,
+}
+
+/// Channel 1's frequency sweep, controlled by NR10.
+///
+/// Every sweep period the frequency is shifted right by the sweep shift &
+/// added to or subtracted from itself.
+pub struct Sweep {
+    enabled: bool,
+    shadow_frequency: i32,
+    timer: u8,
+    /// A calculation has subtracted since the last trigger
+    negate_used: bool,
+}
+
+impl Sweep {
+    pub fn new() -> Self {
+        Self {
+            enabled: false,
+            shadow_frequency: 0,
+            timer: 0,
+            negate_used: false,
+        }
+    }
+
+    /// Restart from the channel's frequency.
+    /// Returns false if the overflow check turns the channel straight off.
+    pub fn trigger(&mut self, frequency: i32, nr10: u8) -> bool {
+        self.shadow_frequency = frequency;
+        self.timer = Self::period(nr10);
+        self.negate_used = false;
+        self.enabled = nr10 & 0b0111_0000 != 0 || Self::shift(nr10) != 0;
+
+        // With a shift the overflow check runs straight away
+        Self::shift(nr10) == 0 || self.calculate(nr10) <= MAX_FREQUENCY
+    }
+
+    /// Clocked at 128Hz by the frame sequencer
+    pub fn clock(&mut self, nr10: u8) -> SweepResult {
+        self.timer = self.timer.saturating_sub(1);
+        if self.timer > 0 {
+            return SweepResult::default();
+        }
+        self.timer = Self::period(nr10);
+
+        // A period of 0 reloads the timer but doesn't sweep
+        if !self.enabled || nr10 & 0b0111_0000 == 0 {
+            return SweepResult::default();
+        }
+
+        let frequency = self.calculate(nr10);
+        if frequency > MAX_FREQUENCY {
+            return SweepResult {
+                frequency: None,
+                overflow: true,
+            };
+        }
+        if Self::shift(nr10) == 0 {
+            return SweepResult::default();
+        }
+        self.shadow_frequency = frequency;
+
+        // The new frequency is checked again straight away but not written back
+        SweepResult {
+            frequency: Some(frequency),
+            overflow: self.calculate(nr10) > MAX_FREQUENCY,
+        }
+    }
+
+    /// Switching from subtraction to addition after subtracting turns the channel off.
+    /// Returns false if that happened.
+    pub fn nr10_written(&mut self, nr10: u8) -> bool {
+        !(self.negate_used && nr10 & 0b0000_1000 == 0)
+    }
+
+    fn calculate(&mut self, nr10: u8) -> i32 {
+        let change = self.shadow_frequency >> Self::shift(nr10);
+
+        if nr10 & 0b0000_1000 != 0 {
+            self.negate_used = true;
+            self.shadow_frequency - change
+        } else {
+            self.shadow_frequency + change
+        }
+    }
+
+    /// The sweep timer treats a period of 0 as 8
+    fn period(nr10: u8) -> u8 {
+        match (nr10 & 0b0111_0000) >> 4 {
+            0 => 8,
+            period => period,
+        }
+    }
+
+    fn shift(nr10: u8) -> u8 {
+        nr10 & 0b0000_0111
+    }
+}
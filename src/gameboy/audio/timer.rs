@@ -38,4 +38,30 @@ impl Timer {
         }
         TickResult::Disabled
     }
+
+    /// Tick the timer & return how many times it elapsed.
+    /// Unlike `tick` this keeps up with timers shorter than `dt`.
+    pub fn tick_many(&mut self, dt: u32) -> u32 {
+        if !self.enabled || self.length <= 0 {
+            return 0;
+        }
+
+        let mut ticks = 0;
+        self.count -= dt as i32;
+        while self.count <= 0 {
+            self.count += self.length;
+            ticks += 1;
+        }
+        ticks
+    }
+
+    /// Change the length used from the next time the timer elapses
+    pub fn set_length(&mut self, length: i32) {
+        self.length = length;
+    }
+
+    /// Cycles until the timer next elapses
+    pub fn remaining(&self) -> i32 {
+        self.count
+    }
 }
@@ -0,0 +1,225 @@
+use crate::gameboy::bus::Bus;
+
+use super::timer::Timer;
+use super::{FrameSequencer, LengthCounter};
+
+const NR30: u16 = 0xFF1A;
+const NR31: u16 = 0xFF1B;
+const NR32: u16 = 0xFF1C;
+const NR33: u16 = 0xFF1D;
+const NR34: u16 = 0xFF1E;
+const WAVE_RAM_START: u16 = 0xFF30;
+
+/// The number of 4 bit samples in wave RAM
+const WAVE_SAMPLES: u8 = 32;
+
+/// Channel 3 plays the 32 samples in wave RAM on a loop
+pub struct WaveChannel {
+    channel_timer: Timer,
+    frequency: i32,
+    /// The sample being played, 0-31
+    position: u8,
+    /// The last sample read from wave RAM. This isn't cleared on trigger.
+    sample: u8,
+    enabled: bool,
+    length_counter: LengthCounter,
+}
+
+impl WaveChannel {
+    pub fn new() -> Self {
+        Self {
+            channel_timer: Timer::new(),
+            frequency: 0,
+            position: 0,
+            sample: 0,
+            enabled: false,
+            length_counter: LengthCounter::new(256),
+        }
+    }
+
+    pub fn tick(&mut self, dt: u32, memory: &Bus) {
+        if self.enabled {
+            // Each time the channel timer elapses the next sample is read
+            for _ in 0..self.channel_timer.tick_many(dt) {
+                self.position = (self.position + 1) % WAVE_SAMPLES;
+                self.sample = Self::read_sample(memory, self.position);
+            }
+        }
+    }
+
+    /// React to the CPU writing one of the channel's registers
+    pub fn register_written(
+        &mut self,
+        address: u16,
+        value: u8,
+        frame_sequencer: &FrameSequencer,
+        memory: &mut Bus,
+    ) {
+        match address {
+            // Turning the DAC off turns the channel off
+            NR30 if (value & 0b1000_0000) == 0 => self.enabled = false,
+            NR31 => self.length_counter.load(value),
+            NR33 => self.update_frequency(memory),
+            NR34 => {
+                self.update_frequency(memory);
+
+                let next_clocks_length = frame_sequencer.next_clocks_length();
+                let length_enabled = (value & 0b0100_0000) != 0;
+                if self
+                    .length_counter
+                    .set_enabled(length_enabled, next_clocks_length)
+                {
+                    self.enabled = false;
+                }
+
+                if (value & 0b1000_0000) != 0 {
+                    self.trigger(next_clocks_length, memory);
+
+                    // Reset the trigger
+                    memory.write_raw(NR34, value & 0b0111_1111);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    pub fn clock_length(&mut self) {
+        if self.length_counter.clock() {
+            self.enabled = false;
+        }
+    }
+
+    /// Whether the channel is playing, as read back through NR52
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn get_volume(&self, memory: &Bus) -> i16 {
+        if !self.enabled {
+            return 0;
+        }
+
+        // The output level is applied by shifting the sample
+        let sample = match (memory.read_raw(NR32) & 0b0110_0000) >> 5 {
+            0 => 0,
+            1 => self.sample,
+            2 => self.sample >> 1,
+            _ => self.sample >> 2,
+        };
+        sample as i16
+    }
+
+    pub fn trigger(&mut self, next_clocks_length: bool, memory: &mut Bus) {
+        // On the DMG retriggering just as a sample is read corrupts wave RAM
+        if self.enabled && self.channel_timer.remaining() <= 2 {
+            Self::corrupt_wave_ram(memory, (self.position + 1) % WAVE_SAMPLES);
+        }
+
+        self.frequency = Self::get_frequency(memory);
+
+        // Wave channel samples are read twice as fast as square channel duty steps
+        self.channel_timer.start((2048 - self.frequency) * 2);
+        self.position = 0;
+
+        self.length_counter.trigger(next_clocks_length);
+
+        // The channel can only start if its DAC is on
+        self.enabled = Self::dac_enabled(memory);
+    }
+
+    /// A new frequency is used from the next time the channel timer elapses
+    fn update_frequency(&mut self, memory: &Bus) {
+        self.frequency = Self::get_frequency(memory);
+        self.channel_timer.set_length((2048 - self.frequency) * 2);
+    }
+
+    fn get_frequency(memory: &Bus) -> i32 {
+        let freq_lsb = memory.read_raw(NR33) as i32;
+        let freq_msb = (memory.read_raw(NR34) & 0b0000_0111) as i32;
+        (freq_msb << 8) | freq_lsb
+    }
+
+    fn dac_enabled(memory: &Bus) -> bool {
+        (memory.read_raw(NR30) & 0b1000_0000) != 0
+    }
+
+    /// Each byte of wave RAM holds 2 samples with the first in the upper 4 bits
+    fn read_sample(memory: &Bus, position: u8) -> u8 {
+        let byte = memory.read_raw(WAVE_RAM_START + (position / 2) as u16);
+        if position.is_multiple_of(2) {
+            byte >> 4
+        } else {
+            byte & 0b0000_1111
+        }
+    }
+
+    /// The byte being read is copied over the start of wave RAM.
+    /// If it's in the first 4 bytes only byte 0 changes, otherwise the aligned
+    /// block of 4 bytes containing it is copied over the first 4.
+    fn corrupt_wave_ram(memory: &mut Bus, position: u8) {
+        let offset = (position / 2) as u16;
+
+        if offset < 4 {
+            let value = memory.read_raw(WAVE_RAM_START + offset);
+            memory.write_raw(WAVE_RAM_START, value);
+        } else {
+            let block = offset & !0b11;
+            for i in 0..4 {
+                let value = memory.read_raw(WAVE_RAM_START + block + i);
+                memory.write_raw(WAVE_RAM_START + i, value);
+            }
+        }
+    }
+}
+
+#[test]
+fn retriggering_as_a_sample_is_read_corrupts_wave_ram() {
+    let mut memory = Bus::new(&[]).unwrap();
+    for i in 0..16 {
+        memory.write_raw(WAVE_RAM_START + i, i as u8 * 0x11);
+    }
+
+    // Each sample lasts 4 cycles
+    memory.write_raw(NR30, 0b1000_0000);
+    memory.write_raw(NR33, 0xFE);
+
+    let frame_sequencer = FrameSequencer::new();
+    let mut channel = WaveChannel::new();
+    memory.write_raw(NR34, 0b1000_0111);
+    channel.register_written(NR34, 0b1000_0111, &frame_sequencer, &mut memory);
+
+    // Move to sample 9 & stop just before sample 10 in byte 5 is read
+    channel.tick(38, &memory);
+    assert_eq!(channel.position, 9);
+
+    memory.write_raw(NR34, 0b1000_0111);
+    channel.register_written(NR34, 0b1000_0111, &frame_sequencer, &mut memory);
+
+    let wave_ram: Vec<u8> = (0..16)
+        .map(|i| memory.read_raw(WAVE_RAM_START + i))
+        .collect();
+    assert_eq!(&wave_ram[0..4], &[0x44, 0x55, 0x66, 0x77]);
+    assert_eq!(&wave_ram[4..8], &[0x44, 0x55, 0x66, 0x77]);
+}
+
+#[test]
+fn retriggering_between_samples_leaves_wave_ram_alone() {
+    let mut memory = Bus::new(&[]).unwrap();
+    for i in 0..16 {
+        memory.write_raw(WAVE_RAM_START + i, i as u8 * 0x11);
+    }
+
+    memory.write_raw(NR30, 0b1000_0000);
+    memory.write_raw(NR33, 0xFE);
+
+    let frame_sequencer = FrameSequencer::new();
+    let mut channel = WaveChannel::new();
+    memory.write_raw(NR34, 0b1000_0111);
+    channel.register_written(NR34, 0b1000_0111, &frame_sequencer, &mut memory);
+    channel.tick(37, &memory);
+
+    memory.write_raw(NR34, 0b1000_0111);
+    channel.register_written(NR34, 0b1000_0111, &frame_sequencer, &mut memory);
+
+    assert_eq!(memory.read_raw(WAVE_RAM_START), 0x00);
+}
@@ -0,0 +1,481 @@
+use super::cartridge::CartridgeError;
+use super::interrupt_controller::{request_interrupt, Interrupt};
+use super::joypad::{Joypad, JoypadButton};
+use super::mbc::{create_mapper, Mapper};
+use super::memory_labels::Labels;
+
+/// Anything the CPU can read from & write to
+pub trait Memory {
+    fn read(&self, address: u16) -> u8;
+    /// Returns false if the write was ignored
+    fn write(&mut self, address: u16, value: u8) -> bool;
+}
+
+/// A flat block of memory with no regions.
+///
+/// This is useful for testing instructions without the rest of the Gameboy.
+impl Memory for Vec<u8> {
+    fn read(&self, address: u16) -> u8 {
+        self[address as usize]
+    }
+
+    fn write(&mut self, address: u16, value: u8) -> bool {
+        self[address as usize] = value;
+        true
+    }
+}
+
+/// The areas of memory an address can point to. Each contains the offset into that area.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Region {
+    BootRom(usize),
+    Rom,
+    VideoRam(usize),
+    CartRam,
+    WorkRam(usize),
+    Oam(usize),
+    Unusable,
+    Io(usize),
+    HighRam(usize),
+    InterruptEnable,
+}
+
+/// Routes reads & writes to the correct area of memory.
+///
+/// `read` & `write` are how the CPU sees memory. ROM can't be written to,
+/// the unusable area is ignored & the IO registers have unreadable bits.
+/// The hardware (PPU, timer, etc.) uses `read_raw` & `write_raw` which skip these rules.
+pub struct Bus {
+    boot_rom: Option<Vec<u8>>,
+    mapper: Box<dyn Mapper>,
+    /// The cartridge RAM has changed since the save file was written
+    save_dirty: bool,
+    video_ram: Vec<u8>,
+    work_ram: Vec<u8>,
+    oam: Vec<u8>,
+    io: Vec<u8>,
+    high_ram: Vec<u8>,
+    interrupt_enable: u8,
+    /// While OAM DMA runs the CPU can only reach HRAM
+    dma: Option<Dma>,
+    joypad: Joypad,
+}
+
+/// OAM DMA copies a byte every M-cycle
+const DMA_CYCLES_PER_BYTE: u32 = 4;
+const OAM_SIZE: u32 = 0xA0;
+
+/// An OAM DMA transfer which runs alongside the CPU
+#[derive(Copy, Clone, Debug)]
+struct Dma {
+    source: u16,
+    cycles: u32,
+}
+
+impl Bus {
+    /// Create a bus containing the cartridge ROM.
+    /// The mapper is picked from the ROM header.
+    pub fn new(rom_data: &[u8]) -> Result<Bus, CartridgeError> {
+        let mut bus = Bus {
+            boot_rom: None,
+            mapper: create_mapper(rom_data)?,
+            save_dirty: false,
+            video_ram: vec![0x00; 0x2000],
+            work_ram: vec![0x00; 0x2000],
+            oam: vec![0x00; 0xA0],
+            io: vec![0x00; 0x80],
+            high_ram: vec![0x00; 0x7F],
+            interrupt_enable: 0x00,
+            dma: None,
+            joypad: Joypad::new(),
+        };
+        bus.update_joypad();
+        Ok(bus)
+    }
+
+    /// Create a bus with the boot ROM mapped over the start of the cartridge ROM.
+    ///
+    /// DMG & MGB boot ROMs cover 0x0000-0x00FF. The larger CGB boot ROM also covers
+    /// 0x0200-0x08FF, leaving the cartridge header visible in between.
+    pub fn new_with_boot_rom(boot_rom: &[u8], rom_data: &[u8]) -> Result<Bus, CartridgeError> {
+        let mut bus = Bus::new(rom_data)?;
+        bus.boot_rom = Some(boot_rom.to_vec());
+        Ok(bus)
+    }
+
+    pub fn get_save_data(&self) -> Vec<u8> {
+        self.mapper.save_data()
+    }
+
+    pub fn load_save_data(&mut self, data: &[u8]) {
+        self.mapper.load_save_data(data);
+    }
+
+    /// Whether the cartridge RAM has been written to since the last call
+    pub fn take_save_dirty(&mut self) -> bool {
+        std::mem::replace(&mut self.save_dirty, false)
+    }
+
+    /// Whether the cartridge's rumble motor is on
+    pub fn is_rumbling(&self) -> bool {
+        self.mapper.is_rumbling()
+    }
+
+    /// Run the cartridge hardware for a number of cycles
+    pub fn tick_cartridge(&mut self, cycles: u32) {
+        self.mapper.tick(cycles);
+    }
+
+    /// Start copying 0xXX00-0xXX9F into OAM. Starting again during a transfer restarts it.
+    pub fn start_dma(&mut self, source_high: u8) {
+        // The DMG can't DMA from 0xE000-0xFFFF & reads work RAM through the echo instead
+        let source_high = match source_high {
+            0xE0..=0xFF => source_high - 0x20,
+            _ => source_high,
+        };
+
+        self.dma = Some(Dma {
+            source: (source_high as u16) << 8,
+            cycles: 0,
+        });
+    }
+
+    /// Copy the bytes of the current OAM DMA due in the cycles
+    pub fn tick_dma(&mut self, cycles: u32) {
+        let Some(dma) = self.dma else {
+            return;
+        };
+
+        let copied = dma.cycles / DMA_CYCLES_PER_BYTE;
+        let new_cycles = dma.cycles + cycles;
+        let to_copy = (new_cycles / DMA_CYCLES_PER_BYTE).min(OAM_SIZE);
+
+        for offset in copied..to_copy {
+            let value = self.read_raw(dma.source + offset as u16);
+            self.oam[offset as usize] = value;
+        }
+
+        self.dma = if to_copy < OAM_SIZE {
+            Some(Dma {
+                cycles: new_cycles,
+                ..dma
+            })
+        } else {
+            None
+        };
+    }
+
+    /// Press or release a button. Pressing a button in a selected row requests the Joypad interrupt.
+    pub fn set_button(&mut self, button: JoypadButton, pressed: bool) {
+        self.joypad.set_pressed(button, pressed);
+        self.update_joypad();
+    }
+
+    /// Refresh the low nibble of P1. Any bit going from 1 to 0 requests the Joypad interrupt.
+    fn update_joypad(&mut self) {
+        let previous = self.read_raw(Labels::JOYPAD);
+        let value = self.joypad.read(previous);
+        self.write_raw(Labels::JOYPAD, value);
+
+        if previous & !value & 0b0000_1111 != 0 {
+            request_interrupt(self, Interrupt::Joypad);
+        }
+    }
+
+    /// Restore the cartridge ROM in place of the boot ROM
+    pub fn disable_boot_rom(&mut self) {
+        self.boot_rom = None;
+    }
+
+    /// Read memory without any of the CPU access rules
+    pub fn read_raw(&self, address: u16) -> u8 {
+        match self.region(address) {
+            Region::BootRom(offset) => self.boot_rom.as_ref().map_or(0xFF, |rom| rom[offset]),
+            Region::Rom => self.mapper.read_rom(address),
+            Region::VideoRam(offset) => self.video_ram[offset],
+            Region::CartRam => self.mapper.read_ram(address),
+            Region::WorkRam(offset) => self.work_ram[offset],
+            Region::Oam(offset) => self.oam[offset],
+            Region::Unusable => 0x00,
+            Region::Io(offset) => self.io[offset],
+            Region::HighRam(offset) => self.high_ram[offset],
+            Region::InterruptEnable => self.interrupt_enable,
+        }
+    }
+
+    /// Write memory without any of the CPU access rules. ROM can be changed this way.
+    pub fn write_raw(&mut self, address: u16, value: u8) {
+        match self.region(address) {
+            Region::BootRom(offset) => {
+                if let Some(rom) = self.boot_rom.as_mut() {
+                    rom[offset] = value;
+                }
+            }
+            Region::Rom => self.mapper.poke_rom(address, value),
+            Region::VideoRam(offset) => self.video_ram[offset] = value,
+            Region::CartRam => {
+                self.mapper.write_ram(address, value);
+                self.save_dirty = true;
+            }
+            Region::WorkRam(offset) => self.work_ram[offset] = value,
+            Region::Oam(offset) => self.oam[offset] = value,
+            Region::Unusable => {}
+            Region::Io(offset) => self.io[offset] = value,
+            Region::HighRam(offset) => self.high_ram[offset] = value,
+            Region::InterruptEnable => self.interrupt_enable = value,
+        }
+    }
+
+    /// Returns a block of VRAM for the PPU to read tiles from
+    pub fn read_video_ram_slice(&self, address: u16, size: u16) -> &[u8] {
+        let start = (address - Labels::CHARACTER_RAM_START) as usize;
+        &self.video_ram[start..(start + size as usize)]
+    }
+
+    /// Whether the CPU is locked out of a region.
+    ///
+    /// The PPU locks VRAM while drawing & OAM while scanning OAM or drawing.
+    /// The STAT mode is 0 while the screen is off so nothing is locked then.
+    fn is_locked(&self, region: Region) -> bool {
+        if self.dma.is_some() {
+            return !matches!(region, Region::HighRam(_));
+        }
+
+        let mode = self.read_raw(Labels::LCD_STATUS) & 0b0000_0011;
+        match region {
+            Region::VideoRam(_) => mode == 3,
+            Region::Oam(_) => mode == 2 || mode == 3,
+            _ => false,
+        }
+    }
+
+    fn boot_rom_len(&self) -> usize {
+        self.boot_rom.as_ref().map_or(0, |rom| rom.len())
+    }
+
+    fn region(&self, address: u16) -> Region {
+        match address {
+            0x0000..=0x00FF if self.boot_rom.is_some() => Region::BootRom(address as usize),
+            0x0200..=0x08FF if self.boot_rom_len() > address as usize => {
+                Region::BootRom(address as usize)
+            }
+            0x0000..=0x7FFF => Region::Rom,
+            0x8000..=0x9FFF => Region::VideoRam((address - 0x8000) as usize),
+            0xA000..=0xBFFF => Region::CartRam,
+            0xC000..=0xDFFF => Region::WorkRam((address - 0xC000) as usize),
+            // Echo RAM mirrors 0xC000-0xDDFF
+            0xE000..=0xFDFF => Region::WorkRam((address - 0xE000) as usize),
+            0xFE00..=0xFE9F => Region::Oam((address - 0xFE00) as usize),
+            0xFEA0..=0xFEFF => Region::Unusable,
+            0xFF00..=0xFF7F => Region::Io((address - 0xFF00) as usize),
+            0xFF80..=0xFFFE => Region::HighRam((address - 0xFF80) as usize),
+            0xFFFF => Region::InterruptEnable,
+        }
+    }
+
+    fn write_io(&mut self, address: u16, value: u8) -> bool {
+        let previous = self.read_raw(address);
+        let sound_on = self.read_raw(Labels::SOUND_ON) & 0b1000_0000 != 0;
+
+        let new_value = match address {
+            // Only the row select bits of P1 can be written
+            Labels::JOYPAD => (value & 0b0011_0000) | (previous & 0b1100_1111),
+            // Writing any value resets DIV
+            Labels::DIVIDER => 0x00,
+            // LY is read only
+            Labels::LCDC_Y => previous,
+            // The mode & coincidence bits of STAT are read only
+            Labels::LCD_STATUS => (value & 0b1111_1000) | (previous & 0b0000_0111),
+            // The sound registers can't be written while the APU is off.
+            // On the DMG the length counters in NRx1 can still be loaded.
+            0xFF11 | 0xFF16 | 0xFF1B | 0xFF20 if !sound_on => previous,
+            0xFF10..=0xFF14 | 0xFF16..=0xFF1E | 0xFF20..=0xFF25 if !sound_on => return false,
+            // Only the power bit of NR52 can be written
+            Labels::SOUND_ON => (value & 0b1000_0000) | (previous & 0b0000_1111),
+            // Any write restores the cartridge in place of the boot ROM
+            Labels::BOOTLOADER_DISABLE => {
+                self.disable_boot_rom();
+                value
+            }
+            _ => value,
+        };
+
+        self.write_raw(address, new_value);
+
+        // Selecting a row updates the buttons which can be read
+        if address == Labels::JOYPAD {
+            self.update_joypad();
+        }
+        true
+    }
+}
+
+impl Memory for Bus {
+    fn read(&self, address: u16) -> u8 {
+        let region = self.region(address);
+        if self.is_locked(region) {
+            return 0xFF;
+        }
+
+        let value = self.read_raw(address);
+
+        match region {
+            Region::Io(_) => value | io_read_mask(address),
+            _ => value,
+        }
+    }
+
+    fn write(&mut self, address: u16, value: u8) -> bool {
+        let region = self.region(address);
+        if self.is_locked(region) {
+            // DMA can be written during a transfer to restart it
+            if self.dma.is_some() && address == Labels::DMA {
+                self.write_raw(address, value);
+                return true;
+            }
+            return false;
+        }
+
+        match region {
+            // ROM is read only. Writing to it controls the bank controller
+            Region::Rom => self.mapper.write_control(address, value),
+            Region::BootRom(_) | Region::Unusable => return false,
+            Region::Io(_) => return self.write_io(address, value),
+            _ => self.write_raw(address, value),
+        }
+        true
+    }
+}
+
+/// Returns the bits of an IO register which always read as 1.
+/// These are either unused or write only.
+fn io_read_mask(address: u16) -> u8 {
+    match address {
+        Labels::JOYPAD => 0b1100_0000,
+        0xFF01 => 0b0000_0000,
+        0xFF02 => 0b0111_1110,
+        0xFF04..=0xFF06 => 0b0000_0000,
+        0xFF07 => 0b1111_1000,
+        Labels::INTERRUPT_TRIGGER => 0b1110_0000,
+        // Sound registers
+        0xFF10 => 0b1000_0000,
+        0xFF11 | 0xFF16 => 0b0011_1111,
+        0xFF12 | 0xFF17 | 0xFF21 | 0xFF22 | 0xFF24 | 0xFF25 => 0b0000_0000,
+        0xFF13 | 0xFF18 | 0xFF1B | 0xFF1D | 0xFF20 => 0b1111_1111,
+        0xFF14 | 0xFF19 | 0xFF1E | 0xFF23 => 0b1011_1111,
+        0xFF1A => 0b0111_1111,
+        0xFF1C => 0b1001_1111,
+        Labels::SOUND_ON => 0b0111_0000,
+        // Wave pattern RAM
+        0xFF30..=0xFF3F => 0b0000_0000,
+        // LCD registers
+        Labels::LCD_STATUS => 0b1000_0000,
+        0xFF40 | 0xFF42..=0xFF4B => 0b0000_0000,
+        // Everything else isn't connected
+        _ => 0b1111_1111,
+    }
+}
+
+#[test]
+fn echo_ram_mirrors_work_ram() {
+    let mut bus = Bus::new(&[]).unwrap();
+
+    bus.write(0xC010, 0x12);
+    assert_eq!(bus.read(0xE010), 0x12);
+
+    bus.write(0xFDFF, 0x34);
+    assert_eq!(bus.read(0xDDFF), 0x34);
+}
+
+#[test]
+fn rom_is_read_only() {
+    let mut bus = Bus::new(&[0x01, 0x02]).unwrap();
+
+    bus.write(0x0001, 0xFF);
+    assert_eq!(bus.read(0x0001), 0x02);
+
+    bus.write_raw(0x0001, 0xFF);
+    assert_eq!(bus.read(0x0001), 0xFF);
+}
+
+#[test]
+fn unusable_area_ignores_writes() {
+    let mut bus = Bus::new(&[]).unwrap();
+
+    bus.write(0xFEA0, 0x12);
+    assert_eq!(bus.read(0xFEA0), 0x00);
+}
+
+#[test]
+fn io_registers_have_read_masks() {
+    let mut bus = Bus::new(&[]).unwrap();
+
+    // Unused registers always read 0xFF
+    bus.write(0xFF03, 0x00);
+    assert_eq!(bus.read(0xFF03), 0xFF);
+
+    bus.write(Labels::TIMER_CONTROL, 0x05);
+    assert_eq!(bus.read(Labels::TIMER_CONTROL), 0xFD);
+    assert_eq!(bus.read_raw(Labels::TIMER_CONTROL), 0x05);
+}
+
+#[test]
+fn io_registers_have_write_masks() {
+    let mut bus = Bus::new(&[]).unwrap();
+
+    bus.write_raw(Labels::LCDC_Y, 0x20);
+    bus.write(Labels::LCDC_Y, 0x10);
+    assert_eq!(bus.read(Labels::LCDC_Y), 0x20);
+
+    bus.write_raw(Labels::LCD_STATUS, 0b0000_0110);
+    bus.write(Labels::LCD_STATUS, 0b0100_0001);
+    assert_eq!(bus.read(Labels::LCD_STATUS), 0b1100_0110);
+
+    bus.write_raw(Labels::DIVIDER, 0x20);
+    bus.write(Labels::DIVIDER, 0x10);
+    assert_eq!(bus.read(Labels::DIVIDER), 0x00);
+}
+
+#[test]
+fn boot_rom_is_mapped_until_disabled() {
+    let boot_rom = vec![0xAA; 0x100];
+    let mut bus = Bus::new_with_boot_rom(&boot_rom, &[0x55; 0x101]).unwrap();
+
+    assert_eq!(bus.read(0x0000), 0xAA);
+    assert_eq!(bus.read(0x0100), 0x55);
+
+    bus.write(Labels::BOOTLOADER_DISABLE, 0x01);
+    assert_eq!(bus.read(0x0000), 0x55);
+}
+
+#[test]
+fn cgb_boot_rom_leaves_the_header_visible() {
+    let boot_rom = vec![0xAA; 0x900];
+    let bus = Bus::new_with_boot_rom(&boot_rom, &[0x55; 0x101]).unwrap();
+
+    assert_eq!(bus.read(0x00FF), 0xAA);
+    assert_eq!(bus.read(0x0100), 0x55);
+    assert_eq!(bus.read(0x0200), 0xAA);
+    assert_eq!(bus.read(0x08FF), 0xAA);
+    assert_eq!(bus.read(0x0900), 0xFF);
+}
+
+#[test]
+fn sound_registers_ignore_writes_while_the_apu_is_off() {
+    let mut bus = Bus::new(&[]).unwrap();
+
+    assert!(!bus.write(Labels::SOUND_VOLUME, 0x77));
+    assert_eq!(bus.read_raw(Labels::SOUND_VOLUME), 0x00);
+
+    // The length in NR11 can still be loaded but the register doesn't change
+    assert!(bus.write(0xFF11, 0b1000_0011));
+    assert_eq!(bus.read_raw(0xFF11), 0x00);
+
+    // Wave RAM can still be written
+    bus.write(0xFF30, 0x12);
+    assert_eq!(bus.read_raw(0xFF30), 0x12);
+
+    bus.write(Labels::SOUND_ON, 0x80);
+    assert!(bus.write(Labels::SOUND_VOLUME, 0x77));
+    assert_eq!(bus.read_raw(Labels::SOUND_VOLUME), 0x77);
+}
@@ -0,0 +1,292 @@
+use std::fmt;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+const HEADER_END: usize = 0x150;
+const TITLE_START: usize = 0x134;
+const TITLE_END: usize = 0x144;
+const MANUFACTURER_CODE_START: usize = 0x13F;
+const CGB_FLAG: usize = 0x143;
+const NEW_LICENSEE_CODE: usize = 0x144;
+const SGB_FLAG: usize = 0x146;
+pub const CARTRIDGE_TYPE: usize = 0x147;
+const ROM_SIZE: usize = 0x148;
+pub const RAM_SIZE: usize = 0x149;
+const OLD_LICENSEE_CODE: usize = 0x14B;
+const VERSION: usize = 0x14C;
+pub const HEADER_CHECKSUM: usize = 0x14D;
+const GLOBAL_CHECKSUM: usize = 0x14E;
+
+/// The old licensee code which means the new licensee code should be used instead
+const USE_NEW_LICENSEE: u8 = 0x33;
+
+/// Reasons a ROM can't be loaded as a cartridge
+#[derive(Debug)]
+pub enum CartridgeError {
+    Io(std::io::Error),
+    /// The ROM is too small to contain a header
+    TooSmall(usize),
+    UnknownRomSize(u8),
+    UnknownRamSize(u8),
+    UnsupportedCartridgeType(u8),
+    /// The ROM is smaller than the size in the header
+    Truncated {
+        expected: usize,
+        actual: usize,
+    },
+    HeaderChecksumMismatch {
+        expected: u8,
+        actual: u8,
+    },
+}
+
+impl fmt::Display for CartridgeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CartridgeError::Io(err) => write!(f, "{}", err),
+            CartridgeError::TooSmall(size) => write!(
+                f,
+                "ROM is {:#X} bytes which is too small to contain a header",
+                size
+            ),
+            CartridgeError::UnknownRomSize(code) => write!(f, "Unknown ROM size {:#X}", code),
+            CartridgeError::UnknownRamSize(code) => write!(f, "Unknown RAM size {:#X}", code),
+            CartridgeError::UnsupportedCartridgeType(code) => {
+                write!(f, "Cartridge type {:#X} isn't supported", code)
+            }
+            CartridgeError::Truncated { expected, actual } => write!(
+                f,
+                "ROM is {:#X} bytes but the header says it should be {:#X}",
+                actual, expected
+            ),
+            CartridgeError::HeaderChecksumMismatch { expected, actual } => write!(
+                f,
+                "Header checksum is {:#X} but the header adds up to {:#X}",
+                expected, actual
+            ),
+        }
+    }
+}
+
+impl std::error::Error for CartridgeError {}
+
+impl From<std::io::Error> for CartridgeError {
+    fn from(err: std::io::Error) -> Self {
+        CartridgeError::Io(err)
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CgbSupport {
+    /// A DMG game
+    None,
+    /// Works on both DMG & CGB
+    Enhanced,
+    /// Only works on CGB
+    Only,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Licensee {
+    Old(u8),
+    New(String),
+}
+
+/// A game ROM with its parsed header (0x0100-0x014F)
+pub struct Cartridge {
+    data: Vec<u8>,
+    title: String,
+    cgb_support: CgbSupport,
+    sgb_support: bool,
+    cartridge_type: u8,
+    rom_size: usize,
+    ram_size: usize,
+    licensee: Licensee,
+    version: u8,
+    header_checksum: u8,
+    global_checksum: u16,
+}
+
+impl Cartridge {
+    /// Read a ROM file & parse its header
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Cartridge, CartridgeError> {
+        let mut rom_file = File::open(path)?;
+        let mut rom_data = Vec::new();
+        rom_file.read_to_end(&mut rom_data)?;
+
+        Cartridge::from_bytes(rom_data)
+    }
+
+    /// Parse the header of a ROM.
+    ///
+    /// Fails if the header is missing, contains unknown sizes or says the ROM is
+    /// larger than it is. Neither checksum is checked here so the caller can decide
+    /// whether a mismatch is fatal. See `check_header_checksum`.
+    pub fn from_bytes(data: Vec<u8>) -> Result<Cartridge, CartridgeError> {
+        if data.len() < HEADER_END {
+            return Err(CartridgeError::TooSmall(data.len()));
+        }
+
+        let rom_size = rom_size_from_code(data[ROM_SIZE])?;
+        let ram_size = ram_size_from_code(data[RAM_SIZE])?;
+        let header_checksum = data[HEADER_CHECKSUM];
+
+        if data.len() < rom_size {
+            return Err(CartridgeError::Truncated {
+                expected: rom_size,
+                actual: data.len(),
+            });
+        }
+
+        let cgb_support = match data[CGB_FLAG] {
+            0xC0 => CgbSupport::Only,
+            0x80 => CgbSupport::Enhanced,
+            _ => CgbSupport::None,
+        };
+
+        // CGB games use the end of the title for the manufacturer code & CGB flag
+        let title_end = match cgb_support {
+            CgbSupport::None => TITLE_END,
+            _ => MANUFACTURER_CODE_START,
+        };
+        let title = data[TITLE_START..title_end]
+            .iter()
+            .take_while(|&&c| c != 0x00)
+            .map(|&c| c as char)
+            .collect();
+
+        let licensee = match data[OLD_LICENSEE_CODE] {
+            USE_NEW_LICENSEE => Licensee::New(
+                data[NEW_LICENSEE_CODE..NEW_LICENSEE_CODE + 2]
+                    .iter()
+                    .map(|&c| c as char)
+                    .collect(),
+            ),
+            code => Licensee::Old(code),
+        };
+
+        Ok(Cartridge {
+            title,
+            cgb_support,
+            sgb_support: data[SGB_FLAG] == 0x03,
+            cartridge_type: data[CARTRIDGE_TYPE],
+            rom_size,
+            ram_size,
+            licensee,
+            version: data[VERSION],
+            header_checksum,
+            global_checksum: u16::from_be_bytes([data[GLOBAL_CHECKSUM], data[GLOBAL_CHECKSUM + 1]]),
+            data,
+        })
+    }
+
+    pub fn get_rom_data(&self) -> &[u8] {
+        &self.data
+    }
+
+    pub fn get_title(&self) -> &str {
+        &self.title
+    }
+
+    #[allow(dead_code)]
+    pub fn get_cgb_support(&self) -> CgbSupport {
+        self.cgb_support
+    }
+
+    #[allow(dead_code)]
+    pub fn supports_sgb(&self) -> bool {
+        self.sgb_support
+    }
+
+    #[allow(dead_code)]
+    pub fn get_cartridge_type(&self) -> u8 {
+        self.cartridge_type
+    }
+
+    /// Whether the cartridge RAM is kept by a battery & should be saved
+    pub fn has_battery(&self) -> bool {
+        matches!(
+            self.cartridge_type,
+            0x03 | 0x06 | 0x09 | 0x0D | 0x0F | 0x10 | 0x13 | 0x1B | 0x1E | 0x22 | 0xFF
+        )
+    }
+
+    /// The ROM size in bytes
+    #[allow(dead_code)]
+    pub fn get_rom_size(&self) -> usize {
+        self.rom_size
+    }
+
+    /// The external RAM size in bytes
+    #[allow(dead_code)]
+    pub fn get_ram_size(&self) -> usize {
+        self.ram_size
+    }
+
+    #[allow(dead_code)]
+    pub fn get_licensee(&self) -> &Licensee {
+        &self.licensee
+    }
+
+    #[allow(dead_code)]
+    pub fn get_version(&self) -> u8 {
+        self.version
+    }
+
+    #[allow(dead_code)]
+    pub fn get_header_checksum(&self) -> u8 {
+        self.header_checksum
+    }
+
+    /// The boot ROM locks up if the header doesn't add up to the header checksum
+    pub fn check_header_checksum(&self) -> Result<(), CartridgeError> {
+        let computed_checksum = compute_header_checksum(&self.data);
+        if self.header_checksum != computed_checksum {
+            return Err(CartridgeError::HeaderChecksumMismatch {
+                expected: self.header_checksum,
+                actual: computed_checksum,
+            });
+        }
+        Ok(())
+    }
+
+    pub fn get_global_checksum(&self) -> u16 {
+        self.global_checksum
+    }
+
+    /// Sum every byte of the ROM except the global checksum itself
+    pub fn compute_global_checksum(&self) -> u16 {
+        self.data
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != GLOBAL_CHECKSUM && *i != GLOBAL_CHECKSUM + 1)
+            .fold(0u16, |sum, (_, &byte)| sum.wrapping_add(byte as u16))
+    }
+}
+
+/// The checksum the boot ROM verifies over 0x0134-0x014C
+fn compute_header_checksum(data: &[u8]) -> u8 {
+    data[TITLE_START..HEADER_CHECKSUM]
+        .iter()
+        .fold(0u8, |sum, &byte| sum.wrapping_sub(byte).wrapping_sub(1))
+}
+
+fn rom_size_from_code(code: u8) -> Result<usize, CartridgeError> {
+    match code {
+        0x00..=0x08 => Ok(0x8000 << code),
+        _ => Err(CartridgeError::UnknownRomSize(code)),
+    }
+}
+
+pub fn ram_size_from_code(code: u8) -> Result<usize, CartridgeError> {
+    match code {
+        0x00 => Ok(0),
+        0x01 => Ok(0x800),
+        0x02 => Ok(0x2000),
+        0x03 => Ok(0x8000),
+        0x04 => Ok(0x20000),
+        0x05 => Ok(0x10000),
+        _ => Err(CartridgeError::UnknownRamSize(code)),
+    }
+}
@@ -1,5 +1,15 @@
 use super::register::{RegisterLabel16, RegisterLabel8, RegisterPair};
 
+/// The low power states the CPU can be put into by HALT & STOP
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PowerState {
+    Running,
+    /// Stop fetching instructions until an interrupt is pending
+    Halted,
+    /// Stop everything until a joypad button is pressed
+    Stopped,
+}
+
 #[allow(clippy::upper_case_acronyms)]
 #[derive(Clone)]
 pub struct CPU {
@@ -10,6 +20,11 @@ pub struct CPU {
     /// The flag used to determine whether interrupts should be enabled.
     /// This is needed because the `ei` instruction only enables interrupts after the instruction following `ei`
     ei_triggered: bool,
+
+    power_state: PowerState,
+    /// Set when HALT is run with IME=0 & an interrupt already pending.
+    /// The next opcode fetch won't increment the program counter.
+    halt_bug: bool,
 }
 
 impl CPU {
@@ -39,6 +54,8 @@ impl CPU {
             registers,
             ime_flag: false,
             ei_triggered: false,
+            power_state: PowerState::Running,
+            halt_bug: false,
         }
     }
 
@@ -99,6 +116,38 @@ impl CPU {
         self.ei_triggered = false;
         self.ime_flag = false;
     }
+
+    pub fn get_power_state(&self) -> PowerState {
+        self.power_state
+    }
+
+    /// Stop fetching instructions until an interrupt is pending
+    pub fn halt(&mut self) {
+        self.power_state = PowerState::Halted;
+    }
+
+    /// Stop the CPU until a joypad button is pressed
+    pub fn stop(&mut self) {
+        self.power_state = PowerState::Stopped;
+    }
+
+    /// Leave either of the low power states
+    pub fn wake(&mut self) {
+        self.power_state = PowerState::Running;
+    }
+
+    /// Leave HALT straight away without incrementing the program counter on the next fetch
+    pub fn trigger_halt_bug(&mut self) {
+        self.power_state = PowerState::Running;
+        self.halt_bug = true;
+    }
+
+    /// Returns whether the next fetch is affected by the HALT bug & clears it
+    pub fn take_halt_bug(&mut self) -> bool {
+        let halt_bug = self.halt_bug;
+        self.halt_bug = false;
+        halt_bug
+    }
 }
 
 //-------------------------------------------------------
@@ -121,6 +170,26 @@ fn interrupt_flag_behavior() {
     assert_eq!(cpu.is_interrupt_enable_started(), false);
 }
 
+#[test]
+fn power_state_behavior() {
+    let mut cpu = CPU::new();
+    assert_eq!(cpu.get_power_state(), PowerState::Running);
+
+    cpu.halt();
+    assert_eq!(cpu.get_power_state(), PowerState::Halted);
+
+    cpu.trigger_halt_bug();
+    assert_eq!(cpu.get_power_state(), PowerState::Running);
+    assert!(cpu.take_halt_bug());
+    assert!(!cpu.take_halt_bug());
+
+    cpu.stop();
+    assert_eq!(cpu.get_power_state(), PowerState::Stopped);
+
+    cpu.wake();
+    assert_eq!(cpu.get_power_state(), PowerState::Running);
+}
+
 #[test]
 fn created_cpu_is_zero() {
     let cpu = CPU::new();
@@ -36,9 +36,9 @@ fn set_flag(register: u8, flag: Flags, on: bool) -> u8 {
         (Flags::Z, true) => register | 0b1000_0000,
         (Flags::N, false) => register & 0b1011_1111,
         (Flags::N, true) => register | 0b0100_0000,
-        (Flags::H, false) => register & 0b0101_1111,
+        (Flags::H, false) => register & 0b1101_1111,
         (Flags::H, true) => register | 0b0010_0000,
-        (Flags::C, false) => register & 0b0110_1111,
+        (Flags::C, false) => register & 0b1110_1111,
         (Flags::C, true) => register | 0b0001_0000,
     }
 }
@@ -70,4 +70,6 @@ fn set_flag_test() {
     assert_eq!(set_flag(0b1000_0000, Flags::Z, false), 0b0000_0000);
     assert_eq!(set_flag(0b1000_0000, Flags::Z, true), 0b1000_0000);
     assert_eq!(set_flag(0b0000_0000, Flags::Z, false), 0b0000_0000);
+    assert_eq!(set_flag(0b1010_0000, Flags::H, false), 0b1000_0000);
+    assert_eq!(set_flag(0b1001_0000, Flags::C, false), 0b1000_0000);
 }
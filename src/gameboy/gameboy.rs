@@ -1,12 +1,17 @@
-use super::audio::ALU;
-use super::cpu::CPU;
-use super::interrupt_routine::InterruptRoutine;
+use super::audio::{StereoFrame, ALU};
+use super::bus::{Bus, Memory};
+use super::cartridge::{CartridgeError, HEADER_CHECKSUM};
+use super::cpu::{PowerState, CPU};
+use super::interrupt_controller::{
+    clear_interrupt, highest_priority_interrupt, pending_interrupts,
+};
+use super::joypad::JoypadButton;
 use super::memory_adapter::MemoryAdapter;
 use super::memory_labels::Labels;
-use super::memory_view::MemoryView;
 use super::opcodes::Decoder;
-use super::ppu::PPU;
+use super::ppu::{Renderer, TileMap, PPU};
 use super::screen::ScreenColor;
+use super::timer::Timer;
 use super::{read_flag, write_flag, Flags, OpCode, RegisterLabel16, RegisterLabel8};
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
@@ -16,23 +21,63 @@ pub enum TickResult {
     Crash,
 }
 
+/// The IO registers after the DMG boot ROM has finished
+const POST_BOOT_IO: [(u16, u8); 29] = [
+    (Labels::JOYPAD, 0xCF),
+    (0xFF02, 0x7E),
+    (Labels::TIMER_CONTROL, 0xF8),
+    (Labels::INTERRUPT_TRIGGER, 0xE1),
+    // Sound. The APU is powered on first so the other registers can be written.
+    (Labels::SOUND_ON, 0xF1),
+    (0xFF10, 0x80),
+    (0xFF11, 0xBF),
+    (0xFF12, 0xF3),
+    (0xFF13, 0xFF),
+    (0xFF14, 0xBF),
+    (0xFF16, 0x3F),
+    (0xFF18, 0xFF),
+    (0xFF19, 0xBF),
+    (0xFF1A, 0x7F),
+    (0xFF1B, 0xFF),
+    (0xFF1C, 0x9F),
+    (0xFF1D, 0xFF),
+    (0xFF1E, 0xBF),
+    (0xFF20, 0xFF),
+    (0xFF23, 0xBF),
+    (0xFF24, 0x77),
+    (0xFF25, 0xF3),
+    // LCD
+    (Labels::LCD_CONTROLS, 0x91),
+    // Only the STAT interrupt enables. The PPU fills in the mode & LY=LYC bits.
+    (Labels::LCD_STATUS, 0x80),
+    (Labels::DMA, 0xFF),
+    (Labels::BG_PALETTE, 0xFC),
+    (Labels::OBJ_PALETTE_0, 0xFF),
+    (Labels::OBJ_PALETTE_1, 0xFF),
+    (Labels::BOOTLOADER_DISABLE, 0xFF),
+];
+
+/// The internal divider when the DMG boot ROM hands over to the game. DIV reads 0xAB.
+const POST_BOOT_DIVIDER: u16 = 0xABCC;
+
 pub struct Gameboy<'a> {
     cpu: CPU,
     ppu: PPU,
     alu: ALU<'a>,
-    memory: Vec<u8>,
-    rom_header_data: Vec<u8>,
+    timer: Timer,
+    bus: Bus,
 }
 
-const INTERRUPT_ROUTINES: [InterruptRoutine; 1] = [InterruptRoutine {
-    bit: 0,
-    routine_address: 0x40,
-}];
-
 impl<'a> Gameboy<'a> {
-    pub fn new_with_bootloader<F>(audio_callback: F, game_data: &[u8]) -> Gameboy<'a>
+    /// Construct a Gameboy which starts by running the DMG boot ROM.
+    ///
+    /// Fails if the cartridge type in the ROM header isn't supported.
+    pub fn new_with_bootloader<F>(
+        audio_callback: F,
+        game_data: &[u8],
+    ) -> Result<Gameboy<'a>, CartridgeError>
     where
-        F: FnMut(i16) + 'a,
+        F: FnMut(StereoFrame) + 'a,
     {
         let bootloader = vec![
             0x31, 0xFE, 0xFF, 0xAF, 0x21, 0xFF, 0x9F, 0x32, 0xCB, 0x7C, 0x20, 0xFB, 0x21, 0x26,
@@ -69,59 +114,103 @@ impl<'a> Gameboy<'a> {
             0xE0, 0x50,
         ];
 
-        if game_data.len() <= bootloader.len() {
-            panic!("Game code not larger than bootloader");
-        }
+        Gameboy::new_with_boot_rom(audio_callback, &bootloader, game_data)
+    }
 
-        let mut memory = vec![0x0; 0xFFFF + 1];
-        memory[..game_data.len()].clone_from_slice(game_data);
-        memory[..bootloader.len()].clone_from_slice(&bootloader[..]);
+    /// Construct a Gameboy which starts by running the provided boot ROM.
+    ///
+    /// Fails if the cartridge type in the ROM header isn't supported.
+    pub fn new_with_boot_rom<F>(
+        audio_callback: F,
+        boot_rom: &[u8],
+        game_data: &[u8],
+    ) -> Result<Gameboy<'a>, CartridgeError>
+    where
+        F: FnMut(StereoFrame) + 'a,
+    {
+        let bus = Bus::new_with_boot_rom(boot_rom, game_data)?;
 
-        let mut rom_header_data = vec![0x0u8; bootloader.len()];
-        rom_header_data[..0xFF].clone_from_slice(&game_data[..0xFF]);
+        Ok(Gameboy {
+            cpu: CPU::new(),
+            ppu: PPU::new(),
+            timer: Timer::new(),
+            alu: ALU::new(audio_callback),
+            bus,
+        })
+    }
 
-        Gameboy {
+    /// Construct a Gameboy in the state the DMG boot ROM leaves it in,
+    /// ready to start the game at 0x0100.
+    ///
+    /// Fails if the cartridge type in the ROM header isn't supported.
+    pub fn new_skip_boot<F>(
+        audio_callback: F,
+        game_data: &[u8],
+    ) -> Result<Gameboy<'a>, CartridgeError>
+    where
+        F: FnMut(StereoFrame) + 'a,
+    {
+        let mut gb = Gameboy {
             cpu: CPU::new(),
             ppu: PPU::new(),
+            timer: Timer::new(),
             alu: ALU::new(audio_callback),
-            memory,
-            rom_header_data,
+            bus: Bus::new(game_data)?,
+        };
+
+        // The boot ROM leaves H & C set unless the header checksum is 0
+        let header_checksum = game_data.get(HEADER_CHECKSUM).copied().unwrap_or(0x00);
+        let flags = if header_checksum == 0x00 { 0x80 } else { 0xB0 };
+
+        gb.set_register_16(RegisterLabel16::AF, 0x0100 | flags);
+        gb.set_register_16(RegisterLabel16::BC, 0x0013);
+        gb.set_register_16(RegisterLabel16::DE, 0x00D8);
+        gb.set_register_16(RegisterLabel16::HL, 0x014D);
+        gb.set_register_16(RegisterLabel16::StackPointer, 0xFFFE);
+        gb.set_register_16(RegisterLabel16::ProgramCounter, 0x0100);
+
+        for (address, value) in POST_BOOT_IO {
+            gb.set_memory_at(address, value);
         }
+        gb.timer.set_divider(POST_BOOT_DIVIDER, &mut gb.bus);
+        gb.ppu.update_status(&mut gb.bus);
+
+        Ok(gb)
     }
 
     /// Construct a new Gameboy.
     ///
-    /// The provided Vec to fill the first section of memory,
+    /// The provided Vec is used as the cartridge ROM,
     /// starting at 0x0000. All other parts of memory will be
     /// set to zero.
+    ///
+    /// Panics if the cartridge type in the ROM header isn't supported.
     #[allow(dead_code)]
     pub fn new(data: Vec<u8>) -> Gameboy<'a> {
-        let mut memory = vec![0; 0xFFFF + 1];
-        memory[..data.len()].clone_from_slice(&data[..]);
+        let bus = Bus::new(&data).unwrap();
 
         Gameboy {
             cpu: CPU::new(),
             ppu: PPU::new(),
+            timer: Timer::new(),
             alu: ALU::new(|_| {}),
-            memory,
-            rom_header_data: vec![],
+            bus,
         }
     }
 
     #[allow(dead_code)]
     pub fn new_with_audio<'b, F>(data: Vec<u8>, audio_callback: F) -> Gameboy<'b>
     where
-        F: FnMut(i16) + 'b,
+        F: FnMut(StereoFrame) + 'b,
     {
-        let mut memory = vec![0; 0xFFFF + 1];
-        memory[..data.len()].clone_from_slice(&data[..]);
+        let bus = Bus::new(&data).unwrap();
 
         Gameboy {
             cpu: CPU::new(),
             ppu: PPU::new(),
+            timer: Timer::new(),
             alu: ALU::new(audio_callback),
-            memory,
-            rom_header_data: vec![],
+            bus,
         }
     }
 
@@ -173,74 +262,87 @@ impl<'a> Gameboy<'a> {
     /// Run the next instruction and return the number of cycles used.
     #[allow(dead_code)]
     pub fn step_once(&mut self) -> Option<u32> {
-        // If interrupts are enabled check each interrupt flag
-        if self.cpu.is_interrupts_enabled() {
-            let interrupt_enabled_flags = self.memory[0xFFFF];
-            let interrupt_triggered_flags = self.memory[Labels::INTERRUPT_TRIGGER as usize];
-
-            for interrupt in INTERRUPT_ROUTINES.iter() {
-                // Check vblank interrupt
-                let interrupt_enabled =
-                    (interrupt_enabled_flags & (0b0000_0001 << interrupt.bit)) > 0;
-                let interrupt_triggered =
-                    (interrupt_triggered_flags & 0b0000_0001 << interrupt.bit) > 0;
-
-                if interrupt_enabled && interrupt_triggered {
-                    // if one is enabled call it's handler using 20 CPU cycles
-                    let cycles = self.call_routine(interrupt.routine_address);
-
-                    // Reset interrupt trigger
-                    self.memory[Labels::INTERRUPT_TRIGGER as usize] = self.memory
-                        [Labels::INTERRUPT_TRIGGER as usize]
-                        & !(0b0000_0001 << interrupt.bit);
-
-                    // disable interrupts in the process
-                    self.cpu.disable_interrupts();
-
-                    return Some(cycles);
+        match self.cpu.get_power_state() {
+            PowerState::Stopped => {
+                // Nothing runs until a joypad button is pressed
+                return Some(4);
+            }
+            PowerState::Halted => {
+                // Any pending interrupt wakes the CPU even if interrupts are disabled
+                if pending_interrupts(&self.bus) == 0 {
+                    self.tick_components(4);
+                    return Some(4);
                 }
+                self.cpu.wake();
             }
+            PowerState::Running => {}
         }
 
-        let opcode = self.get_opcode();
+        // If interrupts are enabled service the pending interrupt with the highest priority
+        if self.cpu.is_interrupts_enabled() && pending_interrupts(&self.bus) != 0 {
+            let cycles = self.service_interrupt();
+            self.tick_components(cycles);
+            return Some(cycles);
+        }
+
+        let opcode = if self.cpu.take_halt_bug() {
+            self.get_halt_bug_opcode()
+        } else {
+            self.get_opcode()
+        };
         match opcode {
             Ok(op) => {
                 let interrupts_enabled_before = self.cpu.is_interrupt_enable_started();
 
-                let mut enable_rom_header = false;
-                let mut perform_dma_copy = None;
+                let mut start_dma = None;
+                let mut reset_divider = false;
+                let mut counter_written = false;
+                let mut previous_timer_control = None;
+                let mut sound_writes = Vec::new();
+                let timer_control = self.bus.read_raw(Labels::TIMER_CONTROL);
 
                 let cycles;
                 {
                     // Set up the memory callbacks
-                    let mut mem_adapter = MemoryAdapter::new(&mut self.memory);
+                    let mut mem_adapter = MemoryAdapter::new(&mut self.bus);
                     let ppu_ref = &mut self.ppu;
                     mem_adapter.add_callback(Labels::BG_PALETTE, |new_palette| {
                         ppu_ref.reset_bg_palette(new_palette);
                     });
-                    mem_adapter.add_callback(Labels::BOOTLOADER_DISABLE, |_| {
-                        // Restore the Cart memory in place of the bootloader
-                        enable_rom_header = true;
-                    });
                     mem_adapter.add_callback(Labels::DMA, |source| {
-                        // Copy locations in memory
-                        perform_dma_copy = Some(source);
+                        start_dma = Some(source);
+                    });
+                    mem_adapter.add_callback(Labels::DIVIDER, |_| {
+                        reset_divider = true;
                     });
+                    mem_adapter.add_callback(Labels::TIMER_COUNTER, |_| {
+                        counter_written = true;
+                    });
+                    mem_adapter.add_callback(Labels::TIMER_CONTROL, |_| {
+                        previous_timer_control = Some(timer_control);
+                    });
+                    mem_adapter.add_range_callback(
+                        Labels::SOUND_START..=Labels::SOUND_ON,
+                        |address, value| sound_writes.push((address, value)),
+                    );
                     cycles = op.run(&mut self.cpu, mem_adapter)?;
                 }
 
-                if enable_rom_header {
-                    self.memory[..0xFF].copy_from_slice(&self.rom_header_data[..0xFF]);
+                if let Some(source) = start_dma {
+                    self.bus.start_dma(source);
                 }
 
-                if let Some(source) = perform_dma_copy {
-                    let start = ((source as u16) << 8) as usize;
-                    let end = (((source as u16) << 8) + 0x9F) as usize;
-                    let block: Vec<u8> = self.memory[start..end]
-                        .iter()
-                        .map(|val| val.clone())
-                        .collect();
-                    self.memory[0xFE00..0xFE9F].clone_from_slice(&block[..]);
+                if reset_divider {
+                    self.timer.reset_divider(&mut self.bus);
+                }
+                if counter_written {
+                    self.timer.counter_written();
+                }
+                if let Some(previous_control) = previous_timer_control {
+                    self.timer.control_changed(previous_control, &mut self.bus);
+                }
+                for (address, value) in sound_writes {
+                    self.alu.register_written(address, value, &mut self.bus);
                 }
 
                 // If interrupts are also enabled afterwards then enable interrupts
@@ -248,11 +350,16 @@ impl<'a> Gameboy<'a> {
                     self.cpu.enable_interrupts();
                 }
 
-                // Now run the PPU by the same amount of cycles
-                self.ppu.tick(cycles, &mut self.memory);
+                // HALT with interrupts disabled & an interrupt already pending
+                // doesn't halt. Instead the DMG reads the next byte twice.
+                if self.cpu.get_power_state() == PowerState::Halted
+                    && !self.cpu.is_interrupts_enabled()
+                    && pending_interrupts(&self.bus) != 0
+                {
+                    self.cpu.trigger_halt_bug();
+                }
 
-                // Run the ALU by the same amount of cycles
-                self.alu.tick(cycles, &mut self.memory);
+                self.tick_components(cycles);
 
                 Some(cycles)
             }
@@ -262,6 +369,53 @@ impl<'a> Gameboy<'a> {
         }
     }
 
+    /// Called when a joypad button is pressed. This wakes the CPU from STOP.
+    pub fn joypad_pressed(&mut self) {
+        if self.cpu.get_power_state() == PowerState::Stopped {
+            self.cpu.wake();
+        }
+    }
+
+    pub fn press_button(&mut self, button: JoypadButton) {
+        self.bus.set_button(button, true);
+        self.joypad_pressed();
+    }
+
+    pub fn release_button(&mut self, button: JoypadButton) {
+        self.bus.set_button(button, false);
+    }
+
+    #[allow(dead_code)]
+    pub fn get_power_state(&self) -> PowerState {
+        self.cpu.get_power_state()
+    }
+
+    /// The battery backed contents of the cartridge
+    pub fn get_save_data(&self) -> Vec<u8> {
+        self.bus.get_save_data()
+    }
+
+    pub fn load_save_data(&mut self, data: &[u8]) {
+        self.bus.load_save_data(data);
+    }
+
+    /// Whether the cartridge RAM has changed since the last time this was called
+    pub fn take_save_dirty(&mut self) -> bool {
+        self.bus.take_save_dirty()
+    }
+
+    /// Pick how the screen is drawn. This should be called straight after construction.
+    pub fn with_renderer(mut self, renderer: Renderer) -> Gameboy<'a> {
+        self.ppu.set_renderer(renderer);
+        self
+    }
+
+    /// Whether the cartridge's rumble motor is on
+    #[allow(dead_code)]
+    pub fn is_rumbling(&self) -> bool {
+        self.bus.is_rumbling()
+    }
+
     #[allow(dead_code)]
     pub fn get_register_16(&self, register: RegisterLabel16) -> u16 {
         self.cpu.read_16_bits(register)
@@ -294,37 +448,53 @@ impl<'a> Gameboy<'a> {
 
     #[allow(dead_code)]
     pub fn set_memory_at(&mut self, address: u16, value: u8) {
-        self.memory[address as usize] = value;
+        let previous_value = self.bus.read_raw(address);
+        self.bus.write_raw(address, value);
 
         if address == Labels::BG_PALETTE {
             self.ppu.reset_bg_palette(value);
         }
         if address == Labels::BOOTLOADER_DISABLE {
             // Restore the Cart memory in place of the bootloader
-            self.memory[..0xFF].copy_from_slice(&self.rom_header_data[..0xFF]);
+            self.bus.disable_boot_rom();
+        }
+        if address == Labels::DIVIDER {
+            self.timer.reset_divider(&mut self.bus);
+        }
+        if address == Labels::TIMER_COUNTER {
+            self.timer.counter_written();
+        }
+        if address == Labels::TIMER_CONTROL {
+            self.timer.control_changed(previous_value, &mut self.bus);
+        }
+        if (Labels::SOUND_START..=Labels::SOUND_ON).contains(&address) {
+            self.alu.register_written(address, value, &mut self.bus);
         }
 
         // This hack resets any values in the case of the display being switched off
-        self.ppu.tick(0, &mut self.memory);
+        self.ppu.tick(0, &mut self.bus);
     }
 
     #[allow(dead_code)]
     pub fn get_memory_at(&self, address: u16) -> u8 {
-        MemoryView::new(&self.memory).get_memory_at(address)
+        self.bus.read_raw(address)
     }
 
     #[allow(dead_code)]
-    pub fn get_memory_slice_at(&self, address: u16, size: u16) -> &[u8] {
-        MemoryView::new(&self.memory).get_memory_slice_at(address, size)
+    pub fn get_memory_slice_at(&self, address: u16, size: u16) -> Vec<u8> {
+        // Stop at the end of memory
+        (address as u32..(address as u32 + size as u32).min(0xFFFF + 1))
+            .map(|address| self.bus.read_raw(address as u16))
+            .collect()
     }
 
     /// Return the VRAM information
     ///
     /// Return the data stored in the VRAM as pixel data. This is useful for
-    /// viewing all the tiles currently stored
+    /// viewing all the tiles currently stored in either tile map
     #[allow(dead_code)]
-    pub fn get_vram_data(&self) -> Vec<ScreenColor> {
-        self.ppu.get_vram_data(&self.memory)
+    pub fn get_vram_data(&self, map: TileMap) -> Vec<ScreenColor> {
+        self.ppu.get_vram_data(map, &self.bus)
     }
 
     #[allow(dead_code)]
@@ -391,25 +561,77 @@ impl<'a> Gameboy<'a> {
     }
 
     fn get_opcode_at(&self, address: u16) -> Result<OpCode, String> {
-        Decoder::decode_instruction(address, &self.memory)
+        // Instructions are at most 3 bytes long
+        let code: Vec<u8> = (0..3)
+            .map(|offset| self.bus.read(address.wrapping_add(offset)))
+            .collect();
+
+        Decoder::decode_bytes(address, &code)
+    }
+
+    /// Decode the next opcode as the DMG does after the HALT bug.
+    ///
+    /// The program counter isn't incremented after reading the opcode
+    /// so the same byte is also read as the first byte after the opcode.
+    fn get_halt_bug_opcode(&mut self) -> Result<OpCode, String> {
+        let counter = self.cpu.read_16_bits(RegisterLabel16::ProgramCounter);
+
+        let code = [
+            self.bus.read(counter),
+            self.bus.read(counter),
+            self.bus.read(counter.wrapping_add(1)),
+        ];
+
+        // Run the opcode from the previous address so the program counter ends up 1 byte short
+        self.cpu
+            .write_16_bits(RegisterLabel16::ProgramCounter, counter.wrapping_sub(1));
+
+        Decoder::decode_bytes(counter, &code)
+    }
+
+    /// Run the PPU, ALU & timer by the same amount of cycles as the CPU
+    fn tick_components(&mut self, cycles: u32) {
+        self.ppu.tick(cycles, &mut self.bus);
+        self.timer.tick(cycles, &mut self.bus);
+        let frame_clocks = self.timer.take_frame_sequencer_clocks();
+        self.alu.tick(cycles, frame_clocks, &mut self.bus);
+        self.bus.tick_cartridge(cycles);
+        self.bus.tick_dma(cycles);
     }
 
-    fn call_routine(&mut self, address: u16) -> u32 {
+    /// Push the program counter & jump to the routine of the highest priority interrupt.
+    ///
+    /// The interrupt is picked after the high byte of the program counter is pushed.
+    /// If that push overwrote IE & cancelled every interrupt the CPU jumps to 0x0000.
+    fn service_interrupt(&mut self) -> u32 {
+        // disable interrupts in the process
+        self.cpu.disable_interrupts();
+
         let return_address = self.cpu.read_16_bits(RegisterLabel16::ProgramCounter);
         let stack_address = self.cpu.read_16_bits(RegisterLabel16::StackPointer);
 
         let return_addr_bytes = return_address.to_le_bytes();
 
-        self.memory[(stack_address.checked_sub(1).unwrap_or(0)) as usize] = return_addr_bytes[1];
-        self.memory[(stack_address.checked_sub(2).unwrap_or(0)) as usize] = return_addr_bytes[0];
+        self.bus
+            .write(stack_address.wrapping_sub(1), return_addr_bytes[1]);
 
-        self.cpu.write_16_bits(
-            RegisterLabel16::StackPointer,
-            stack_address.saturating_sub(2),
-        );
+        let routine_address = match highest_priority_interrupt(&self.bus) {
+            Some(interrupt) => {
+                clear_interrupt(&mut self.bus, interrupt);
+                interrupt.routine_address()
+            }
+            None => 0x0000,
+        };
+
+        self.bus
+            .write(stack_address.wrapping_sub(2), return_addr_bytes[0]);
+
+        self.cpu
+            .write_16_bits(RegisterLabel16::StackPointer, stack_address.wrapping_sub(2));
         self.cpu
-            .write_16_bits(RegisterLabel16::ProgramCounter, address);
+            .write_16_bits(RegisterLabel16::ProgramCounter, routine_address);
 
+        // Servicing an interrupt takes 5 machine cycles
         20
     }
 }
@@ -0,0 +1,105 @@
+use super::bus::Bus;
+use super::memory_labels::Labels;
+
+/// The sources which can request an interrupt
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Interrupt {
+    VBlank,
+    LcdStat,
+    Timer,
+    Serial,
+    Joypad,
+}
+
+/// All interrupts ordered by priority. When more than one interrupt is
+/// pending the one with the lowest bit is serviced first.
+pub const INTERRUPT_PRIORITY: [Interrupt; 5] = [
+    Interrupt::VBlank,
+    Interrupt::LcdStat,
+    Interrupt::Timer,
+    Interrupt::Serial,
+    Interrupt::Joypad,
+];
+
+/// Only the bottom 5 bits of IE & IF are used
+const INTERRUPT_MASK: u8 = 0b0001_1111;
+
+impl Interrupt {
+    /// The bit used for the interrupt in both IE & IF
+    pub fn bit(self) -> u8 {
+        match self {
+            Interrupt::VBlank => 0,
+            Interrupt::LcdStat => 1,
+            Interrupt::Timer => 2,
+            Interrupt::Serial => 3,
+            Interrupt::Joypad => 4,
+        }
+    }
+
+    /// The address the CPU jumps to when servicing the interrupt
+    pub fn routine_address(self) -> u16 {
+        0x40 + 0x08 * self.bit() as u16
+    }
+}
+
+/// Raise a request for an interrupt by setting its bit in IF
+pub fn request_interrupt(memory: &mut Bus, interrupt: Interrupt) {
+    let flags = memory.read_raw(Labels::INTERRUPT_TRIGGER);
+    memory.write_raw(
+        Labels::INTERRUPT_TRIGGER,
+        flags | (0b0000_0001 << interrupt.bit()),
+    );
+}
+
+/// Acknowledge an interrupt by resetting its bit in IF
+pub fn clear_interrupt(memory: &mut Bus, interrupt: Interrupt) {
+    let flags = memory.read_raw(Labels::INTERRUPT_TRIGGER);
+    memory.write_raw(
+        Labels::INTERRUPT_TRIGGER,
+        flags & !(0b0000_0001 << interrupt.bit()),
+    );
+}
+
+/// Returns the interrupts which are both enabled & requested
+pub fn pending_interrupts(memory: &Bus) -> u8 {
+    memory.read_raw(Labels::INTERRUPT_ENABLE)
+        & memory.read_raw(Labels::INTERRUPT_TRIGGER)
+        & INTERRUPT_MASK
+}
+
+/// Returns the pending interrupt with the highest priority
+pub fn highest_priority_interrupt(memory: &Bus) -> Option<Interrupt> {
+    let pending = pending_interrupts(memory);
+
+    INTERRUPT_PRIORITY
+        .iter()
+        .find(|interrupt| (pending & (0b0000_0001 << interrupt.bit())) != 0)
+        .copied()
+}
+
+#[test]
+fn interrupts_have_the_correct_routine_addresses() {
+    assert_eq!(Interrupt::VBlank.routine_address(), 0x40);
+    assert_eq!(Interrupt::LcdStat.routine_address(), 0x48);
+    assert_eq!(Interrupt::Timer.routine_address(), 0x50);
+    assert_eq!(Interrupt::Serial.routine_address(), 0x58);
+    assert_eq!(Interrupt::Joypad.routine_address(), 0x60);
+}
+
+#[test]
+fn highest_priority_interrupt_is_the_lowest_enabled_bit() {
+    let mut memory = Bus::new(&[]).unwrap();
+    assert_eq!(highest_priority_interrupt(&memory), None);
+
+    request_interrupt(&mut memory, Interrupt::Joypad);
+    request_interrupt(&mut memory, Interrupt::Timer);
+
+    // Nothing is pending until the interrupts are enabled
+    assert_eq!(highest_priority_interrupt(&memory), None);
+
+    memory.write_raw(Labels::INTERRUPT_ENABLE, 0b0001_0100);
+    assert_eq!(highest_priority_interrupt(&memory), Some(Interrupt::Timer));
+
+    clear_interrupt(&mut memory, Interrupt::Timer);
+    assert_eq!(highest_priority_interrupt(&memory), Some(Interrupt::Joypad));
+}
@@ -1,4 +0,0 @@
-pub struct InterruptRoutine {
-    pub bit: u8,
-    pub routine_address: u16,
-}
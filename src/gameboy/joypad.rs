@@ -0,0 +1,79 @@
+/// The eight buttons on the Gameboy
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum JoypadButton {
+    Right,
+    Left,
+    Up,
+    Down,
+    A,
+    B,
+    Select,
+    Start,
+}
+
+impl JoypadButton {
+    /// Whether the button is read through P14 rather than P15
+    fn is_direction(self) -> bool {
+        matches!(
+            self,
+            JoypadButton::Right | JoypadButton::Left | JoypadButton::Up | JoypadButton::Down
+        )
+    }
+
+    /// The bit in the low nibble of P1 the button pulls low
+    fn bit(self) -> u8 {
+        match self {
+            JoypadButton::Right | JoypadButton::A => 0,
+            JoypadButton::Left | JoypadButton::B => 1,
+            JoypadButton::Up | JoypadButton::Select => 2,
+            JoypadButton::Down | JoypadButton::Start => 3,
+        }
+    }
+}
+
+/// The button matrix behind P1 (0xFF00).
+///
+/// Writing 0 to P14 (bit 4) selects the directions & 0 to P15 (bit 5) selects the
+/// action buttons. The low nibble reads 0 for each pressed button in the selected rows.
+pub struct Joypad {
+    directions: u8,
+    actions: u8,
+}
+
+impl Joypad {
+    pub fn new() -> Joypad {
+        Joypad {
+            directions: 0x00,
+            actions: 0x00,
+        }
+    }
+
+    pub fn set_pressed(&mut self, button: JoypadButton, pressed: bool) {
+        let row = if button.is_direction() {
+            &mut self.directions
+        } else {
+            &mut self.actions
+        };
+
+        if pressed {
+            *row |= 1 << button.bit();
+        } else {
+            *row &= !(1 << button.bit());
+        }
+    }
+
+    /// The value of P1 with the select bits taken from `select`
+    pub fn read(&self, select: u8) -> u8 {
+        let select = select & 0b0011_0000;
+
+        let mut pressed = 0x00;
+        if select & 0b0001_0000 == 0 {
+            pressed |= self.directions;
+        }
+        if select & 0b0010_0000 == 0 {
+            pressed |= self.actions;
+        }
+
+        0b1100_0000 | select | (!pressed & 0b0000_1111)
+    }
+}
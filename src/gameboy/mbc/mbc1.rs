@@ -0,0 +1,132 @@
+use super::{pad_rom, rom_offset, Mapper, RAM_BANK_SIZE, ROM_BANK_SIZE};
+
+/// Offset of the Nintendo logo in the header
+const LOGO_START: usize = 0x104;
+const LOGO_END: usize = 0x134;
+
+/// The MBC1 bank controller.
+///
+/// Up to 2MiB of ROM & 32KiB of RAM. The 2 bit upper bank register is used for
+/// either the RAM bank or bits 5-6 of the ROM bank. In mode 1 it is also applied
+/// to 0x0000-0x3FFF & the RAM.
+pub struct Mbc1 {
+    rom: Vec<u8>,
+    ram: Vec<u8>,
+    ram_enabled: bool,
+    rom_bank: u8,
+    upper_bank: u8,
+    advanced_banking: bool,
+    multicart: bool,
+}
+
+impl Mbc1 {
+    pub fn new(rom_data: &[u8], ram_size: usize) -> Mbc1 {
+        let rom = pad_rom(rom_data);
+
+        let multicart = is_multicart(&rom);
+
+        Mbc1 {
+            rom,
+            ram: vec![0x00; ram_size],
+            ram_enabled: false,
+            rom_bank: 0x01,
+            upper_bank: 0x00,
+            advanced_banking: false,
+            multicart,
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn is_multicart(&self) -> bool {
+        self.multicart
+    }
+
+    fn rom_offset(&self, address: u16) -> usize {
+        // MBC1M carts don't connect bit 4 of the ROM bank register
+        let (upper_shift, lower_mask) = if self.multicart { (4, 0x0F) } else { (5, 0x1F) };
+
+        let bank = match address {
+            0x0000..=0x3FFF if self.advanced_banking => self.upper_bank << upper_shift,
+            0x0000..=0x3FFF => 0x00,
+            _ => (self.upper_bank << upper_shift) | (self.rom_bank & lower_mask),
+        } as usize;
+
+        rom_offset(&self.rom, bank, address)
+    }
+
+    fn ram_offset(&self, address: u16) -> Option<usize> {
+        if !self.ram_enabled || self.ram.is_empty() {
+            return None;
+        }
+
+        let bank = if self.advanced_banking {
+            self.upper_bank as usize
+        } else {
+            0
+        };
+
+        let offset = bank * RAM_BANK_SIZE + (address - 0xA000) as usize;
+        Some(offset % self.ram.len())
+    }
+}
+
+impl Mapper for Mbc1 {
+    fn read_rom(&self, address: u16) -> u8 {
+        self.rom[self.rom_offset(address)]
+    }
+
+    fn write_control(&mut self, address: u16, value: u8) {
+        match address {
+            0x0000..=0x1FFF => self.ram_enabled = value & 0x0F == 0x0A,
+            0x2000..=0x3FFF => {
+                // Bank 0 can't be selected here. Only the full 5 bits are checked
+                self.rom_bank = match value & 0x1F {
+                    0x00 => 0x01,
+                    bank => bank,
+                };
+            }
+            0x4000..=0x5FFF => self.upper_bank = value & 0x03,
+            _ => self.advanced_banking = value & 0x01 == 0x01,
+        }
+    }
+
+    fn poke_rom(&mut self, address: u16, value: u8) {
+        let offset = self.rom_offset(address);
+        self.rom[offset] = value;
+    }
+
+    fn read_ram(&self, address: u16) -> u8 {
+        match self.ram_offset(address) {
+            Some(offset) => self.ram[offset],
+            None => 0xFF,
+        }
+    }
+
+    fn write_ram(&mut self, address: u16, value: u8) {
+        if let Some(offset) = self.ram_offset(address) {
+            self.ram[offset] = value;
+        }
+    }
+
+    fn ram(&self) -> &[u8] {
+        &self.ram
+    }
+
+    fn ram_mut(&mut self) -> &mut [u8] {
+        &mut self.ram
+    }
+}
+
+/// MBC1M multicarts are 1MiB & contain a game every 256KiB.
+/// Each game has its own header so the logo appears again in bank 0x10.
+fn is_multicart(rom: &[u8]) -> bool {
+    const GAME_SIZE: usize = ROM_BANK_SIZE * 0x10;
+
+    if rom.len() != GAME_SIZE * 4 {
+        return false;
+    }
+
+    let logo = &rom[LOGO_START..LOGO_END];
+    logo.iter().any(|&byte| byte != 0x00)
+        && &rom[GAME_SIZE + LOGO_START..GAME_SIZE + LOGO_END] == logo
+}
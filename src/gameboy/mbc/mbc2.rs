@@ -0,0 +1,90 @@
+use super::{pad_rom, rom_offset, Mapper};
+
+const RAM_SIZE: usize = 0x200;
+
+/// The MBC2 bank controller.
+///
+/// Up to 256KiB of ROM & 512 4 bit values of built in RAM.
+/// Bit 8 of the address decides which register a write to 0x0000-0x3FFF sets.
+pub struct Mbc2 {
+    rom: Vec<u8>,
+    ram: Vec<u8>,
+    ram_enabled: bool,
+    rom_bank: u8,
+}
+
+impl Mbc2 {
+    pub fn new(rom_data: &[u8]) -> Mbc2 {
+        let rom = pad_rom(rom_data);
+
+        Mbc2 {
+            rom,
+            ram: vec![0x00; RAM_SIZE],
+            ram_enabled: false,
+            rom_bank: 0x01,
+        }
+    }
+
+    fn rom_offset(&self, address: u16) -> usize {
+        let bank = match address {
+            0x0000..=0x3FFF => 0x00,
+            _ => self.rom_bank as usize,
+        };
+
+        rom_offset(&self.rom, bank, address)
+    }
+
+    /// The 512 values are repeated across 0xA000-0xBFFF
+    fn ram_offset(address: u16) -> usize {
+        (address as usize - 0xA000) % RAM_SIZE
+    }
+}
+
+impl Mapper for Mbc2 {
+    fn read_rom(&self, address: u16) -> u8 {
+        self.rom[self.rom_offset(address)]
+    }
+
+    fn write_control(&mut self, address: u16, value: u8) {
+        match address {
+            0x0000..=0x3FFF if address & 0x0100 == 0 => {
+                self.ram_enabled = value & 0x0F == 0x0A;
+            }
+            0x0000..=0x3FFF => {
+                self.rom_bank = match value & 0x0F {
+                    0x00 => 0x01,
+                    bank => bank,
+                };
+            }
+            _ => {}
+        }
+    }
+
+    fn poke_rom(&mut self, address: u16, value: u8) {
+        let offset = self.rom_offset(address);
+        self.rom[offset] = value;
+    }
+
+    fn read_ram(&self, address: u16) -> u8 {
+        if !self.ram_enabled {
+            return 0xFF;
+        }
+
+        // Only the bottom 4 bits exist
+        self.ram[Mbc2::ram_offset(address)] | 0xF0
+    }
+
+    fn write_ram(&mut self, address: u16, value: u8) {
+        if self.ram_enabled {
+            self.ram[Mbc2::ram_offset(address)] = value & 0x0F;
+        }
+    }
+
+    fn ram(&self) -> &[u8] {
+        &self.ram
+    }
+
+    fn ram_mut(&mut self) -> &mut [u8] {
+        &mut self.ram
+    }
+}
@@ -0,0 +1,141 @@
+use super::rtc::{Clock, Rtc};
+use super::{load_ram, pad_rom, rom_offset, Mapper, RAM_BANK_SIZE};
+
+/// The MBC3 bank controller.
+///
+/// Up to 2MiB of ROM, 32KiB of RAM & an optional real time clock.
+/// The RTC registers are mapped to 0xA000-0xBFFF by selecting them in place of a RAM bank.
+pub struct Mbc3 {
+    rom: Vec<u8>,
+    ram: Vec<u8>,
+    rtc: Option<Rtc>,
+    ram_enabled: bool,
+    rom_bank: u8,
+    /// 0x00-0x03 select a RAM bank & 0x08-0x0C select an RTC register
+    ram_bank: u8,
+}
+
+impl Mbc3 {
+    /// The clock is only used if the cartridge has an RTC
+    pub fn new(rom_data: &[u8], ram_size: usize, has_rtc: bool, clock: Box<dyn Clock>) -> Mbc3 {
+        let rom = pad_rom(rom_data);
+
+        Mbc3 {
+            rom,
+            ram: vec![0x00; ram_size],
+            rtc: if has_rtc { Some(Rtc::new(clock)) } else { None },
+            ram_enabled: false,
+            rom_bank: 0x01,
+            ram_bank: 0x00,
+        }
+    }
+
+    fn rom_offset(&self, address: u16) -> usize {
+        let bank = match address {
+            0x0000..=0x3FFF => 0x00,
+            _ => self.rom_bank as usize,
+        };
+
+        rom_offset(&self.rom, bank, address)
+    }
+
+    fn ram_offset(&self, address: u16) -> Option<usize> {
+        if self.ram.is_empty() || self.ram_bank > 0x03 {
+            return None;
+        }
+
+        let offset = self.ram_bank as usize * RAM_BANK_SIZE + (address - 0xA000) as usize;
+        Some(offset % self.ram.len())
+    }
+}
+
+impl Mapper for Mbc3 {
+    fn tick(&mut self, cycles: u32) {
+        if let Some(rtc) = self.rtc.as_mut() {
+            rtc.tick(cycles);
+        }
+    }
+
+    fn read_rom(&self, address: u16) -> u8 {
+        self.rom[self.rom_offset(address)]
+    }
+
+    fn write_control(&mut self, address: u16, value: u8) {
+        match address {
+            0x0000..=0x1FFF => self.ram_enabled = value & 0x0F == 0x0A,
+            0x2000..=0x3FFF => {
+                self.rom_bank = match value & 0x7F {
+                    0x00 => 0x01,
+                    bank => bank,
+                };
+            }
+            0x4000..=0x5FFF => self.ram_bank = value & 0x0F,
+            _ => {
+                if let Some(rtc) = self.rtc.as_mut() {
+                    rtc.write_latch(value);
+                }
+            }
+        }
+    }
+
+    fn poke_rom(&mut self, address: u16, value: u8) {
+        let offset = self.rom_offset(address);
+        self.rom[offset] = value;
+    }
+
+    fn read_ram(&self, address: u16) -> u8 {
+        if !self.ram_enabled {
+            return 0xFF;
+        }
+
+        match (self.ram_bank, self.rtc.as_ref()) {
+            (0x08..=0x0C, Some(rtc)) => rtc.read(self.ram_bank),
+            _ => match self.ram_offset(address) {
+                Some(offset) => self.ram[offset],
+                None => 0xFF,
+            },
+        }
+    }
+
+    fn write_ram(&mut self, address: u16, value: u8) {
+        if !self.ram_enabled {
+            return;
+        }
+
+        match (self.ram_bank, self.rtc.as_mut()) {
+            (0x08..=0x0C, Some(rtc)) => rtc.write(self.ram_bank, value),
+            _ => {
+                if let Some(offset) = self.ram_offset(address) {
+                    self.ram[offset] = value;
+                }
+            }
+        }
+    }
+
+    fn ram(&self) -> &[u8] {
+        &self.ram
+    }
+
+    fn ram_mut(&mut self) -> &mut [u8] {
+        &mut self.ram
+    }
+
+    /// The contents of the save file: the RAM followed by the RTC
+    fn save_data(&self) -> Vec<u8> {
+        let mut data = self.ram.clone();
+        if let Some(rtc) = self.rtc.as_ref() {
+            data.extend_from_slice(&rtc.save());
+        }
+        data
+    }
+
+    /// Restore the RAM & RTC from a save file.
+    /// Saves without an RTC trailer only restore the RAM.
+    fn load_save_data(&mut self, data: &[u8]) {
+        let ram_size = load_ram(&mut self.ram, data);
+
+        if let Some(rtc) = self.rtc.as_mut() {
+            rtc.load(&data[ram_size..]);
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use super::{pad_rom, rom_offset, Mapper, RAM_BANK_SIZE};
+
+const RUMBLE_BIT: u8 = 0b0000_1000;
+
+/// The MBC5 bank controller.
+///
+/// Up to 8MiB of ROM with a 9 bit bank number & up to 16 banks of RAM.
+/// Bank 0 can be mapped to 0x4000-0x7FFF. On rumble carts bit 3 of the
+/// RAM bank register drives the motor instead.
+pub struct Mbc5 {
+    rom: Vec<u8>,
+    ram: Vec<u8>,
+    ram_enabled: bool,
+    rom_bank: u16,
+    ram_bank: u8,
+    has_rumble: bool,
+    rumbling: bool,
+}
+
+impl Mbc5 {
+    pub fn new(rom_data: &[u8], ram_size: usize, has_rumble: bool) -> Mbc5 {
+        let rom = pad_rom(rom_data);
+
+        Mbc5 {
+            rom,
+            ram: vec![0x00; ram_size],
+            ram_enabled: false,
+            rom_bank: 0x01,
+            ram_bank: 0x00,
+            has_rumble,
+            rumbling: false,
+        }
+    }
+
+    fn rom_offset(&self, address: u16) -> usize {
+        let bank = match address {
+            0x0000..=0x3FFF => 0x00,
+            _ => self.rom_bank as usize,
+        };
+
+        rom_offset(&self.rom, bank, address)
+    }
+
+    fn ram_offset(&self, address: u16) -> Option<usize> {
+        if !self.ram_enabled || self.ram.is_empty() {
+            return None;
+        }
+
+        let offset = self.ram_bank as usize * RAM_BANK_SIZE + (address - 0xA000) as usize;
+        Some(offset % self.ram.len())
+    }
+}
+
+impl Mapper for Mbc5 {
+    fn read_rom(&self, address: u16) -> u8 {
+        self.rom[self.rom_offset(address)]
+    }
+
+    fn write_control(&mut self, address: u16, value: u8) {
+        match address {
+            0x0000..=0x1FFF => self.ram_enabled = value == 0x0A,
+            0x2000..=0x2FFF => self.rom_bank = (self.rom_bank & 0x100) | value as u16,
+            0x3000..=0x3FFF => {
+                self.rom_bank = (self.rom_bank & 0xFF) | (((value & 0x01) as u16) << 8);
+            }
+            0x4000..=0x5FFF if self.has_rumble => {
+                self.rumbling = value & RUMBLE_BIT != 0;
+                self.ram_bank = value & 0x07;
+            }
+            0x4000..=0x5FFF => self.ram_bank = value & 0x0F,
+            _ => {}
+        }
+    }
+
+    fn poke_rom(&mut self, address: u16, value: u8) {
+        let offset = self.rom_offset(address);
+        self.rom[offset] = value;
+    }
+
+    fn read_ram(&self, address: u16) -> u8 {
+        match self.ram_offset(address) {
+            Some(offset) => self.ram[offset],
+            None => 0xFF,
+        }
+    }
+
+    fn write_ram(&mut self, address: u16, value: u8) {
+        if let Some(offset) = self.ram_offset(address) {
+            self.ram[offset] = value;
+        }
+    }
+
+    fn ram(&self) -> &[u8] {
+        &self.ram
+    }
+
+    fn ram_mut(&mut self) -> &mut [u8] {
+        &mut self.ram
+    }
+
+    fn is_rumbling(&self) -> bool {
+        self.rumbling
+    }
+}
@@ -0,0 +1,114 @@
+mod mbc1;
+mod mbc2;
+mod mbc3;
+mod mbc5;
+mod rom_only;
+pub mod rtc;
+
+use self::rtc::SystemClock;
+use super::cartridge::{ram_size_from_code, CartridgeError, CARTRIDGE_TYPE, RAM_SIZE};
+
+pub use self::mbc1::Mbc1;
+pub use self::mbc2::Mbc2;
+pub use self::mbc3::Mbc3;
+pub use self::mbc5::Mbc5;
+pub use self::rom_only::RomOnly;
+
+/// The size of each switchable ROM bank
+const ROM_BANK_SIZE: usize = 0x4000;
+/// The size of each switchable RAM bank
+const RAM_BANK_SIZE: usize = 0x2000;
+
+/// The hardware inside the cartridge.
+///
+/// This owns the cartridge ROM & RAM and decides which banks are visible at
+/// 0x0000-0x7FFF & 0xA000-0xBFFF.
+pub trait Mapper {
+    fn read_rom(&self, address: u16) -> u8;
+
+    /// Writes to ROM from the CPU set the bank controller registers
+    fn write_control(&mut self, address: u16, value: u8);
+
+    /// Change the ROM in the bank currently mapped at the address
+    fn poke_rom(&mut self, address: u16, value: u8);
+
+    fn read_ram(&self, address: u16) -> u8;
+
+    fn write_ram(&mut self, address: u16, value: u8);
+
+    /// All of the cartridge RAM, across every bank
+    fn ram(&self) -> &[u8];
+
+    fn ram_mut(&mut self) -> &mut [u8];
+
+    /// Advance anything in the cartridge which runs off the clock
+    fn tick(&mut self, _cycles: u32) {}
+
+    /// The contents of the battery backed save file
+    fn save_data(&self) -> Vec<u8> {
+        self.ram().to_vec()
+    }
+
+    /// Restore the cartridge RAM from a save file
+    fn load_save_data(&mut self, data: &[u8]) {
+        load_ram(self.ram_mut(), data);
+    }
+
+    /// Whether the rumble motor is on
+    fn is_rumbling(&self) -> bool {
+        false
+    }
+}
+
+/// Pick the mapper from the cartridge type in the ROM header
+pub fn create_mapper(rom_data: &[u8]) -> Result<Box<dyn Mapper>, CartridgeError> {
+    let header_byte = |address: usize| rom_data.get(address).copied().unwrap_or(0x00);
+    let ram_size = ram_size_from_code(header_byte(RAM_SIZE))?;
+
+    let mapper: Box<dyn Mapper> = match header_byte(CARTRIDGE_TYPE) {
+        0x00 | 0x08 | 0x09 => Box::new(RomOnly::new(rom_data, ram_size)),
+        0x01..=0x03 => Box::new(Mbc1::new(rom_data, ram_size)),
+        0x05 | 0x06 => Box::new(Mbc2::new(rom_data)),
+        code @ 0x0F..=0x13 => {
+            let has_rtc = code <= 0x10;
+            Box::new(Mbc3::new(
+                rom_data,
+                ram_size,
+                has_rtc,
+                Box::new(SystemClock),
+            ))
+        }
+        code @ 0x19..=0x1E => {
+            let has_rumble = code >= 0x1C;
+            Box::new(Mbc5::new(rom_data, ram_size, has_rumble))
+        }
+        code => return Err(CartridgeError::UnsupportedCartridgeType(code)),
+    };
+
+    Ok(mapper)
+}
+
+/// Pad the ROM to a power of 2 number of banks so bank numbers can be masked.
+/// The padding reads as open bus.
+fn pad_rom(rom_data: &[u8]) -> Vec<u8> {
+    let mut rom = rom_data.to_vec();
+    let rom_size = rom.len().max(ROM_BANK_SIZE * 2).next_power_of_two();
+    rom.resize(rom_size, 0xFF);
+    rom
+}
+
+/// The offset into the ROM of an address in a bank.
+/// Bank numbers past the end of the ROM wrap around.
+fn rom_offset(rom: &[u8], bank: usize, address: u16) -> usize {
+    let bank_count = rom.len() / ROM_BANK_SIZE;
+    let bank = bank & (bank_count - 1);
+
+    bank * ROM_BANK_SIZE + (address as usize & (ROM_BANK_SIZE - 1))
+}
+
+/// Copy as much of a save file as fits into the RAM. Returns the number of bytes used.
+fn load_ram(ram: &mut [u8], data: &[u8]) -> usize {
+    let size = ram.len().min(data.len());
+    ram[..size].copy_from_slice(&data[..size]);
+    size
+}
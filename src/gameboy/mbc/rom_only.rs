@@ -0,0 +1,63 @@
+use super::{pad_rom, Mapper};
+
+/// A cartridge without a bank controller. The whole ROM is always mapped
+/// & any RAM is always enabled.
+pub struct RomOnly {
+    rom: Vec<u8>,
+    ram: Vec<u8>,
+}
+
+impl RomOnly {
+    /// The ROM is padded to 32KiB
+    pub fn new(rom_data: &[u8], ram_size: usize) -> RomOnly {
+        let rom = pad_rom(rom_data);
+
+        RomOnly {
+            rom,
+            ram: vec![0x00; ram_size],
+        }
+    }
+
+    fn ram_offset(&self, address: u16) -> Option<usize> {
+        let offset = (address - 0xA000) as usize;
+        if offset < self.ram.len() {
+            Some(offset)
+        } else {
+            None
+        }
+    }
+}
+
+impl Mapper for RomOnly {
+    fn read_rom(&self, address: u16) -> u8 {
+        self.rom[address as usize]
+    }
+
+    /// There are no registers so writes to ROM are ignored
+    fn write_control(&mut self, _address: u16, _value: u8) {}
+
+    fn poke_rom(&mut self, address: u16, value: u8) {
+        self.rom[address as usize] = value;
+    }
+
+    fn read_ram(&self, address: u16) -> u8 {
+        match self.ram_offset(address) {
+            Some(offset) => self.ram[offset],
+            None => 0xFF,
+        }
+    }
+
+    fn write_ram(&mut self, address: u16, value: u8) {
+        if let Some(offset) = self.ram_offset(address) {
+            self.ram[offset] = value;
+        }
+    }
+
+    fn ram(&self) -> &[u8] {
+        &self.ram
+    }
+
+    fn ram_mut(&mut self) -> &mut [u8] {
+        &mut self.ram
+    }
+}
@@ -0,0 +1,238 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The RTC counts seconds from the cartridge's 32.768KHz crystal.
+/// This is kept in step with the emulated CPU clock.
+const CYCLES_PER_SECOND: u32 = 4_194_304;
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+const DAY_HIGH_BIT: u8 = 0b0000_0001;
+const HALT_BIT: u8 = 0b0100_0000;
+const DAY_CARRY_BIT: u8 = 0b1000_0000;
+
+/// Size of the RTC trailer BGB & VBA add to the end of save files
+pub const RTC_SAVE_SIZE: usize = 48;
+
+/// Where the RTC gets the current time from when catching up after a save is loaded
+pub trait Clock {
+    /// Seconds since the unix epoch
+    fn now(&self) -> u64;
+}
+
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |time| time.as_secs())
+    }
+}
+
+/// The 5 RTC registers, in the order they are selected (0x08-0x0C)
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+struct RtcRegisters {
+    seconds: u8,
+    minutes: u8,
+    hours: u8,
+    day_low: u8,
+    /// Bit 0 is bit 8 of the day, bit 6 halts the clock & bit 7 is the day carry
+    day_high: u8,
+}
+
+impl RtcRegisters {
+    fn read(&self, register: u8) -> u8 {
+        match register {
+            0x08 => self.seconds,
+            0x09 => self.minutes,
+            0x0A => self.hours,
+            0x0B => self.day_low,
+            _ => self.day_high,
+        }
+    }
+
+    fn to_bytes(self) -> [u8; 20] {
+        let mut bytes = [0x00; 20];
+        let values = [
+            self.seconds,
+            self.minutes,
+            self.hours,
+            self.day_low,
+            self.day_high,
+        ];
+        for (i, value) in values.iter().enumerate() {
+            bytes[i * 4..(i + 1) * 4].copy_from_slice(&(*value as u32).to_le_bytes());
+        }
+        bytes
+    }
+
+    fn from_bytes(bytes: &[u8]) -> RtcRegisters {
+        let value = |i: usize| bytes[i * 4];
+        RtcRegisters {
+            seconds: value(0) & 0x3F,
+            minutes: value(1) & 0x3F,
+            hours: value(2) & 0x1F,
+            day_low: value(3),
+            day_high: value(4) & (DAY_HIGH_BIT | HALT_BIT | DAY_CARRY_BIT),
+        }
+    }
+}
+
+/// The MBC3 real time clock.
+///
+/// The game reads a latched copy of the registers so they can't change while being read.
+pub struct Rtc {
+    registers: RtcRegisters,
+    latched: RtcRegisters,
+    cycles: u32,
+    /// The last value written to the latch register. Writing 0 then 1 latches the clock.
+    latch_write: u8,
+    clock: Box<dyn Clock>,
+}
+
+impl Rtc {
+    pub fn new(clock: Box<dyn Clock>) -> Rtc {
+        Rtc {
+            registers: RtcRegisters::default(),
+            latched: RtcRegisters::default(),
+            cycles: 0,
+            latch_write: 0xFF,
+            clock,
+        }
+    }
+
+    pub fn tick(&mut self, cycles: u32) {
+        if self.is_halted() {
+            return;
+        }
+
+        self.cycles += cycles;
+        while self.cycles >= CYCLES_PER_SECOND {
+            self.cycles -= CYCLES_PER_SECOND;
+            self.tick_second();
+        }
+    }
+
+    pub fn write_latch(&mut self, value: u8) {
+        if self.latch_write == 0x00 && value == 0x01 {
+            self.latched = self.registers;
+        }
+        self.latch_write = value;
+    }
+
+    pub fn read(&self, register: u8) -> u8 {
+        self.latched.read(register)
+    }
+
+    pub fn write(&mut self, register: u8, value: u8) {
+        match register {
+            0x08 => {
+                // Writing the seconds resets the part of the second which has passed
+                self.registers.seconds = value & 0x3F;
+                self.cycles = 0;
+            }
+            0x09 => self.registers.minutes = value & 0x3F,
+            0x0A => self.registers.hours = value & 0x1F,
+            0x0B => self.registers.day_low = value,
+            _ => self.registers.day_high = value & (DAY_HIGH_BIT | HALT_BIT | DAY_CARRY_BIT),
+        }
+
+        // Writes show up in the latched registers straight away
+        self.latched = self.registers;
+    }
+
+    /// The BGB/VBA trailer: the registers & latched registers as 32 bit values
+    /// followed by a 64 bit unix timestamp of when it was saved
+    pub fn save(&self) -> [u8; RTC_SAVE_SIZE] {
+        let mut data = [0x00; RTC_SAVE_SIZE];
+        data[0..20].copy_from_slice(&self.registers.to_bytes());
+        data[20..40].copy_from_slice(&self.latched.to_bytes());
+        data[40..48].copy_from_slice(&self.clock.now().to_le_bytes());
+        data
+    }
+
+    /// Restore the registers from a save & catch up with the time that has passed since.
+    /// Some emulators only write a 32 bit timestamp so 44 byte trailers are accepted too.
+    pub fn load(&mut self, data: &[u8]) {
+        if data.len() < 44 {
+            return;
+        }
+
+        self.registers = RtcRegisters::from_bytes(&data[0..20]);
+        self.latched = RtcRegisters::from_bytes(&data[20..40]);
+        self.cycles = 0;
+
+        let saved_at = match data.len() {
+            44..=47 => u32::from_le_bytes([data[40], data[41], data[42], data[43]]) as u64,
+            _ => u64::from_le_bytes(data[40..48].try_into().unwrap()),
+        };
+
+        let elapsed = self.clock.now().saturating_sub(saved_at);
+        self.advance(elapsed);
+    }
+
+    fn is_halted(&self) -> bool {
+        self.registers.day_high & HALT_BIT != 0
+    }
+
+    fn day(&self) -> u16 {
+        (((self.registers.day_high & DAY_HIGH_BIT) as u16) << 8) | self.registers.day_low as u16
+    }
+
+    fn set_day(&mut self, day: u64) {
+        if day > 0x1FF {
+            self.registers.day_high |= DAY_CARRY_BIT;
+        }
+        let day = (day & 0x1FF) as u16;
+
+        self.registers.day_low = day as u8;
+        self.registers.day_high = (self.registers.day_high & !DAY_HIGH_BIT) | (day >> 8) as u8;
+    }
+
+    /// Count up one second. Registers written with out of range values
+    /// keep counting until they overflow their bits without carrying.
+    fn tick_second(&mut self) {
+        let registers = &mut self.registers;
+
+        registers.seconds = (registers.seconds + 1) & 0x3F;
+        if registers.seconds != 60 {
+            return;
+        }
+        registers.seconds = 0;
+
+        registers.minutes = (registers.minutes + 1) & 0x3F;
+        if registers.minutes != 60 {
+            return;
+        }
+        registers.minutes = 0;
+
+        registers.hours = (registers.hours + 1) & 0x1F;
+        if registers.hours != 24 {
+            return;
+        }
+        registers.hours = 0;
+
+        let day = self.day() as u64 + 1;
+        self.set_day(day);
+    }
+
+    /// Jump forward a number of seconds
+    fn advance(&mut self, seconds: u64) {
+        if self.is_halted() {
+            return;
+        }
+
+        let registers = self.registers;
+        let total = registers.seconds as u64
+            + registers.minutes as u64 * 60
+            + registers.hours as u64 * 60 * 60
+            + seconds;
+
+        self.registers.seconds = (total % 60) as u8;
+        self.registers.minutes = (total / 60 % 60) as u8;
+        self.registers.hours = (total / (60 * 60) % 24) as u8;
+
+        let day = self.day() as u64 + total / SECONDS_PER_DAY;
+        self.set_day(day);
+    }
+}
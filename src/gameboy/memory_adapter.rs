@@ -1,17 +1,22 @@
-use super::memory_view::MemoryView;
+use std::ops::RangeInclusive;
+
+use super::bus::Memory;
 
 type StoredCallback<'a> = Box<dyn FnMut(u8) + 'a>;
+type StoredRangeCallback<'a> = Box<dyn FnMut(u16, u8) + 'a>;
 
 pub struct MemoryAdapter<'a> {
-    memory: &'a mut Vec<u8>,
+    memory: &'a mut dyn Memory,
     callback_conditions: Vec<(u16, StoredCallback<'a>)>,
+    range_callbacks: Vec<(RangeInclusive<u16>, StoredRangeCallback<'a>)>,
 }
 
 impl<'a> MemoryAdapter<'a> {
-    pub fn new(memory: &mut Vec<u8>) -> MemoryAdapter {
+    pub fn new(memory: &'a mut dyn Memory) -> MemoryAdapter<'a> {
         MemoryAdapter {
             memory,
             callback_conditions: vec![],
+            range_callbacks: vec![],
         }
     }
 
@@ -19,22 +24,36 @@ impl<'a> MemoryAdapter<'a> {
         self.callback_conditions.push((source, Box::new(callback)));
     }
 
+    /// Subscribe to writes anywhere in a range. The callback gets the address & value.
+    pub fn add_range_callback<CB: 'a + FnMut(u16, u8)>(
+        &mut self,
+        range: RangeInclusive<u16>,
+        callback: CB,
+    ) {
+        self.range_callbacks.push((range, Box::new(callback)));
+    }
+
+    /// Write through to memory. Callbacks only fire if the write wasn't ignored.
     pub fn set_memory_at(&mut self, address: u16, value: u8) {
-        self.memory[address as usize] = value;
+        if !self.memory.write(address, value) {
+            return;
+        }
 
         for (source, cb) in self.callback_conditions.iter_mut() {
             if address == *source {
                 (cb)(value);
             }
         }
-    }
 
-    pub fn get_memory_at(&self, address: u16) -> u8 {
-        MemoryView::new(self.memory).get_memory_at(address)
+        for (range, cb) in self.range_callbacks.iter_mut() {
+            if range.contains(&address) {
+                (cb)(address, value);
+            }
+        }
     }
 
-    pub fn get_memory(&mut self) -> &mut Vec<u8> {
-        self.memory
+    pub fn get_memory_at(&self, address: u16) -> u8 {
+        self.memory.read(address)
     }
 }
 
@@ -56,3 +75,22 @@ fn we_can_subscribe_to_memory_change_events() {
     assert_eq!(add_01_changed, true);
     assert_eq!(add_02_changed, false);
 }
+
+#[test]
+fn we_can_subscribe_to_a_range_of_memory() {
+    let mut memory = vec![0x00; 0x10];
+    let mut writes = Vec::new();
+
+    {
+        let mut adapter = MemoryAdapter::new(&mut memory);
+        adapter.add_range_callback(0x04..=0x07, |address, value| {
+            writes.push((address, value));
+        });
+        adapter.set_memory_at(0x03, 1);
+        adapter.set_memory_at(0x04, 2);
+        adapter.set_memory_at(0x07, 3);
+        adapter.set_memory_at(0x08, 4);
+    }
+
+    assert_eq!(writes, vec![(0x04, 2), (0x07, 3)]);
+}
@@ -4,12 +4,30 @@ pub mod Labels {
     // pub const CHARACTER_RAM_START_BLOCK_1: u16 = 0x8800; // not needed yet
     pub const CHARACTER_RAM_START_BLOCK_2: u16 = 0x9000;
     pub const BG_MAP_DATA_1_START: u16 = 0x9800;
+    pub const BG_MAP_DATA_2_START: u16 = 0x9C00;
+    pub const OAM_START: u16 = 0xFE00;
+    pub const JOYPAD: u16 = 0xFF00;
+    pub const DIVIDER: u16 = 0xFF04;
+    pub const TIMER_COUNTER: u16 = 0xFF05;
+    pub const TIMER_MODULO: u16 = 0xFF06;
+    pub const TIMER_CONTROL: u16 = 0xFF07;
     pub const INTERRUPT_TRIGGER: u16 = 0xFF0F;
+    pub const SOUND_START: u16 = 0xFF10;
+    pub const SOUND_VOLUME: u16 = 0xFF24;
+    pub const SOUND_PANNING: u16 = 0xFF25;
+    pub const SOUND_ON: u16 = 0xFF26;
     pub const BG_PALETTE: u16 = 0xFF47;
+    pub const OBJ_PALETTE_0: u16 = 0xFF48;
+    pub const OBJ_PALETTE_1: u16 = 0xFF49;
     pub const LCD_CONTROLS: u16 = 0xFF40;
+    pub const LCD_STATUS: u16 = 0xFF41;
     pub const SCROLL_Y: u16 = 0xFF42;
     pub const SCROLL_X: u16 = 0xFF43;
     pub const LCDC_Y: u16 = 0xFF44;
+    pub const LY_COMPARE: u16 = 0xFF45;
     pub const DMA: u16 = 0xFF46;
+    pub const WINDOW_Y: u16 = 0xFF4A;
+    pub const WINDOW_X: u16 = 0xFF4B;
     pub const BOOTLOADER_DISABLE: u16 = 0xFF50;
+    pub const INTERRUPT_ENABLE: u16 = 0xFFFF;
 }
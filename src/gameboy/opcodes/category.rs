@@ -29,10 +29,39 @@ pub enum Category {
     CPL,
     SCF,
     RST,
+    SBC,
+    DAA,
+    CCF,
+    RLCA,
+    RRCA,
+    RRA,
+    HALT,
+    STOP,
+    RLC,
+    RRC,
+    RR,
+    SLA,
+    SRA,
+    SRL,
+    RES,
+    SET,
 }
 
 fn is_cb_category(category: Category) -> bool {
-    matches!(category, Category::RL | Category::BIT)
+    matches!(
+        category,
+        Category::RLC
+            | Category::RRC
+            | Category::RL
+            | Category::RR
+            | Category::SLA
+            | Category::SRA
+            | Category::SWAP
+            | Category::SRL
+            | Category::BIT
+            | Category::RES
+            | Category::SET
+    )
 }
 
 pub fn category_from_str(cat: &str) -> Category {
@@ -65,6 +94,22 @@ pub fn category_from_str(cat: &str) -> Category {
         "SWAP" => Category::SWAP,
         "SCF" => Category::SCF,
         "RST" => Category::RST,
+        "SBC" => Category::SBC,
+        "DAA" => Category::DAA,
+        "CCF" => Category::CCF,
+        "RLCA" => Category::RLCA,
+        "RRCA" => Category::RRCA,
+        "RRA" => Category::RRA,
+        "HALT" => Category::HALT,
+        "STOP" => Category::STOP,
+        "RLC" => Category::RLC,
+        "RRC" => Category::RRC,
+        "RR" => Category::RR,
+        "SLA" => Category::SLA,
+        "SRA" => Category::SRA,
+        "SRL" => Category::SRL,
+        "RES" => Category::RES,
+        "SET" => Category::SET,
         _ => {
             panic!("Failed to create category {:?}", cat);
         }
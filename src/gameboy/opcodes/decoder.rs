@@ -12,12 +12,21 @@ pub enum DecodingError {
 pub struct Decoder {}
 
 impl Decoder {
+    /// Decode the instruction at the program counter within a flat block of code
+    #[cfg(test)]
     pub fn decode_instruction(program_counter: u16, program_code: &[u8]) -> Result<OpCode, String> {
-        let mut code = program_code[program_counter as usize];
+        Self::decode_bytes(program_counter, &program_code[program_counter as usize..])
+    }
+
+    /// Decode an instruction from the bytes starting at its opcode.
+    ///
+    /// The address is only used for error messages.
+    pub fn decode_bytes(address: u16, bytes: &[u8]) -> Result<OpCode, String> {
+        let mut code = bytes[0];
         let parts_or_error = match code {
             0xCB => {
                 // Get the next code
-                code = program_code[program_counter as usize + 1];
+                code = bytes[1];
                 parts_from_dictionary(code, &CB_DICTIONARY, DecodingError::CBFailure)
             }
             _ => {
@@ -27,18 +36,15 @@ impl Decoder {
         };
 
         let parts = parts_or_error.map_err(|err_type| match err_type {
-            DecodingError::DefaultCodeFailure => format!(
-                "Unknown command {:#X} at address: {:#X}",
-                code, program_counter
-            ),
+            DecodingError::DefaultCodeFailure => {
+                format!("Unknown command {:#X} at address: {:#X}", code, address)
+            }
             DecodingError::CBFailure => format!("Unknown command 0xCB {:#X}", code),
         })?;
 
         let category = category_from_str(parts[0]);
 
-        let args = parts[1..]
-            .iter()
-            .map(|arg| arg_from_str(arg, program_counter, program_code));
+        let args = parts[1..].iter().map(|arg| arg_from_str(arg, 0, bytes));
 
         let mut clean_args = [Argument::None; 2];
 
@@ -7,6 +7,7 @@ lazy_static! {
         (0x04, "INC B"),
         (0x05, "DEC B"),
         (0x06, "LD8 B d8"),
+        (0x07, "RLCA"),
         (0x08, "LD16 (a16) SP"),
         (0x09, "ADD16 HL BC"),
         (0x0A, "LD8 A (BC)"),
@@ -14,6 +15,8 @@ lazy_static! {
         (0x0C, "INC C"),
         (0x0D, "DEC C"),
         (0x0E, "LD8 C d8"),
+        (0x0F, "RRCA"),
+        (0x10, "STOP d8"),
         (0x11, "LD16 DE d16"),
         (0x12, "LD8 (DE) A"),
         (0x13, "INC DE"),
@@ -28,6 +31,7 @@ lazy_static! {
         (0x1C, "INC E"),
         (0x1D, "DEC E"),
         (0x1E, "LD8 E d8"),
+        (0x1F, "RRA"),
         (0x20, "JP NZ r8"),
         (0x21, "LD16 HL d16"),
         (0x22, "LD8 (HL+) A"),
@@ -35,6 +39,7 @@ lazy_static! {
         (0x24, "INC H"),
         (0x25, "DEC H"),
         (0x26, "LD8 H d8"),
+        (0x27, "DAA"),
         (0x28, "JP Z r8"),
         (0x29, "ADD16 HL HL"),
         (0x2A, "LD8 A (HL+)"),
@@ -43,6 +48,7 @@ lazy_static! {
         (0x2D, "DEC L"),
         (0x2E, "LD8 L d8"),
         (0x2F, "CPL"),
+        (0x30, "JP NC r8"),
         (0x31, "LD16 SP d16"),
         (0x32, "LD8 (HL-) A"),
         (0x33, "INC SP"),
@@ -57,6 +63,7 @@ lazy_static! {
         (0x3C, "INC A"),
         (0x3D, "DEC A"),
         (0x3E, "LD8 A d8"),
+        (0x3F, "CCF"),
         (0x40, "LD8 B B"),
         (0x40, "LD8 B B"),
         (0x41, "LD8 B C"),
@@ -112,6 +119,7 @@ lazy_static! {
         (0x73, "LD8 (HL) E"),
         (0x74, "LD8 (HL) H"),
         (0x75, "LD8 (HL) L"),
+        (0x76, "HALT"),
         (0x77, "LD8 (HL) A"),
         (0x78, "LD8 A B"),
         (0x79, "LD8 A C"),
@@ -135,6 +143,7 @@ lazy_static! {
         (0x8B, "ADC E"),
         (0x8C, "ADC H"),
         (0x8D, "ADC L"),
+        (0x8E, "ADC (HL)"),
         (0x8F, "ADC A"),
         (0x90, "SUB B"),
         (0x91, "SUB C"),
@@ -142,7 +151,16 @@ lazy_static! {
         (0x93, "SUB E"),
         (0x94, "SUB H"),
         (0x95, "SUB L"),
+        (0x96, "SUB (HL)"),
         (0x97, "SUB A"),
+        (0x98, "SBC B"),
+        (0x99, "SBC C"),
+        (0x9A, "SBC D"),
+        (0x9B, "SBC E"),
+        (0x9C, "SBC H"),
+        (0x9D, "SBC L"),
+        (0x9E, "SBC (HL)"),
+        (0x9F, "SBC A"),
         (0xA0, "AND B"),
         (0xA1, "AND C"),
         (0xA2, "AND D"),
@@ -157,6 +175,7 @@ lazy_static! {
         (0xAB, "XOR E"),
         (0xAC, "XOR H"),
         (0xAD, "XOR L"),
+        (0xAE, "XOR (HL)"),
         (0xAF, "XOR A"),
         (0xB0, "OR B"),
         (0xB1, "OR C"),
@@ -164,6 +183,7 @@ lazy_static! {
         (0xB3, "OR E"),
         (0xB4, "OR H"),
         (0xB5, "OR L"),
+        (0xB6, "OR (HL)"),
         (0xB7, "OR A"),
         (0xB8, "CP B"),
         (0xB9, "CP C"),
@@ -186,17 +206,20 @@ lazy_static! {
         (0xCA, "JP Z a16"),
         (0xCC, "CALL Z a16"),
         (0xCD, "CALL a16"),
+        (0xCE, "ADC d8"),
         (0xCF, "RST 08H"),
         (0xD0, "RET NC"),
         (0xD1, "POP DE"),
         (0xD2, "JP NC a16"),
         (0xD4, "CALL NC a16"),
         (0xD5, "PUSH DE"),
+        (0xD6, "SUB d8"),
         (0xD7, "RST 10H"),
         (0xD8, "RET CA"),
         (0xD9, "RETI"),
         (0xDA, "JP CA a16"),
         (0xDC, "CALL CA a16"),
+        (0xDE, "SBC d8"),
         (0xDF, "RST 18H"),
         (0xE0, "LD8 (a8) A"),
         (0xE1, "POP HL"),
@@ -204,12 +227,15 @@ lazy_static! {
         (0xE5, "PUSH HL"),
         (0xE6, "AND d8"),
         (0xE7, "RST 20H"),
+        (0xE8, "ADD16 SP r8"),
         (0xE9, "JP (HL)"),
         (0xEA, "LD8 (a16) A"),
+        (0xEE, "XOR d8"),
         (0xEF, "RST 28H"),
         (0xF0, "LD8 A (a8)"),
         (0xF1, "POP AF"),
         (0xF2, "LD8 A (C)"),
+        (0xF6, "OR d8"),
         (0xF7, "RST 30H"),
         (0xF9, "LD16 SP HL"),
         (0xFA, "LD8 A (a16)"),
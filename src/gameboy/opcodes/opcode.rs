@@ -29,7 +29,7 @@ impl OpCode {
 
         match self.category {
             Category::LD16 => {
-                cycles += run_ld16(&self.args, cpu, memory.get_memory());
+                cycles += run_ld16(&self.args, cpu, &mut memory);
             }
             Category::LD8 => {
                 cycles += run_ld8(&self.args, cpu, &mut memory);
@@ -39,44 +39,44 @@ impl OpCode {
                 cycles += 4;
             }
             Category::XOR => {
-                cycles += run_xor(&self.args, cpu, memory.get_memory());
+                cycles += run_xor(&self.args, cpu, &mut memory);
             }
             Category::AND => {
-                cycles += run_and(&self.args, cpu, memory.get_memory());
+                cycles += run_and(&self.args, cpu, &mut memory);
             }
             Category::BIT => {
-                cycles += run_bit(&self.args, cpu, memory.get_memory());
+                cycles += run_bit(&self.args, cpu, &mut memory);
             }
             Category::JP => {
-                cycles += run_jmp(&self.args, cpu, memory.get_memory());
+                cycles += run_jmp(&self.args, cpu, &mut memory);
             }
             Category::CALL => {
-                cycles += run_call(&self.args, cpu, memory.get_memory());
+                cycles += run_call(&self.args, cpu, &mut memory);
             }
             Category::RET => {
-                cycles += run_ret(&self.args, cpu, memory.get_memory());
+                cycles += run_ret(&self.args, cpu, &mut memory);
             }
             Category::RETI => {
                 // This is a strange combination instruction which just does
                 // RET & EI. It's used because it takes the same number of
                 // cycles as just doing RET.
-                cycles += run_ret(&self.args, cpu, memory.get_memory());
-                run_ei(&self.args, cpu, memory.get_memory());
+                cycles += run_ret(&self.args, cpu, &mut memory);
+                run_ei(&self.args, cpu, &mut memory);
             }
             Category::PUSH => {
-                cycles += run_push(&self.args, cpu, memory.get_memory());
+                cycles += run_push(&self.args, cpu, &mut memory);
             }
             Category::POP => {
-                cycles += run_pop(&self.args, cpu, memory.get_memory());
+                cycles += run_pop(&self.args, cpu, &mut memory);
             }
             Category::ADD => {
-                cycles += run_add(&self.args, cpu, memory.get_memory());
+                cycles += run_add(&self.args, cpu, &mut memory);
             }
             Category::ADD16 => {
-                cycles += run_add16(&self.args, cpu, memory.get_memory());
+                cycles += run_add16(&self.args, cpu, &mut memory);
             }
             Category::ADC => {
-                cycles += run_adc(&self.args, cpu, memory.get_memory());
+                cycles += run_adc(&self.args, cpu, &mut memory);
             }
             Category::INC => {
                 cycles += run_inc(&self.args, cpu, &mut memory);
@@ -85,37 +85,85 @@ impl OpCode {
                 cycles += run_dec(&self.args, cpu, &mut memory);
             }
             Category::RL => {
-                cycles += run_rl(&self.args, cpu, memory.get_memory());
+                cycles += run_rl(&self.args, cpu, &mut memory);
             }
             Category::RLA => {
-                cycles += run_rla(cpu, memory.get_memory());
+                cycles += run_rla(cpu, &mut memory);
             }
             Category::SUB => {
-                cycles += run_sub(&self.args, cpu, memory.get_memory());
+                cycles += run_sub(&self.args, cpu, &mut memory);
             }
             Category::CP => {
-                cycles += run_cp(&self.args, cpu, memory.get_memory());
+                cycles += run_cp(&self.args, cpu, &mut memory);
             }
             Category::OR => {
-                cycles += run_or(&self.args, cpu, memory.get_memory());
+                cycles += run_or(&self.args, cpu, &mut memory);
             }
             Category::EI => {
-                cycles += run_ei(&self.args, cpu, memory.get_memory());
+                cycles += run_ei(&self.args, cpu, &mut memory);
             }
             Category::CPL => {
-                cycles += run_cpl(&self.args, cpu, memory.get_memory());
+                cycles += run_cpl(&self.args, cpu, &mut memory);
             }
             Category::SWAP => {
-                cycles += run_swap(&self.args, cpu, memory.get_memory());
+                cycles += run_swap(&self.args, cpu, &mut memory);
             }
             Category::DI => {
-                cycles += run_di(&self.args, cpu, memory.get_memory());
+                cycles += run_di(&self.args, cpu, &mut memory);
             }
             Category::SCF => {
-                cycles += run_scf(cpu, memory.get_memory());
+                cycles += run_scf(cpu, &mut memory);
             }
             Category::RST => {
-                cycles += run_rst(&self.args, cpu, memory.get_memory());
+                cycles += run_rst(&self.args, cpu, &mut memory);
+            }
+            Category::SBC => {
+                cycles += run_sbc(&self.args, cpu, &mut memory);
+            }
+            Category::DAA => {
+                cycles += run_daa(cpu, &mut memory);
+            }
+            Category::CCF => {
+                cycles += run_ccf(cpu, &mut memory);
+            }
+            Category::RLCA => {
+                cycles += run_rlca(cpu, &mut memory);
+            }
+            Category::RRCA => {
+                cycles += run_rrca(cpu, &mut memory);
+            }
+            Category::RRA => {
+                cycles += run_rra(cpu, &mut memory);
+            }
+            Category::HALT => {
+                cycles += run_halt(&self.args, cpu, &mut memory);
+            }
+            Category::STOP => {
+                cycles += run_stop(&self.args, cpu, &mut memory);
+            }
+            Category::RLC => {
+                cycles += run_rlc(&self.args, cpu, &mut memory);
+            }
+            Category::RRC => {
+                cycles += run_rrc(&self.args, cpu, &mut memory);
+            }
+            Category::RR => {
+                cycles += run_rr(&self.args, cpu, &mut memory);
+            }
+            Category::SLA => {
+                cycles += run_sla(&self.args, cpu, &mut memory);
+            }
+            Category::SRA => {
+                cycles += run_sra(&self.args, cpu, &mut memory);
+            }
+            Category::SRL => {
+                cycles += run_srl(&self.args, cpu, &mut memory);
+            }
+            Category::RES => {
+                cycles += run_res(&self.args, cpu, &mut memory);
+            }
+            Category::SET => {
+                cycles += run_set(&self.args, cpu, &mut memory);
             }
         };
 
@@ -1,3 +1,4 @@
+use crate::gameboy::memory_adapter::MemoryAdapter;
 use crate::gameboy::{cpu::CPU, read_flag, write_flag, Flags, RegisterLabel8};
 
 use super::super::argument::Argument;
@@ -8,19 +9,13 @@ struct FlagResult {
     carry: bool,
 }
 
-fn checked_add(v1: u8, v2: u8) -> (u8, FlagResult) {
-    // sum = a + b
-    // no_carry_sum = a ^ b
-    // carry_info = sum ^ no_carry_sum
-    // half_carry = carry_info & 0x10
-    // carry = carry_info & 0x100
-
-    let wrapped_sum = v1.wrapping_add(v2);
-    let sum = v1 as u16 + v2 as u16;
-    let no_carry_sum = (v1 ^ v2) as u16;
-    let carry_info = sum ^ no_carry_sum;
-    let half_carry = ((carry_info & 0x10) >> 4) > 0;
-    let carry = ((carry_info & 0x100) >> 8) > 0;
+fn checked_add(v1: u8, v2: u8, carry: u8) -> (u8, FlagResult) {
+    // The carry has to be included in both the half carry & carry
+    // checks otherwise 0x0F + 0x00 + 1 would not set H
+    let sum = v1 as u16 + v2 as u16 + carry as u16;
+    let wrapped_sum = sum as u8;
+    let half_carry = (v1 & 0x0F) + (v2 & 0x0F) + carry > 0x0F;
+    let carry = sum > 0xFF;
 
     (
         wrapped_sum,
@@ -32,29 +27,29 @@ fn checked_add(v1: u8, v2: u8) -> (u8, FlagResult) {
     )
 }
 
-pub fn run_adc(args: &[Argument], cpu: &mut CPU, _memory: &mut [u8]) -> u32 {
+pub fn run_adc(args: &[Argument], cpu: &mut CPU, memory: &mut MemoryAdapter) -> u32 {
     // Result: a = r8 + carry flag + a
     // Z = Z, N = 0, H = H, C = C
+    let mut cycles = 4;
 
-    let mut rhs = match args[0] {
+    let rhs = match args[0] {
         Argument::Register8Constant(register) => cpu.read_8_bits(register),
+        Argument::RegisterIndirect(register) => {
+            cycles += 4;
+            let address = cpu.read_16_bits(register);
+            memory.get_memory_at(address)
+        }
+        Argument::SmallValue(val) => {
+            cycles += 4;
+            val
+        }
         _ => panic!("Invalid argument for ADC {:?}", args[0]),
     };
 
-    let mut lhs = cpu.read_8_bits(RegisterLabel8::A);
+    let lhs = cpu.read_8_bits(RegisterLabel8::A);
     let carry = if read_flag(cpu, Flags::C) { 1 } else { 0 };
 
-    if lhs < 0xFF {
-        lhs += carry;
-    } else if rhs < 0xFF {
-        rhs += carry;
-    }
-
-    let (mut result, flags) = checked_add(lhs, rhs);
-
-    if lhs == 0xFF && rhs == 0xFF {
-        result += carry;
-    }
+    let (result, flags) = checked_add(lhs, rhs, carry);
 
     write_flag(cpu, Flags::N, false);
     write_flag(cpu, Flags::Z, flags.zero);
@@ -63,5 +58,5 @@ pub fn run_adc(args: &[Argument], cpu: &mut CPU, _memory: &mut [u8]) -> u32 {
 
     cpu.write_8_bits(RegisterLabel8::A, result);
 
-    return 4;
+    cycles
 }
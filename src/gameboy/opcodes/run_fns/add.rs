@@ -1,9 +1,10 @@
+use crate::gameboy::memory_adapter::MemoryAdapter;
 use crate::gameboy::{cpu::CPU, RegisterLabel8};
 
 use super::super::super::flags_register::{write_flag, Flags};
 use super::super::argument::Argument;
 
-pub fn run_add(args: &[Argument], cpu: &mut CPU, memory: &mut [u8]) -> u32 {
+pub fn run_add(args: &[Argument], cpu: &mut CPU, memory: &mut MemoryAdapter) -> u32 {
     // Reset flags
     cpu.write_8_bits(RegisterLabel8::F, 0);
 
@@ -22,7 +23,7 @@ pub fn run_add(args: &[Argument], cpu: &mut CPU, memory: &mut [u8]) -> u32 {
         Argument::RegisterIndirect(register) => {
             extra_cycles += 4;
             let address = cpu.read_16_bits(register);
-            memory[address as usize]
+            memory.get_memory_at(address)
         }
         Argument::Register8Constant(register) => cpu.read_8_bits(register),
         Argument::SmallValue(val) => {
@@ -46,7 +47,7 @@ pub fn run_add(args: &[Argument], cpu: &mut CPU, memory: &mut [u8]) -> u32 {
         write_flag(cpu, Flags::C, true);
     }
 
-    if result > 0b0000_1111 && target_value < 0b0001_0000 {
+    if (target_value & 0x0F) + (source & 0x0F) > 0x0F {
         write_flag(cpu, Flags::H, true);
     }
 
@@ -1,9 +1,21 @@
-use crate::gameboy::{cpu::CPU, write_flag, Flags, RegisterLabel16};
+use crate::gameboy::memory_adapter::MemoryAdapter;
+use crate::gameboy::{cpu::CPU, write_flag, Flags, RegisterLabel16, RegisterLabel8};
 
 // use super::super::super::flags_register::{write_flag, Flags};
 use super::super::argument::Argument;
 
-pub fn run_add16(args: &[Argument], cpu: &mut CPU, _: &mut [u8]) -> u32 {
+pub fn run_add16(args: &[Argument], cpu: &mut CPU, _: &mut MemoryAdapter) -> u32 {
+    // ADD SP r8 behaves differently to the HL additions
+    if let (
+        Argument::Register16Constant(RegisterLabel16::StackPointer),
+        Argument::JumpDistance(offset),
+    ) = (args[0], args[1])
+    {
+        let result = add_sp_offset(cpu, offset);
+        cpu.write_16_bits(RegisterLabel16::StackPointer, result);
+        return 16;
+    }
+
     let left_val = match args[0] {
         Argument::Register16Constant(register) => cpu.read_16_bits(register),
         _ => {
@@ -35,3 +47,23 @@ pub fn run_add16(args: &[Argument], cpu: &mut CPU, _: &mut [u8]) -> u32 {
 
     8
 }
+
+/// Add a signed offset to the stack pointer & set the flags.
+///
+/// The flags are calculated from the unsigned addition of the lower
+/// byte and Z & N are always reset. LD HL,SP+r8 sets the flags the same way.
+pub fn add_sp_offset(cpu: &mut CPU, offset: i8) -> u16 {
+    let sp = cpu.read_16_bits(RegisterLabel16::StackPointer);
+    let unsigned_offset = offset as u8 as u16;
+
+    // Reset the flags
+    cpu.write_8_bits(RegisterLabel8::F, 0);
+    write_flag(
+        cpu,
+        Flags::H,
+        (sp & 0x000F) + (unsigned_offset & 0x000F) > 0x000F,
+    );
+    write_flag(cpu, Flags::C, (sp & 0x00FF) + unsigned_offset > 0x00FF);
+
+    sp.wrapping_add(offset as i16 as u16)
+}
@@ -1,9 +1,10 @@
+use crate::gameboy::memory_adapter::MemoryAdapter;
 use crate::gameboy::{cpu::CPU, RegisterLabel8};
 
 use super::super::super::flags_register::{write_flag, Flags};
 use super::super::argument::Argument;
 
-pub fn run_and(args: &[Argument], cpu: &mut CPU, memory: &mut [u8]) -> u32 {
+pub fn run_and(args: &[Argument], cpu: &mut CPU, memory: &mut MemoryAdapter) -> u32 {
     cpu.write_8_bits(RegisterLabel8::F, 0);
     write_flag(cpu, Flags::H, true);
 
@@ -21,7 +22,7 @@ pub fn run_and(args: &[Argument], cpu: &mut CPU, memory: &mut [u8]) -> u32 {
         }
         Argument::RegisterIndirect(reg) => {
             let address = cpu.read_16_bits(reg);
-            let comparitor = memory[address as usize];
+            let comparitor = memory.get_memory_at(address);
 
             cycles += 4;
 
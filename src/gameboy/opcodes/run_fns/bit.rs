@@ -1,24 +1,53 @@
 use crate::gameboy::cpu::CPU;
+use crate::gameboy::memory_adapter::MemoryAdapter;
 
 use super::super::super::flags_register::{write_flag, Flags};
 use super::super::argument::Argument;
+use super::cb_argument::{cb_argument_cycles, read_cb_argument, write_cb_argument};
 
-pub fn run_bit(args: &[Argument], cpu: &mut CPU, _memory: &mut [u8]) -> u32 {
-    let mut cycles = 0;
+pub fn run_bit(args: &[Argument], cpu: &mut CPU, memory: &mut MemoryAdapter) -> u32 {
     assert_eq!(args.len(), 2);
 
-    match (args[0], args[1]) {
-        (Argument::Bit(bit), Argument::Register8Constant(register)) => {
-            let register = cpu.read_8_bits(register);
-
-            let result = (((0x1 << bit) ^ register) >> bit) == 1;
-            write_flag(cpu, Flags::Z, result);
-            write_flag(cpu, Flags::N, false);
-            write_flag(cpu, Flags::H, true);
-        }
+    let bit = match args[0] {
+        Argument::Bit(bit) => bit,
         _ => panic!("Invalid arguments"),
+    };
+
+    let value = read_cb_argument(args[1], cpu, memory);
+
+    // Z is set if the bit is 0. C is unaffected
+    let result = (value & (0x1 << bit)) == 0;
+    write_flag(cpu, Flags::Z, result);
+    write_flag(cpu, Flags::N, false);
+    write_flag(cpu, Flags::H, true);
+
+    // BIT only reads memory so (HL) is quicker than the other CB instructions
+    match args[1] {
+        Argument::RegisterIndirect(_) => 12,
+        _ => 8,
     }
+}
+
+pub fn run_res(args: &[Argument], cpu: &mut CPU, memory: &mut MemoryAdapter) -> u32 {
+    let bit = match args[0] {
+        Argument::Bit(bit) => bit,
+        _ => panic!("Invalid arguments"),
+    };
+
+    let value = read_cb_argument(args[1], cpu, memory);
+    write_cb_argument(args[1], cpu, memory, value & !(0x1 << bit));
+
+    cb_argument_cycles(args[1])
+}
+
+pub fn run_set(args: &[Argument], cpu: &mut CPU, memory: &mut MemoryAdapter) -> u32 {
+    let bit = match args[0] {
+        Argument::Bit(bit) => bit,
+        _ => panic!("Invalid arguments"),
+    };
+
+    let value = read_cb_argument(args[1], cpu, memory);
+    write_cb_argument(args[1], cpu, memory, value | (0x1 << bit));
 
-    cycles += 12;
-    cycles
+    cb_argument_cycles(args[1])
 }
@@ -0,0 +1,41 @@
+use crate::gameboy::cpu::CPU;
+use crate::gameboy::memory_adapter::MemoryAdapter;
+use crate::gameboy::{write_flag, Flags, RegisterLabel8};
+
+use super::super::Argument;
+
+/// Read the value a CB instruction works on. This is either
+/// an 8 bit register or the memory pointed to by (HL).
+pub fn read_cb_argument(argument: Argument, cpu: &CPU, memory: &MemoryAdapter) -> u8 {
+    match argument {
+        Argument::Register8Constant(register) => cpu.read_8_bits(register),
+        Argument::RegisterIndirect(register) => memory.get_memory_at(cpu.read_16_bits(register)),
+        _ => panic!("Invalid argument for CB instruction {:?}", argument),
+    }
+}
+
+pub fn write_cb_argument(argument: Argument, cpu: &mut CPU, memory: &mut MemoryAdapter, value: u8) {
+    match argument {
+        Argument::Register8Constant(register) => cpu.write_8_bits(register, value),
+        Argument::RegisterIndirect(register) => {
+            memory.set_memory_at(cpu.read_16_bits(register), value)
+        }
+        _ => panic!("Invalid argument for CB instruction {:?}", argument),
+    }
+}
+
+/// The cycles for a rotate, shift, RES or SET instruction.
+/// (HL) needs both a read & a write of memory.
+pub fn cb_argument_cycles(argument: Argument) -> u32 {
+    match argument {
+        Argument::RegisterIndirect(_) => 16,
+        _ => 8,
+    }
+}
+
+/// Rotates & shifts reset N & H and set Z & C from the result
+pub fn write_shift_flags(cpu: &mut CPU, result: u8, carry: bool) {
+    cpu.write_8_bits(RegisterLabel8::F, 0);
+    write_flag(cpu, Flags::Z, result == 0);
+    write_flag(cpu, Flags::C, carry);
+}
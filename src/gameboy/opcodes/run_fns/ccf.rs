@@ -0,0 +1,10 @@
+use crate::gameboy::memory_adapter::MemoryAdapter;
+use crate::gameboy::{cpu::CPU, read_flag, write_flag, Flags};
+
+pub fn run_ccf(cpu: &mut CPU, _memory: &mut MemoryAdapter) -> u32 {
+    let carry = read_flag(cpu, Flags::C);
+    write_flag(cpu, Flags::C, !carry);
+    write_flag(cpu, Flags::N, false);
+    write_flag(cpu, Flags::H, false);
+    4
+}
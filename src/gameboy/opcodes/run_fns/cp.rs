@@ -1,10 +1,11 @@
 use crate::gameboy::cpu::CPU;
+use crate::gameboy::memory_adapter::MemoryAdapter;
 
 use super::super::super::{write_flag, Flags};
 use super::super::Argument;
 use crate::gameboy::RegisterLabel8;
 
-pub fn run_cp(args: &[Argument], cpu: &mut CPU, memory: &mut [u8]) -> u32 {
+pub fn run_cp(args: &[Argument], cpu: &mut CPU, memory: &mut MemoryAdapter) -> u32 {
     let mut cycles = 4;
 
     // Clear all the flags
@@ -22,7 +23,7 @@ pub fn run_cp(args: &[Argument], cpu: &mut CPU, memory: &mut [u8]) -> u32 {
         Argument::RegisterIndirect(register) => {
             let addr = cpu.read_16_bits(register);
             cycles += 4;
-            memory[addr as usize]
+            memory.get_memory_at(addr)
         }
         Argument::Register8Constant(register) => cpu.read_8_bits(register),
         _ => {
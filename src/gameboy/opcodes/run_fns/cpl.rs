@@ -1,9 +1,10 @@
+use crate::gameboy::memory_adapter::MemoryAdapter;
 use crate::gameboy::{cpu::CPU, write_flag, Flags, RegisterLabel8};
 
 use super::super::Argument;
 
 #[allow(clippy::eq_op)]
-pub fn run_cpl(_: &[Argument], cpu: &mut CPU, _: &mut Vec<u8>) -> u32 {
+pub fn run_cpl(_: &[Argument], cpu: &mut CPU, _: &mut MemoryAdapter) -> u32 {
     let a = cpu.read_8_bits(RegisterLabel8::A);
 
     cpu.write_8_bits(RegisterLabel8::A, !a);
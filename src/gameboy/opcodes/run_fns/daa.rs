@@ -0,0 +1,38 @@
+use crate::gameboy::memory_adapter::MemoryAdapter;
+use crate::gameboy::{cpu::CPU, read_flag, write_flag, Flags, RegisterLabel8};
+
+pub fn run_daa(cpu: &mut CPU, _memory: &mut MemoryAdapter) -> u32 {
+    // Adjust A so that it contains the binary coded decimal result of
+    // the previous ADD/ADC/SUB/SBC. The N flag tells us which it was.
+    let mut a = cpu.read_8_bits(RegisterLabel8::A);
+    let mut carry = read_flag(cpu, Flags::C);
+    let half_carry = read_flag(cpu, Flags::H);
+
+    if !read_flag(cpu, Flags::N) {
+        // After an addition fix up each nibble that has gone above 9
+        if carry || a > 0x99 {
+            a = a.wrapping_add(0x60);
+            carry = true;
+        }
+        if half_carry || (a & 0x0F) > 0x09 {
+            a = a.wrapping_add(0x06);
+        }
+    } else {
+        // After a subtraction only the flags tell us what to undo
+        if carry {
+            a = a.wrapping_sub(0x60);
+        }
+        if half_carry {
+            a = a.wrapping_sub(0x06);
+        }
+    }
+
+    // N is left untouched
+    write_flag(cpu, Flags::Z, a == 0);
+    write_flag(cpu, Flags::H, false);
+    write_flag(cpu, Flags::C, carry);
+
+    cpu.write_8_bits(RegisterLabel8::A, a);
+
+    4
+}
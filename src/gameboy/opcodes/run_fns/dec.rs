@@ -35,9 +35,7 @@ pub fn run_dec(args: &[Argument], cpu: &mut CPU, memory: &mut MemoryAdapter) ->
         Argument::Register16Constant(register) => {
             let val = cpu.read_16_bits(register);
 
-            if val > 0 {
-                cpu.write_16_bits(register, val - 1);
-            }
+            cpu.write_16_bits(register, val.wrapping_sub(1));
 
             8
         }
@@ -0,0 +1,17 @@
+use crate::gameboy::cpu::CPU;
+use crate::gameboy::memory_adapter::MemoryAdapter;
+
+use super::super::argument::Argument;
+
+pub fn run_halt(_args: &[Argument], cpu: &mut CPU, _memory: &mut MemoryAdapter) -> u32 {
+    // The Gameboy wakes up the CPU when an interrupt is pending
+    cpu.halt();
+    4
+}
+
+pub fn run_stop(_args: &[Argument], cpu: &mut CPU, _memory: &mut MemoryAdapter) -> u32 {
+    // The extra byte is skipped because the instruction is 2 bytes long.
+    // Only a joypad press will wake the CPU.
+    cpu.stop();
+    4
+}
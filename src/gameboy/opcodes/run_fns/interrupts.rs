@@ -1,13 +1,14 @@
 use crate::gameboy::cpu::CPU;
+use crate::gameboy::memory_adapter::MemoryAdapter;
 
 use super::super::argument::Argument;
 
-pub fn run_ei(_args: &[Argument], cpu: &mut CPU, _memory: &mut [u8]) -> u32 {
+pub fn run_ei(_args: &[Argument], cpu: &mut CPU, _memory: &mut MemoryAdapter) -> u32 {
     cpu.enable_global_interrupt();
     4
 }
 
-pub fn run_di(_args: &[Argument], cpu: &mut CPU, _memory: &mut [u8]) -> u32 {
+pub fn run_di(_args: &[Argument], cpu: &mut CPU, _memory: &mut MemoryAdapter) -> u32 {
     cpu.disable_interrupts();
     4
 }
@@ -1,13 +1,14 @@
 #![allow(clippy::if_same_then_else)]
 
 use crate::gameboy::cpu::CPU;
+use crate::gameboy::memory_adapter::MemoryAdapter;
 
 use super::super::super::{read_flag, Flags};
 use super::super::argument::JumpCondition;
 use super::super::Argument;
 use crate::gameboy::RegisterLabel16;
 
-pub fn run_jmp(args: &[Argument], cpu: &mut CPU, _memory: &mut [u8]) -> u32 {
+pub fn run_jmp(args: &[Argument], cpu: &mut CPU, _memory: &mut MemoryAdapter) -> u32 {
     assert!(args.len() <= 2);
 
     let should_jump = match args[0] {
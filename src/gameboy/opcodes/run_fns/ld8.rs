@@ -19,6 +19,9 @@ pub fn run_ld8(args: &[Argument], cpu: &mut CPU, memory: &mut MemoryAdapter) ->
                 memory.get_memory_at(cpu.read_16_bits(register))
             }
             Argument::AddressIndirect(address) => memory.get_memory_at(address),
+            Argument::HighOffsetRegister(register) => {
+                memory.get_memory_at(0xFF00 + cpu.read_8_bits(register) as u16)
+            }
             _ => panic!("Command does not support source argument {:?}", args[1]),
         };
 
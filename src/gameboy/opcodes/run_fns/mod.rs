@@ -4,9 +4,13 @@ mod add16;
 mod and;
 mod bit;
 mod call;
+mod cb_argument;
+mod ccf;
 mod cp;
 mod cpl;
+mod daa;
 mod dec;
+mod halt;
 mod inc;
 mod interrupts;
 mod jmp;
@@ -19,8 +23,12 @@ mod ret;
 mod rotate_left;
 mod rotate_left_a;
 mod rotate_method;
+mod rotate_right;
+mod rotate_right_a;
 mod rst;
+mod sbc;
 mod scf;
+mod shift;
 mod sub;
 mod swap;
 mod xor;
@@ -29,11 +37,14 @@ pub use self::adc::run_adc;
 pub use self::add::run_add;
 pub use self::add16::run_add16;
 pub use self::and::run_and;
-pub use self::bit::run_bit;
+pub use self::bit::{run_bit, run_res, run_set};
 pub use self::call::run_call;
+pub use self::ccf::run_ccf;
 pub use self::cp::run_cp;
 pub use self::cpl::run_cpl;
+pub use self::daa::run_daa;
 pub use self::dec::run_dec;
+pub use self::halt::{run_halt, run_stop};
 pub use self::inc::run_inc;
 pub use self::interrupts::{run_di, run_ei};
 pub use self::jmp::run_jmp;
@@ -43,10 +54,14 @@ pub use self::or::run_or;
 pub use self::pop::run_pop;
 pub use self::push::run_push;
 pub use self::ret::run_ret;
-pub use self::rotate_left::run_rl;
-pub use self::rotate_left_a::run_rla;
+pub use self::rotate_left::{run_rl, run_rlc};
+pub use self::rotate_left_a::{run_rla, run_rlca};
+pub use self::rotate_right::{run_rr, run_rrc};
+pub use self::rotate_right_a::{run_rra, run_rrca};
 pub use self::rst::run_rst;
+pub use self::sbc::run_sbc;
 pub use self::scf::run_scf;
+pub use self::shift::{run_sla, run_sra, run_srl};
 pub use self::sub::run_sub;
 pub use self::swap::run_swap;
 pub use self::xor::run_xor;
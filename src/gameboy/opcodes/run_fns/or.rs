@@ -1,22 +1,34 @@
 use crate::gameboy::cpu::CPU;
+use crate::gameboy::memory_adapter::MemoryAdapter;
 use crate::gameboy::RegisterLabel8;
 use crate::gameboy::{flags_register, Flags};
 
 use super::super::Argument;
 
-pub fn run_or(args: &[Argument], cpu: &mut CPU, _memory: &mut [u8]) -> u32 {
-    match args[0] {
-        Argument::Register8Constant(register) => {
-            let new_val = cpu.read_8_bits(RegisterLabel8::A) | cpu.read_8_bits(register);
-            cpu.write_8_bits(RegisterLabel8::A, new_val);
-            cpu.write_8_bits(RegisterLabel8::F, 0);
+pub fn run_or(args: &[Argument], cpu: &mut CPU, memory: &mut MemoryAdapter) -> u32 {
+    let mut cycles = 4;
 
-            if new_val == 0 {
-                flags_register::write_flag(cpu, Flags::Z, true);
-            }
+    let value = match args[0] {
+        Argument::Register8Constant(register) => cpu.read_8_bits(register),
+        Argument::RegisterIndirect(register) => {
+            cycles += 4;
+            let address = cpu.read_16_bits(register);
+            memory.get_memory_at(address)
+        }
+        Argument::SmallValue(val) => {
+            cycles += 4;
+            val
         }
         _ => panic!("Argument not supported: {:?}", args[0]),
+    };
+
+    let new_val = cpu.read_8_bits(RegisterLabel8::A) | value;
+    cpu.write_8_bits(RegisterLabel8::A, new_val);
+    cpu.write_8_bits(RegisterLabel8::F, 0);
+
+    if new_val == 0 {
+        flags_register::write_flag(cpu, Flags::Z, true);
     }
 
-    4
+    cycles
 }
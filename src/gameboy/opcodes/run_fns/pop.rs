@@ -1,21 +1,27 @@
 use crate::gameboy::cpu::CPU;
+use crate::gameboy::memory_adapter::MemoryAdapter;
 
 use super::super::Argument;
 use crate::gameboy::RegisterLabel16;
 
-pub fn run_pop(args: &[Argument], cpu: &mut CPU, memory: &mut [u8]) -> u32 {
-    if let Argument::Register16Constant(_) = args[0] {
+pub fn run_pop(args: &[Argument], cpu: &mut CPU, memory: &mut MemoryAdapter) -> u32 {
+    if let Argument::Register16Constant(register) = args[0] {
         // Read the stack pointer
         let sp = cpu.read_16_bits(RegisterLabel16::StackPointer);
 
         // Get the value at the stack pointer
-        let lower_byte = memory[sp as usize] as u16;
-        let higher_byte = memory[sp as usize + 1] as u16;
+        let lower_byte = memory.get_memory_at(sp) as u16;
+        let higher_byte = memory.get_memory_at(sp.wrapping_add(1)) as u16;
 
-        let result = (higher_byte << 8) + lower_byte;
+        let mut result = (higher_byte << 8) + lower_byte;
 
-        // Write the result into the BC register
-        cpu.write_16_bits(RegisterLabel16::BC, result);
+        // The lower nibble of F is always zero
+        if register == RegisterLabel16::AF {
+            result &= 0xFFF0;
+        }
+
+        // Write the result into the register
+        cpu.write_16_bits(register, result);
 
         // Safely add 2 and write away
         cpu.write_16_bits(RegisterLabel16::StackPointer, sp + 2);
@@ -1,17 +1,18 @@
 use crate::gameboy::cpu::CPU;
+use crate::gameboy::memory_adapter::MemoryAdapter;
 
 use super::super::Argument;
 use crate::gameboy::RegisterLabel16;
 
-pub fn run_push(args: &[Argument], cpu: &mut CPU, memory: &mut [u8]) -> u32 {
+pub fn run_push(args: &[Argument], cpu: &mut CPU, memory: &mut MemoryAdapter) -> u32 {
     let mut cycles = 0;
     if let Argument::Register16Constant(reg) = args[0] {
         let value = cpu.read_16_bits(reg);
         let bytes = value.to_be_bytes();
 
         let sp = cpu.read_16_bits(RegisterLabel16::StackPointer);
-        memory[(sp - 1) as usize] = bytes[0];
-        memory[(sp - 2) as usize] = bytes[1];
+        memory.set_memory_at(sp - 1, bytes[0]);
+        memory.set_memory_at(sp - 2, bytes[1]);
 
         cpu.write_16_bits(RegisterLabel16::StackPointer, sp - 2);
 
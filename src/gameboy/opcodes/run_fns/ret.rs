@@ -1,9 +1,10 @@
 use crate::gameboy::cpu::CPU;
+use crate::gameboy::memory_adapter::MemoryAdapter;
 
 use crate::gameboy::opcodes::{Argument, JumpCondition};
 use crate::gameboy::{read_flag, Flags, RegisterLabel16};
 
-pub fn run_ret(args: &[Argument], cpu: &mut CPU, memory: &mut [u8]) -> u32 {
+pub fn run_ret(args: &[Argument], cpu: &mut CPU, memory: &mut MemoryAdapter) -> u32 {
     // If there is a condition then check it
 
     let mut extra_cycles = 0;
@@ -29,12 +30,12 @@ pub fn run_ret(args: &[Argument], cpu: &mut CPU, memory: &mut [u8]) -> u32 {
     }
 }
 
-fn perform_return(cpu: &mut CPU, memory: &mut [u8]) {
+fn perform_return(cpu: &mut CPU, memory: &mut MemoryAdapter) {
     let stack_pointer = cpu.read_16_bits(RegisterLabel16::StackPointer);
 
     // Get the top 2 bytes of the stack
-    let lower_byte = memory[stack_pointer as usize];
-    let higher_byte = memory[stack_pointer as usize + 1];
+    let lower_byte = memory.get_memory_at(stack_pointer);
+    let higher_byte = memory.get_memory_at(stack_pointer.wrapping_add(1));
 
     // Move the stack pointer
     cpu.write_16_bits(RegisterLabel16::StackPointer, stack_pointer + 2);
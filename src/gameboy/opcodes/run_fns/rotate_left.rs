@@ -1,33 +1,34 @@
 use crate::gameboy::cpu::CPU;
+use crate::gameboy::memory_adapter::MemoryAdapter;
 
-use super::super::super::{read_flag, write_flag, Flags};
+use super::super::super::{read_flag, Flags};
 use super::super::Argument;
+use super::cb_argument::{
+    cb_argument_cycles, read_cb_argument, write_cb_argument, write_shift_flags,
+};
 use super::rotate_method::shift_reg_and_flag;
 
-pub fn run_rl(args: &[Argument], cpu: &mut CPU, _memory: &mut [u8]) -> u32 {
-    let mut cycles = 0;
-    if let Argument::Register8Constant(reg) = args[0] {
-        let reg_contents = cpu.read_8_bits(reg);
-        let carry_flag = read_flag(cpu, Flags::C);
+pub fn run_rl(args: &[Argument], cpu: &mut CPU, memory: &mut MemoryAdapter) -> u32 {
+    let reg_contents = read_cb_argument(args[0], cpu, memory);
+    let carry_flag = read_flag(cpu, Flags::C);
 
-        let (new_register, new_carry) = shift_reg_and_flag(reg_contents, carry_flag);
+    // Rotate left through the carry flag
+    let (new_register, new_carry) = shift_reg_and_flag(reg_contents, carry_flag);
 
-        // Set the carry flag
-        write_flag(cpu, Flags::C, new_carry);
+    write_shift_flags(cpu, new_register, new_carry);
+    write_cb_argument(args[0], cpu, memory, new_register);
 
-        // Unset the H & N flags
-        write_flag(cpu, Flags::H, false);
-        write_flag(cpu, Flags::N, false);
+    cb_argument_cycles(args[0])
+}
+
+pub fn run_rlc(args: &[Argument], cpu: &mut CPU, memory: &mut MemoryAdapter) -> u32 {
+    let reg_contents = read_cb_argument(args[0], cpu, memory);
 
-        // Write away the flag
-        cpu.write_8_bits(reg, new_register);
+    // Bit 7 goes into both bit 0 and the carry flag
+    let new_register = reg_contents.rotate_left(1);
 
-        // Check the result in the C register for 0 to set the zero flag
-        if new_register == 0 {
-            write_flag(cpu, Flags::Z, true);
-        }
+    write_shift_flags(cpu, new_register, (reg_contents & 0b1000_0000) != 0);
+    write_cb_argument(args[0], cpu, memory, new_register);
 
-        cycles += 8;
-    }
-    cycles
+    cb_argument_cycles(args[0])
 }
@@ -1,9 +1,10 @@
 use crate::gameboy::cpu::CPU;
+use crate::gameboy::memory_adapter::MemoryAdapter;
 
 use super::super::super::{read_flag, write_flag, Flags, RegisterLabel8};
 use super::rotate_method::shift_reg_and_flag;
 
-pub fn run_rla(cpu: &mut CPU, _memory: &mut [u8]) -> u32 {
+pub fn run_rla(cpu: &mut CPU, _memory: &mut MemoryAdapter) -> u32 {
     let mut cycles = 0;
     let reg_contents = cpu.read_8_bits(RegisterLabel8::A);
     let carry_flag = read_flag(cpu, Flags::C);
@@ -21,11 +22,23 @@ pub fn run_rla(cpu: &mut CPU, _memory: &mut [u8]) -> u32 {
     // Write away the flag
     cpu.write_8_bits(RegisterLabel8::A, new_register);
 
-    // Check the result in the C register for 0 to set the zero flag
-    if new_register == 0 {
-        write_flag(cpu, Flags::Z, true);
-    }
+    // Unlike RL the zero flag is always reset
+    write_flag(cpu, Flags::Z, false);
 
     cycles += 4;
     cycles
 }
+
+pub fn run_rlca(cpu: &mut CPU, _memory: &mut MemoryAdapter) -> u32 {
+    let reg_contents = cpu.read_8_bits(RegisterLabel8::A);
+
+    // Bit 7 goes into both bit 0 and the carry flag
+    let new_register = reg_contents.rotate_left(1);
+
+    cpu.write_8_bits(RegisterLabel8::F, 0);
+    write_flag(cpu, Flags::C, (reg_contents & 0b1000_0000) != 0);
+
+    cpu.write_8_bits(RegisterLabel8::A, new_register);
+
+    4
+}
@@ -10,3 +10,12 @@ pub fn shift_reg_and_flag(register: u8, carry: bool) -> (u8, bool) {
 
     (new_register, eighth_bit == 1)
 }
+
+pub fn shift_right_reg_and_flag(register: u8, carry: bool) -> (u8, bool) {
+    let first_bit = register & 0b0000_0001;
+
+    // Create the new register value with the carry in the top bit
+    let new_register = (register >> 1) | ((carry as u8) << 7);
+
+    (new_register, first_bit == 1)
+}
@@ -0,0 +1,34 @@
+use crate::gameboy::cpu::CPU;
+use crate::gameboy::memory_adapter::MemoryAdapter;
+
+use super::super::super::{read_flag, Flags};
+use super::super::Argument;
+use super::cb_argument::{
+    cb_argument_cycles, read_cb_argument, write_cb_argument, write_shift_flags,
+};
+use super::rotate_method::shift_right_reg_and_flag;
+
+pub fn run_rr(args: &[Argument], cpu: &mut CPU, memory: &mut MemoryAdapter) -> u32 {
+    let reg_contents = read_cb_argument(args[0], cpu, memory);
+    let carry_flag = read_flag(cpu, Flags::C);
+
+    // Rotate right through the carry flag
+    let (new_register, new_carry) = shift_right_reg_and_flag(reg_contents, carry_flag);
+
+    write_shift_flags(cpu, new_register, new_carry);
+    write_cb_argument(args[0], cpu, memory, new_register);
+
+    cb_argument_cycles(args[0])
+}
+
+pub fn run_rrc(args: &[Argument], cpu: &mut CPU, memory: &mut MemoryAdapter) -> u32 {
+    let reg_contents = read_cb_argument(args[0], cpu, memory);
+
+    // Bit 0 goes into both bit 7 and the carry flag
+    let new_register = reg_contents.rotate_right(1);
+
+    write_shift_flags(cpu, new_register, (reg_contents & 0b0000_0001) != 0);
+    write_cb_argument(args[0], cpu, memory, new_register);
+
+    cb_argument_cycles(args[0])
+}
@@ -0,0 +1,35 @@
+use crate::gameboy::cpu::CPU;
+use crate::gameboy::memory_adapter::MemoryAdapter;
+
+use super::super::super::{read_flag, write_flag, Flags, RegisterLabel8};
+use super::rotate_method::shift_right_reg_and_flag;
+
+pub fn run_rra(cpu: &mut CPU, _memory: &mut MemoryAdapter) -> u32 {
+    let reg_contents = cpu.read_8_bits(RegisterLabel8::A);
+    let carry_flag = read_flag(cpu, Flags::C);
+
+    // Rotate right through the carry flag
+    let (new_register, new_carry) = shift_right_reg_and_flag(reg_contents, carry_flag);
+
+    // Only the carry flag is kept. Z is always reset for the A variants
+    cpu.write_8_bits(RegisterLabel8::F, 0);
+    write_flag(cpu, Flags::C, new_carry);
+
+    cpu.write_8_bits(RegisterLabel8::A, new_register);
+
+    4
+}
+
+pub fn run_rrca(cpu: &mut CPU, _memory: &mut MemoryAdapter) -> u32 {
+    let reg_contents = cpu.read_8_bits(RegisterLabel8::A);
+
+    // Bit 0 goes into both bit 7 and the carry flag
+    let new_register = reg_contents.rotate_right(1);
+
+    cpu.write_8_bits(RegisterLabel8::F, 0);
+    write_flag(cpu, Flags::C, (reg_contents & 0b0000_0001) != 0);
+
+    cpu.write_8_bits(RegisterLabel8::A, new_register);
+
+    4
+}
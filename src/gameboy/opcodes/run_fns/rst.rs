@@ -1,9 +1,10 @@
 use super::super::argument::Argument;
 
 use crate::gameboy::cpu::CPU;
+use crate::gameboy::memory_adapter::MemoryAdapter;
 use crate::gameboy::RegisterLabel16;
 
-pub fn run_rst(args: &[Argument], cpu: &mut CPU, memory: &mut [u8]) -> u32 {
+pub fn run_rst(args: &[Argument], cpu: &mut CPU, memory: &mut MemoryAdapter) -> u32 {
     let mut cycles = 0;
     if let Argument::Vector(address) = args[0] {
         // Store the contents of the program counter on the stack
@@ -11,8 +12,8 @@ pub fn run_rst(args: &[Argument], cpu: &mut CPU, memory: &mut [u8]) -> u32 {
         let return_address = pc.to_be_bytes();
 
         let stack = cpu.read_16_bits(RegisterLabel16::StackPointer);
-        memory[(stack - 1) as usize] = return_address[0];
-        memory[(stack - 2) as usize] = return_address[1];
+        memory.set_memory_at(stack - 1, return_address[0]);
+        memory.set_memory_at(stack - 2, return_address[1]);
 
         // Update the stack
         cpu.write_16_bits(RegisterLabel16::StackPointer, stack - 2);
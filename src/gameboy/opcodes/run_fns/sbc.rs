@@ -0,0 +1,42 @@
+use crate::gameboy::cpu::CPU;
+use crate::gameboy::memory_adapter::MemoryAdapter;
+
+use super::super::super::{read_flag, write_flag, Flags};
+use super::super::Argument;
+use crate::gameboy::RegisterLabel8;
+
+pub fn run_sbc(args: &[Argument], cpu: &mut CPU, memory: &mut MemoryAdapter) -> u32 {
+    let mut cycles = 4;
+
+    // Get the value to subtract from A
+    let value = match args[0] {
+        Argument::Register8Constant(register) => cpu.read_8_bits(register),
+        Argument::RegisterIndirect(register) => {
+            cycles += 4;
+            let address = cpu.read_16_bits(register);
+            memory.get_memory_at(address)
+        }
+        Argument::SmallValue(val) => {
+            cycles += 4;
+            val
+        }
+        _ => panic!("Invalid argument for SBC {:?}", args[0]),
+    };
+
+    let a = cpu.read_8_bits(RegisterLabel8::A);
+    let carry = if read_flag(cpu, Flags::C) { 1 } else { 0 };
+
+    // Result: a = a - value - carry flag
+    let result = a.wrapping_sub(value).wrapping_sub(carry);
+
+    // Z = Z, N = 1, H = H, C = C
+    cpu.write_8_bits(RegisterLabel8::F, 0);
+    write_flag(cpu, Flags::Z, result == 0);
+    write_flag(cpu, Flags::N, true);
+    write_flag(cpu, Flags::H, (a & 0x0F) < (value & 0x0F) + carry);
+    write_flag(cpu, Flags::C, (a as u16) < (value as u16) + (carry as u16));
+
+    cpu.write_8_bits(RegisterLabel8::A, result);
+
+    cycles
+}
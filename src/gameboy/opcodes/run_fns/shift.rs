@@ -0,0 +1,43 @@
+use crate::gameboy::cpu::CPU;
+use crate::gameboy::memory_adapter::MemoryAdapter;
+
+use super::super::Argument;
+use super::cb_argument::{
+    cb_argument_cycles, read_cb_argument, write_cb_argument, write_shift_flags,
+};
+
+pub fn run_sla(args: &[Argument], cpu: &mut CPU, memory: &mut MemoryAdapter) -> u32 {
+    let value = read_cb_argument(args[0], cpu, memory);
+
+    // Bit 7 is shifted into the carry & bit 0 becomes 0
+    let result = value << 1;
+
+    write_shift_flags(cpu, result, (value & 0b1000_0000) != 0);
+    write_cb_argument(args[0], cpu, memory, result);
+
+    cb_argument_cycles(args[0])
+}
+
+pub fn run_sra(args: &[Argument], cpu: &mut CPU, memory: &mut MemoryAdapter) -> u32 {
+    let value = read_cb_argument(args[0], cpu, memory);
+
+    // Bit 0 is shifted into the carry & bit 7 keeps its value
+    let result = (value >> 1) | (value & 0b1000_0000);
+
+    write_shift_flags(cpu, result, (value & 0b0000_0001) != 0);
+    write_cb_argument(args[0], cpu, memory, result);
+
+    cb_argument_cycles(args[0])
+}
+
+pub fn run_srl(args: &[Argument], cpu: &mut CPU, memory: &mut MemoryAdapter) -> u32 {
+    let value = read_cb_argument(args[0], cpu, memory);
+
+    // Bit 0 is shifted into the carry & bit 7 becomes 0
+    let result = value >> 1;
+
+    write_shift_flags(cpu, result, (value & 0b0000_0001) != 0);
+    write_cb_argument(args[0], cpu, memory, result);
+
+    cb_argument_cycles(args[0])
+}
@@ -1,38 +1,51 @@
 use crate::gameboy::cpu::CPU;
+use crate::gameboy::memory_adapter::MemoryAdapter;
 
 use super::super::super::{write_flag, Flags};
 use super::super::Argument;
 use crate::gameboy::RegisterLabel8;
 
-pub fn run_sub(args: &[Argument], cpu: &mut CPU, _memory: &mut [u8]) -> u32 {
-    let cycles = 4;
+pub fn run_sub(args: &[Argument], cpu: &mut CPU, memory: &mut MemoryAdapter) -> u32 {
+    let mut cycles = 4;
 
     // Clear all the flags
     cpu.write_8_bits(RegisterLabel8::F, 0);
 
-    // Get the register argument
-    if let Argument::Register8Constant(reg) = args[0] {
-        // Read the registers
-        let reg_value = cpu.read_8_bits(reg);
-        let a_reg_value = cpu.read_8_bits(RegisterLabel8::A);
+    // Get the value to subtract
+    let reg_value = match args[0] {
+        Argument::Register8Constant(reg) => cpu.read_8_bits(reg),
+        Argument::RegisterIndirect(reg) => {
+            cycles += 4;
+            let address = cpu.read_16_bits(reg);
+            memory.get_memory_at(address)
+        }
+        Argument::SmallValue(val) => {
+            cycles += 4;
+            val
+        }
+        _ => panic!("Argument not supported: {:?}", args[0]),
+    };
 
-        // Subtract one from the other
-        let result = a_reg_value.saturating_sub(reg_value);
+    // Read the A register
+    let a_reg_value = cpu.read_8_bits(RegisterLabel8::A);
 
-        // Write away the A flag
-        cpu.write_8_bits(RegisterLabel8::A, result);
+    // Subtract one from the other
+    let result = a_reg_value.wrapping_sub(reg_value);
 
-        if reg_value == a_reg_value {
-            write_flag(cpu, Flags::Z, true);
-        }
+    // Write away the A flag
+    cpu.write_8_bits(RegisterLabel8::A, result);
 
-        if reg_value > a_reg_value {
-            write_flag(cpu, Flags::C, true);
-        }
+    if reg_value == a_reg_value {
+        write_flag(cpu, Flags::Z, true);
+    }
 
-        if a_reg_value >= 0b0001_0000 && result < 0b0001_0000 {
-            write_flag(cpu, Flags::H, true);
-        }
+    if reg_value > a_reg_value {
+        write_flag(cpu, Flags::C, true);
+    }
+
+    // Borrowing from bit 4 happens when the lower nibble is too small
+    if (a_reg_value & 0x0F) < (reg_value & 0x0F) {
+        write_flag(cpu, Flags::H, true);
     }
 
     // Set the N flag
@@ -1,10 +1,11 @@
 use crate::gameboy::cpu::CPU;
+use crate::gameboy::memory_adapter::MemoryAdapter;
 use crate::gameboy::RegisterLabel8;
 
 use super::super::super::flags_register::{write_flag, Flags};
 use super::super::argument::Argument;
 
-pub fn run_swap(args: &[Argument], cpu: &mut CPU, memory: &mut [u8]) -> u32 {
+pub fn run_swap(args: &[Argument], cpu: &mut CPU, memory: &mut MemoryAdapter) -> u32 {
     let mut cycles = 0;
     assert_eq!(args.len(), 2);
 
@@ -23,15 +24,15 @@ pub fn run_swap(args: &[Argument], cpu: &mut CPU, memory: &mut [u8]) -> u32 {
             cpu.write_8_bits(register, result);
         }
         Argument::RegisterIndirect(register) => {
-            let address = cpu.read_16_bits(register) as usize;
-            let value = memory[address];
+            let address = cpu.read_16_bits(register);
+            let value = memory.get_memory_at(address);
 
             let top_nibble = value & 0b1111_0000;
             let result = (value << 4) | (top_nibble >> 4);
 
             zero_result = result == 0;
 
-            memory[address] = result;
+            memory.set_memory_at(address, result);
 
             cycles += 8;
         }
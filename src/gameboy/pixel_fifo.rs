@@ -0,0 +1,313 @@
+use std::collections::VecDeque;
+
+use super::bus::Bus;
+use super::ppu::{convert_base_to_color, is_window_visible, TileMap};
+use super::sprite::{scan_oam, sort_by_priority, sprite_height, Sprite};
+use super::Labels;
+use super::ScreenColor;
+
+/// Fetching a sprite pauses the background fetcher for this many dots
+const SPRITE_FETCH_DOTS: u32 = 6;
+
+/// The steps the background fetcher goes through. Each takes 2 dots apart from
+/// pushing which is retried every dot until the FIFO is empty.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum FetchStep {
+    TileNumber,
+    DataLow,
+    DataHigh,
+    Push,
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+struct ObjPixel {
+    value: u8,
+    uses_palette_1: bool,
+    behind_background: bool,
+}
+
+/// Draws a line a dot at a time the way the hardware does, so register changes
+/// made partway through drawing show up on screen.
+pub struct PixelFifo {
+    bg_fifo: VecDeque<u8>,
+    obj_fifo: VecDeque<ObjPixel>,
+    step: FetchStep,
+    step_dots: u32,
+    /// The tile column the fetcher is on, relative to the start of the line or window
+    fetcher_x: u8,
+    tile_number: u8,
+    data_low: u8,
+    data_high: u8,
+    /// The first tile fetched on each line is thrown away
+    first_fetch: bool,
+    /// Pixels still to drop off the front of the FIFO for SCX fine scrolling
+    discard: u8,
+    /// The x position of the next pixel to be drawn
+    lx: u8,
+    in_window: bool,
+    sprites: Vec<Sprite>,
+    sprite_stall: u32,
+}
+
+impl PixelFifo {
+    pub fn new() -> PixelFifo {
+        PixelFifo {
+            bg_fifo: VecDeque::with_capacity(16),
+            obj_fifo: VecDeque::with_capacity(8),
+            step: FetchStep::TileNumber,
+            step_dots: 0,
+            fetcher_x: 0,
+            tile_number: 0,
+            data_low: 0,
+            data_high: 0,
+            first_fetch: true,
+            discard: 0,
+            lx: 0,
+            in_window: false,
+            sprites: Vec::new(),
+            sprite_stall: 0,
+        }
+    }
+
+    /// Reset at the end of OAM scan, ready to draw the line
+    pub fn start_line(&mut self, memory: &Bus) {
+        let lcd_controls = memory.read_raw(Labels::LCD_CONTROLS);
+        let line = memory.read_raw(Labels::LCDC_Y);
+
+        self.bg_fifo.clear();
+        self.obj_fifo.clear();
+        self.step = FetchStep::TileNumber;
+        self.step_dots = 0;
+        self.fetcher_x = 0;
+        self.first_fetch = true;
+        self.discard = memory.read_raw(Labels::SCROLL_X) % 8;
+        self.lx = 0;
+        self.in_window = false;
+        self.sprite_stall = 0;
+
+        self.sprites = if lcd_controls & 0b0000_0010 != 0 {
+            scan_oam(memory, line, sprite_height(lcd_controls))
+        } else {
+            Vec::new()
+        };
+        sort_by_priority(&mut self.sprites);
+    }
+
+    /// Whether the window was drawn on this line
+    pub fn used_window(&self) -> bool {
+        self.in_window
+    }
+
+    /// Run for a single dot. Returns true once the last pixel of the line is drawn.
+    pub fn tick(&mut self, window_line: u8, screen_data: &mut [ScreenColor], memory: &Bus) -> bool {
+        if self.sprite_stall > 0 {
+            self.sprite_stall -= 1;
+            return false;
+        }
+
+        let lcd_controls = memory.read_raw(Labels::LCD_CONTROLS);
+
+        // Sprites are fetched once the discarded pixels are gone, when the line reaches them
+        // Each sprite pauses the fetcher, even when several start at the same x
+        let fetched = if self.discard == 0 {
+            self.fetch_sprites(lcd_controls, memory)
+        } else {
+            0
+        };
+        if fetched > 0 {
+            self.sprite_stall = SPRITE_FETCH_DOTS * fetched - 1;
+            return false;
+        }
+
+        // The window restarts the fetcher with an empty FIFO
+        if !self.in_window && self.window_starts(memory) {
+            self.in_window = true;
+            self.bg_fifo.clear();
+            self.step = FetchStep::TileNumber;
+            self.step_dots = 0;
+            self.fetcher_x = 0;
+
+            // The SCX fine scroll doesn't apply to the window.
+            // A WX below 7 starts the window off the left of the screen instead.
+            let window_x = memory.read_raw(Labels::WINDOW_X);
+            self.discard = 7u8.saturating_sub(window_x);
+        }
+
+        self.tick_fetcher(lcd_controls, window_line, memory);
+        self.push_pixel(lcd_controls, screen_data, memory)
+    }
+
+    fn window_starts(&self, memory: &Bus) -> bool {
+        let line = memory.read_raw(Labels::LCDC_Y);
+        let window_x = memory.read_raw(Labels::WINDOW_X);
+
+        is_window_visible(line, memory) && self.lx as u16 + 7 >= window_x as u16
+    }
+
+    /// Merge any sprites starting at the current x into the sprite FIFO.
+    /// Returns how many sprites were fetched.
+    fn fetch_sprites(&mut self, lcd_controls: u8, memory: &Bus) -> u32 {
+        if lcd_controls & 0b0000_0010 == 0 {
+            return 0;
+        }
+
+        let screen_x = self.lx as u16 + 8;
+        let (starting, remaining): (Vec<Sprite>, Vec<Sprite>) = self
+            .sprites
+            .iter()
+            .partition(|sprite| sprite.x as u16 <= screen_x);
+        if starting.is_empty() {
+            return 0;
+        }
+        self.sprites = remaining;
+        let fetched = starting.len() as u32;
+
+        let line = memory.read_raw(Labels::LCDC_Y);
+        let height = sprite_height(lcd_controls);
+        while self.obj_fifo.len() < 8 {
+            self.obj_fifo.push_back(ObjPixel::default());
+        }
+
+        for sprite in starting {
+            let y = line + 16 - sprite.y;
+            for x in 0..8u16 {
+                // Pixels of sprites off the left of the screen are dropped
+                let column = sprite.x as u16 + x;
+                if column < screen_x {
+                    continue;
+                }
+
+                // Sprites earlier in the FIFO keep their non transparent pixels
+                let slot = &mut self.obj_fifo[(column - screen_x) as usize];
+                if slot.value == 0 {
+                    *slot = ObjPixel {
+                        value: sprite.get_pixel_value(x as u8, y, height, memory),
+                        uses_palette_1: sprite.uses_palette_1(),
+                        behind_background: sprite.is_behind_background(),
+                    };
+                }
+            }
+        }
+
+        fetched
+    }
+
+    fn tick_fetcher(&mut self, lcd_controls: u8, window_line: u8, memory: &Bus) {
+        if self.step == FetchStep::Push {
+            if self.bg_fifo.is_empty() {
+                if !self.first_fetch {
+                    for bit in (0..8).rev() {
+                        let low = (self.data_low >> bit) & 1;
+                        let high = (self.data_high >> bit) & 1;
+                        self.bg_fifo.push_back(low | (high << 1));
+                    }
+                    self.fetcher_x = self.fetcher_x.wrapping_add(1);
+                }
+                self.first_fetch = false;
+                self.step = FetchStep::TileNumber;
+            }
+            return;
+        }
+
+        self.step_dots += 1;
+        if self.step_dots < 2 {
+            return;
+        }
+        self.step_dots = 0;
+
+        let line = memory.read_raw(Labels::LCDC_Y);
+        let (map, row) = if self.in_window {
+            (
+                TileMap::from_select_bit(lcd_controls, 0b0100_0000),
+                window_line,
+            )
+        } else {
+            (
+                TileMap::from_select_bit(lcd_controls, 0b0000_1000),
+                line.wrapping_add(memory.read_raw(Labels::SCROLL_Y)),
+            )
+        };
+
+        match self.step {
+            FetchStep::TileNumber => {
+                let column = if self.in_window {
+                    self.fetcher_x
+                } else {
+                    (memory.read_raw(Labels::SCROLL_X) / 8).wrapping_add(self.fetcher_x)
+                };
+                let index = (column % 32) as u16 + (row / 8) as u16 * 32;
+                self.tile_number = memory.read_raw(map.start() + index);
+                self.step = FetchStep::DataLow;
+            }
+            FetchStep::DataLow => {
+                let address = tile_row_address(lcd_controls, self.tile_number, row % 8);
+                self.data_low = memory.read_raw(address);
+                self.step = FetchStep::DataHigh;
+            }
+            FetchStep::DataHigh => {
+                let address = tile_row_address(lcd_controls, self.tile_number, row % 8);
+                self.data_high = memory.read_raw(address + 1);
+                self.step = FetchStep::Push;
+            }
+            FetchStep::Push => {}
+        }
+    }
+
+    fn push_pixel(
+        &mut self,
+        lcd_controls: u8,
+        screen_data: &mut [ScreenColor],
+        memory: &Bus,
+    ) -> bool {
+        let Some(bg_value) = self.bg_fifo.pop_front() else {
+            return false;
+        };
+
+        if self.discard > 0 {
+            self.discard -= 1;
+            return false;
+        }
+
+        let obj = self.obj_fifo.pop_front().unwrap_or_default();
+
+        // Palettes are read as each pixel is drawn so they can change partway through a line
+        let bg_value = if lcd_controls & 0b0000_0001 != 0 {
+            bg_value
+        } else {
+            0
+        };
+        let obj_visible = obj.value != 0
+            && lcd_controls & 0b0000_0010 != 0
+            && !(obj.behind_background && bg_value != 0);
+
+        let color = if obj_visible {
+            let palette = if obj.uses_palette_1 {
+                Labels::OBJ_PALETTE_1
+            } else {
+                Labels::OBJ_PALETTE_0
+            };
+            convert_base_to_color(memory.read_raw(palette))[obj.value as usize]
+        } else if lcd_controls & 0b0000_0001 != 0 {
+            convert_base_to_color(memory.read_raw(Labels::BG_PALETTE))[bg_value as usize]
+        } else {
+            ScreenColor::White
+        };
+
+        let line = memory.read_raw(Labels::LCDC_Y) as usize;
+        screen_data[self.lx as usize + line * 160] = color;
+        self.lx += 1;
+
+        self.lx == 160
+    }
+}
+
+/// The address of a row of a background or window tile using the LCDC.4 addressing mode
+fn tile_row_address(lcd_controls: u8, tile_number: u8, row: u8) -> u16 {
+    let tile_start = if lcd_controls & 0b0001_0000 != 0 {
+        Labels::CHARACTER_RAM_START + tile_number as u16 * 16
+    } else {
+        Labels::CHARACTER_RAM_START_BLOCK_2.wrapping_add(((tile_number as i8) as i16 * 16) as u16)
+    };
+
+    tile_start + row as u16 * 2
+}
@@ -1,16 +1,79 @@
 use super::Labels;
 use super::ScreenColor;
-use super::memory_view::MemoryView;
+use super::bus::Bus;
+use super::interrupt_controller::{Interrupt, request_interrupt};
+use super::pixel_fifo::PixelFifo;
+use super::sprite::{scan_oam, sort_by_priority, sprite_height};
+
+const DOTS_PER_LINE: u32 = 456;
+const OAM_SCAN_DOTS: u32 = 80;
+/// Drawing takes at least this long & gets longer with scrolling, sprites & the window
+const MIN_DRAWING_DOTS: u32 = 172;
+const VBLANK_START_LINE: u8 = 144;
+const LINES_PER_FRAME: u8 = 154;
+
+/// The modes the PPU moves through, as reported in the bottom 2 bits of STAT
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Mode {
+    HBlank = 0,
+    VBlank = 1,
+    OamScan = 2,
+    Drawing = 3,
+}
+
+/// How the PPU turns VRAM into pixels
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Renderer {
+    /// Draw each line in one go at the end of drawing. This is fast but misses
+    /// registers being changed partway through a line.
+    Scanline,
+    /// Model the background & sprite fetchers & the pixel FIFO a dot at a time
+    PixelFifo,
+}
 
 #[allow(clippy::upper_case_acronyms)]
 pub struct PPU {
     screen_data: Vec<ScreenColor>,
     bg_palette: [ScreenColor; 4],
-    vblank_triggered: bool,
+    mode: Mode,
+    /// The dot within the current line
     cycles: u32,
+    /// The dot the drawing mode finishes on for the current line
+    drawing_end: u32,
+    /// The STAT interrupt is only requested when this goes from low to high
+    stat_line: bool,
+    /// The window has its own line counter which only moves on lines where the window was drawn
+    window_line: u8,
+    /// Only used by the pixel FIFO renderer
+    pixel_fifo: Option<PixelFifo>,
+}
+
+/// The two 32x32 tile maps in VRAM
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TileMap {
+    Map9800,
+    Map9C00,
+}
+
+impl TileMap {
+    /// Pick the map using one of the LCDC select bits
+    pub fn from_select_bit(lcd_controls: u8, select_bit: u8) -> TileMap {
+        if lcd_controls & select_bit != 0 {
+            TileMap::Map9C00
+        } else {
+            TileMap::Map9800
+        }
+    }
+
+    pub fn start(self) -> u16 {
+        match self {
+            TileMap::Map9800 => Labels::BG_MAP_DATA_1_START,
+            TileMap::Map9C00 => Labels::BG_MAP_DATA_2_START,
+        }
+    }
 }
 
-fn convert_base_to_color(palette_base: u8) -> [ScreenColor; 4] {
+pub fn convert_base_to_color(palette_base: u8) -> [ScreenColor; 4] {
     let mut bg_palette = [
         ScreenColor::White,
         ScreenColor::White,
@@ -43,8 +106,12 @@ impl PPU {
         PPU {
             screen_data,
             bg_palette,
-            vblank_triggered: false,
+            mode: Mode::OamScan,
             cycles: 0,
+            drawing_end: OAM_SCAN_DOTS + MIN_DRAWING_DOTS,
+            stat_line: false,
+            window_line: 0,
+            pixel_fifo: None,
         }
     }
 
@@ -83,18 +150,13 @@ impl PPU {
         pixels
     }
 
-    pub fn get_vram_data(&self, memory: &[u8]) -> Vec<ScreenColor> {
-        let mem_view = MemoryView::new(memory);
+    pub fn get_vram_data(&self, map: TileMap, memory: &Bus) -> Vec<ScreenColor> {
         let mut vram = vec![ScreenColor::White; 256 * 256];
 
-        // Loop through $9800-$9BFF - BG Map Data 1 to see all the sprites on screen
+        // Loop through the map to see all the sprites on screen
         for map_index in 0..1024 {
-            // Get the value in vram for this index
-            let index = mem_view.get_memory_at(Labels::BG_MAP_DATA_1_START + map_index as u16);
-
             // For each point check the tile at that index
-            let sprite_data =
-                mem_view.get_memory_slice_at(Labels::CHARACTER_RAM_START + (index as u16 * 16), 16);
+            let sprite_data = get_tile_data(map.start(), map_index as u16, memory);
 
             // Render the sprite into the VRAM
             for i in 0..8 {
@@ -119,77 +181,294 @@ impl PPU {
         self.bg_palette = convert_base_to_color(value);
     }
 
-    pub fn tick(&mut self, cycles: u32, memory: &mut [u8]) {
+    pub fn set_renderer(&mut self, renderer: Renderer) {
+        self.pixel_fifo = match renderer {
+            Renderer::Scanline => None,
+            Renderer::PixelFifo => Some(PixelFifo::new()),
+        };
+    }
+
+    pub fn tick(&mut self, cycles: u32, memory: &mut Bus) {
         // Get bit 7 (8th)
-        let bit_7_set = (memory[Labels::LCD_CONTROLS as usize] & 0b1000_0000) != 0;
+        let bit_7_set = (memory.read_raw(Labels::LCD_CONTROLS) & 0b1000_0000) != 0;
         let is_screen_on = bit_7_set;
 
-        if is_screen_on {
-            let new_cycles = self.cycles + cycles;
+        if !is_screen_on {
+            // Reset the LY register & start from the top of the screen when turned back on.
+            // STAT reports HBlank while the screen is off.
+            memory.write_raw(Labels::LCDC_Y, 0);
+            let status = memory.read_raw(Labels::LCD_STATUS) & 0b1111_1100;
+            memory.write_raw(Labels::LCD_STATUS, status);
+            self.mode = Mode::OamScan;
+            self.cycles = 0;
+            self.stat_line = false;
+            return;
+        }
+
+        // Step through each mode change within the cycles
+        let mut remaining = cycles;
+        while remaining > 0 {
+            // The pixel FIFO decides when drawing ends so it's run a dot at a time
+            if self.mode == Mode::Drawing && self.pixel_fifo.is_some() {
+                self.cycles += 1;
+                remaining -= 1;
+
+                if self.tick_pixel_fifo(memory) {
+                    self.mode = Mode::HBlank;
+                }
+                self.update_status(memory);
+                continue;
+            }
+
+            let mode_end = self.mode_end();
+            let step = remaining.min(mode_end - self.cycles);
+            self.cycles += step;
+            remaining -= step;
+
+            if self.cycles == mode_end {
+                self.next_mode(memory);
+            }
+            self.update_status(memory);
+        }
+    }
+
+    fn mode_end(&self) -> u32 {
+        match self.mode {
+            Mode::OamScan => OAM_SCAN_DOTS,
+            Mode::Drawing => self.drawing_end,
+            Mode::HBlank | Mode::VBlank => DOTS_PER_LINE,
+        }
+    }
+
+    fn next_mode(&mut self, memory: &mut Bus) {
+        match self.mode {
+            Mode::OamScan => {
+                if memory.read_raw(Labels::LCDC_Y) == 0 {
+                    self.window_line = 0;
+                }
 
-            if new_cycles >= 456 {
+                match &mut self.pixel_fifo {
+                    Some(pixel_fifo) => pixel_fifo.start_line(memory),
+                    None => self.drawing_end = OAM_SCAN_DOTS + drawing_length(memory),
+                }
+                self.mode = Mode::Drawing;
+            }
+            Mode::Drawing => {
+                // LY can only be outside the screen here if it was written to directly
+                if memory.read_raw(Labels::LCDC_Y) < VBLANK_START_LINE {
+                    self.draw_line(memory);
+                }
+                self.mode = Mode::HBlank;
+            }
+            Mode::HBlank | Mode::VBlank => {
                 // increment the LY register
-                let ly = memory[Labels::LCDC_Y as usize];
-                let new_ly = (ly + 1) % 154;
-                memory[Labels::LCDC_Y as usize] = new_ly;
+                let ly = memory.read_raw(Labels::LCDC_Y);
+                let new_ly = (ly + 1) % LINES_PER_FRAME;
+                memory.write_raw(Labels::LCDC_Y, new_ly);
+                self.cycles = 0;
 
-                // The ly has flipped around so vblank can be triggered again
-                if new_ly < ly {
-                    self.vblank_triggered = false;
+                if new_ly < VBLANK_START_LINE {
+                    self.mode = Mode::OamScan;
+                } else {
+                    if new_ly == VBLANK_START_LINE {
+                        request_interrupt(memory, Interrupt::VBlank);
+                    }
+                    self.mode = Mode::VBlank;
                 }
+            }
+        }
+    }
 
-                if new_ly <= 144 {
-                    // Write a line into the screen data starting at LCDC_Y - 1
+    /// Write the mode & LY=LYC bits into STAT & request the STAT interrupt.
+    ///
+    /// All the enabled sources are ORed onto one line & the interrupt is only requested
+    /// when the line goes high. While one source holds the line high the others are
+    /// blocked which is the DMG "STAT blocking" behaviour.
+    pub fn update_status(&mut self, memory: &mut Bus) {
+        let status = memory.read_raw(Labels::LCD_STATUS);
+        let coincidence = memory.read_raw(Labels::LCDC_Y) == memory.read_raw(Labels::LY_COMPARE);
+
+        let new_status = (status & 0b1111_1000) | ((coincidence as u8) << 2) | self.mode as u8;
+        memory.write_raw(Labels::LCD_STATUS, new_status);
+
+        let mode_source = match self.mode {
+            Mode::HBlank => status & 0b0000_1000 != 0,
+            Mode::VBlank => status & 0b0001_0000 != 0,
+            Mode::OamScan => status & 0b0010_0000 != 0,
+            Mode::Drawing => false,
+        };
+        let coincidence_source = coincidence && status & 0b0100_0000 != 0;
+
+        let stat_line = mode_source || coincidence_source;
+        if stat_line && !self.stat_line {
+            request_interrupt(memory, Interrupt::LcdStat);
+        }
+        self.stat_line = stat_line;
+    }
 
-                    // Which line are we drawing
-                    let drawing_line = new_ly.saturating_sub(1);
+    /// Returns true when the line has been drawn
+    fn tick_pixel_fifo(&mut self, memory: &Bus) -> bool {
+        let Some(pixel_fifo) = &mut self.pixel_fifo else {
+            return true;
+        };
 
-                    // This would be where we pick which pixels we want from VRAM
+        // LY can only be outside the screen here if it was written to directly
+        if memory.read_raw(Labels::LCDC_Y) >= VBLANK_START_LINE {
+            return true;
+        }
+
+        let finished = pixel_fifo.tick(self.window_line, &mut self.screen_data, memory);
+        if finished && pixel_fifo.used_window() {
+            self.window_line += 1;
+        }
 
-                    // Find the screen x & screen y
-                    let screen_origin_x = memory[Labels::SCROLL_X as usize] as u16;
-                    let screen_origin_y = memory[Labels::SCROLL_Y as usize] as u16;
+        finished
+    }
 
-                    // for each pixel in line
-                    for pixel in 0..160 {
-                        // Find the coord in the screen data we are writing
-                        let pixel_index = pixel + drawing_line as u32 * 160;
+    fn draw_line(&mut self, memory: &Bus) {
+        // Which line are we drawing
+        let drawing_line = memory.read_raw(Labels::LCDC_Y);
 
-                        // Find the pixel in vram
-                        let vram_x = ((screen_origin_x + pixel as u16) % 255) as u32;
-                        let vram_y = ((screen_origin_y + drawing_line as u16) % 255) as u32;
+        // Find the screen x & screen y
+        let screen_origin_x = memory.read_raw(Labels::SCROLL_X);
+        let screen_origin_y = memory.read_raw(Labels::SCROLL_Y);
 
-                        let tile_index = find_tile_index(vram_x, vram_y);
-                        let tile_bytes = get_tile_data(tile_index, memory);
+        // On the DMG LCDC.0 blanks both the background & the window
+        let lcd_controls = memory.read_raw(Labels::LCD_CONTROLS);
+        let bg_enabled = lcd_controls & 0b0000_0001 != 0;
+        let bg_map = TileMap::from_select_bit(lcd_controls, 0b0000_1000);
 
-                        // Find the pixel within the tile that the screen is looking at
-                        let inside_tile_x = (vram_x % 8) as u8;
-                        let inside_tile_y = (vram_y % 8) as u8;
+        let window_x = memory.read_raw(Labels::WINDOW_X) as u16;
+        let window_visible = is_window_visible(drawing_line, memory);
+        let window_map = TileMap::from_select_bit(lcd_controls, 0b0100_0000);
 
-                        let pixel_value =
-                            get_pixel_value_from_sprite(inside_tile_x, inside_tile_y, tile_bytes);
-                        let pixel_color = self.bg_palette[pixel_value as usize];
-                        self.screen_data[pixel_index as usize] = pixel_color;
-                    }
-                } else {
-                    // Set vblank interrupt but not if already done
-                    if !self.vblank_triggered {
-                        // Trigger vblank
-                        memory[Labels::INTERRUPT_TRIGGER as usize] =
-                            memory[Labels::INTERRUPT_TRIGGER as usize] | 0b0000_0001;
-                        self.vblank_triggered = true;
-                    }
-                }
+        // The color values before the palette is applied.
+        // Sprites need these to know if they are behind the background.
+        let mut bg_values = [0u8; 160];
+
+        // for each pixel in line
+        for pixel in 0..160 {
+            // Find the coord in the screen data we are writing
+            let pixel_index = pixel + drawing_line as u32 * 160;
+
+            if !bg_enabled {
+                self.screen_data[pixel_index as usize] = ScreenColor::White;
+                continue;
             }
 
-            self.cycles = new_cycles % 456;
-        } else {
-            // Reset the LY register
-            memory[Labels::LCDC_Y as usize] = 0;
+            // Find the pixel in vram
+            let in_window = window_visible && pixel as u16 + 7 >= window_x;
+            let (vram_x, vram_y, map) = if in_window {
+                (
+                    pixel + 7 - window_x as u32,
+                    self.window_line as u32,
+                    window_map,
+                )
+            } else {
+                // The background wraps around the 256x256 map
+                (
+                    screen_origin_x.wrapping_add(pixel as u8) as u32,
+                    screen_origin_y.wrapping_add(drawing_line) as u32,
+                    bg_map,
+                )
+            };
+
+            let tile_index = find_tile_index(vram_x, vram_y);
+            let tile_bytes = get_tile_data(map.start(), tile_index, memory);
+
+            // Find the pixel within the tile that the screen is looking at
+            let inside_tile_x = (vram_x % 8) as u8;
+            let inside_tile_y = (vram_y % 8) as u8;
+
+            let pixel_value = get_pixel_value_from_sprite(inside_tile_x, inside_tile_y, tile_bytes);
+            let pixel_color = self.bg_palette[pixel_value as usize];
+            self.screen_data[pixel_index as usize] = pixel_color;
+            bg_values[pixel as usize] = pixel_value;
+        }
+
+        if window_visible {
+            self.window_line += 1;
+        }
+
+        let sprites_enabled = lcd_controls & 0b0000_0010 != 0;
+        if sprites_enabled {
+            self.draw_sprites(drawing_line, &bg_values, memory);
+        }
+    }
+
+    fn draw_sprites(&mut self, line: u8, bg_values: &[u8; 160], memory: &Bus) {
+        let height = sprite_height(memory.read_raw(Labels::LCD_CONTROLS));
+        let mut sprites = scan_oam(memory, line, height);
+        sort_by_priority(&mut sprites);
+
+        let palettes = [
+            convert_base_to_color(memory.read_raw(Labels::OBJ_PALETTE_0)),
+            convert_base_to_color(memory.read_raw(Labels::OBJ_PALETTE_1)),
+        ];
+
+        for pixel in 0..160u16 {
+            // Sprite X is offset by 8 so sprites can be partially off the left of the screen
+            let screen_x = pixel + 8;
+
+            // The highest priority sprite with a non transparent pixel is drawn
+            let found = sprites.iter().find_map(|sprite| {
+                let left = sprite.x as u16;
+                if screen_x < left || screen_x >= left + 8 {
+                    return None;
+                }
+
+                let x = (screen_x - left) as u8;
+                let y = line + 16 - sprite.y;
+                match sprite.get_pixel_value(x, y, height, memory) {
+                    0 => None,
+                    value => Some((sprite, value)),
+                }
+            });
+
+            if let Some((sprite, value)) = found {
+                if sprite.is_behind_background() && bg_values[pixel as usize] != 0 {
+                    continue;
+                }
+
+                let palette = &palettes[sprite.uses_palette_1() as usize];
+                let pixel_index = pixel as usize + line as usize * 160;
+                self.screen_data[pixel_index] = palette[value as usize];
+            }
         }
     }
 }
 
+/// How many dots the drawing mode takes for the current line.
+/// The background fetch discards SCX % 8 pixels & each sprite & the window pause the fetcher.
+fn drawing_length(memory: &Bus) -> u32 {
+    let lcd_controls = memory.read_raw(Labels::LCD_CONTROLS);
+    let line = memory.read_raw(Labels::LCDC_Y);
+
+    let scroll_penalty = (memory.read_raw(Labels::SCROLL_X) % 8) as u32;
+    let sprite_penalty = if lcd_controls & 0b0000_0010 != 0 {
+        scan_oam(memory, line, sprite_height(lcd_controls)).len() as u32 * 6
+    } else {
+        0
+    };
+    let window_penalty = if is_window_visible(line, memory) {
+        6
+    } else {
+        0
+    };
+
+    MIN_DRAWING_DOTS + scroll_penalty + sprite_penalty + window_penalty
+}
+
+/// The window starts at WX - 7 & is hidden if WX is past the edge of the screen
+pub fn is_window_visible(line: u8, memory: &Bus) -> bool {
+    let lcd_controls = memory.read_raw(Labels::LCD_CONTROLS);
+    let window_enabled = lcd_controls & 0b0010_0001 == 0b0010_0001;
+    let window_x = memory.read_raw(Labels::WINDOW_X);
+    let window_y = memory.read_raw(Labels::WINDOW_Y);
+
+    window_enabled && line >= window_y && window_x <= 166
+}
+
 fn find_tile_index(vram_x: u32, vram_y: u32) -> u16 {
     // Find which tile it is
     let tile_x = vram_x / 8;
@@ -199,7 +478,7 @@ fn find_tile_index(vram_x: u32, vram_y: u32) -> u16 {
     (tile_x + tile_y * 32) as u16
 }
 
-fn get_tile_data(tile_index: u16, memory: &[u8]) -> &[u8] {
+fn get_tile_data(map_start: u16, tile_index: u16, memory: &Bus) -> &[u8] {
     /*
     This is more complicated depending on the addressing mode LCDC.4:
     if 0 then we use signed addressing or:
@@ -209,28 +488,19 @@ fn get_tile_data(tile_index: u16, memory: &[u8]) -> &[u8] {
         0-127 means block 0 (0x8000-87FF)
         128-255 means block 1 (0x8800-8FFF)
     */
-    // Get the tile_data_start. Assuming LCDC.3 == 0 & LCDC.4 == 1
-    let is_mode_8000 = memory[Labels::LCD_CONTROLS as usize] & 0b0001_0000 != 0;
+    let is_mode_8000 = memory.read_raw(Labels::LCD_CONTROLS) & 0b0001_0000 != 0;
     if is_mode_8000 {
-        let tile_data_start = (Labels::CHARACTER_RAM_START
-            + memory[(Labels::BG_MAP_DATA_1_START + tile_index) as usize] as u16 * 16)
-            as usize;
+        let tile_data_start =
+            Labels::CHARACTER_RAM_START + memory.read_raw(map_start + tile_index) as u16 * 16;
 
-        &memory[tile_data_start..(tile_data_start + 16)]
+        memory.read_video_ram_slice(tile_data_start, 16)
     } else {
-        let tile_index_value = memory[(Labels::BG_MAP_DATA_1_START + tile_index) as usize];
+        let tile_index_value = memory.read_raw(map_start + tile_index);
 
-        let tile_data_start =
-            (Labels::CHARACTER_RAM_START_BLOCK_2 + (tile_index_value as i8) as u16 * 16) as usize;
-        &memory[tile_data_start..(tile_data_start + 16)]
+        let tile_data_start = Labels::CHARACTER_RAM_START_BLOCK_2
+            .wrapping_add(((tile_index_value as i8) as i16 * 16) as u16);
+        memory.read_video_ram_slice(tile_data_start, 16)
     }
-
-    // old code
-    // let tile_data_start = (Labels::CHARACTER_RAM_START
-    //     + memory[(Labels::BG_MAP_DATA_1_START + tile_index) as usize] as u16 * 16)
-    //     as usize;
-
-    // &memory[tile_data_start..(tile_data_start + 16)]
 }
 
 fn get_pixel_value_from_sprite(x: u8, y: u8, sprite_data: &[u8]) -> u8 {
@@ -0,0 +1,107 @@
+use super::bus::Bus;
+use super::Labels;
+
+/// The hardware only fetches this many sprites on each line
+pub const MAX_SPRITES_PER_LINE: usize = 10;
+
+const OAM_ENTRIES: u16 = 40;
+
+/// An entry in OAM at 0xFE00-0xFE9F
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Sprite {
+    /// Screen Y + 16
+    pub y: u8,
+    /// Screen X + 8
+    pub x: u8,
+    pub tile: u8,
+    pub flags: u8,
+    pub oam_index: u8,
+}
+
+impl Sprite {
+    fn from_oam(memory: &Bus, oam_index: u16) -> Sprite {
+        let address = Labels::OAM_START + oam_index * 4;
+        Sprite {
+            y: memory.read_raw(address),
+            x: memory.read_raw(address + 1),
+            tile: memory.read_raw(address + 2),
+            flags: memory.read_raw(address + 3),
+            oam_index: oam_index as u8,
+        }
+    }
+
+    /// BG & window colors 1-3 are drawn over the sprite
+    pub fn is_behind_background(&self) -> bool {
+        self.flags & 0b1000_0000 != 0
+    }
+
+    pub fn is_y_flipped(&self) -> bool {
+        self.flags & 0b0100_0000 != 0
+    }
+
+    pub fn is_x_flipped(&self) -> bool {
+        self.flags & 0b0010_0000 != 0
+    }
+
+    /// OBP1 is used instead of OBP0
+    pub fn uses_palette_1(&self) -> bool {
+        self.flags & 0b0001_0000 != 0
+    }
+
+    /// Get the 2 bit color of a pixel in the sprite.
+    /// The x & y are relative to the top left of the sprite.
+    pub fn get_pixel_value(&self, x: u8, y: u8, height: u8, memory: &Bus) -> u8 {
+        let y = if self.is_y_flipped() {
+            height - 1 - y
+        } else {
+            y
+        };
+        let x = if self.is_x_flipped() { 7 - x } else { x };
+
+        // In 8x16 mode the bottom bit of the tile index is ignored
+        let tile = if height == 16 {
+            self.tile & 0xFE
+        } else {
+            self.tile
+        };
+
+        // Sprites always use the 0x8000 addressing mode
+        let row_address = Labels::CHARACTER_RAM_START + tile as u16 * 16 + y as u16 * 2;
+        let row = memory.read_video_ram_slice(row_address, 2);
+
+        let ls_bit = (row[0] >> (7 - x)) & 1;
+        let ms_bit = (row[1] >> (7 - x)) & 1;
+
+        ls_bit | (ms_bit << 1)
+    }
+}
+
+/// Height of all sprites set by LCDC.2
+pub fn sprite_height(lcd_controls: u8) -> u8 {
+    if lcd_controls & 0b0000_0100 != 0 {
+        16
+    } else {
+        8
+    }
+}
+
+/// Find the first 10 sprites in OAM which are on the line.
+/// Only the Y position is checked so sprites off the side of the screen still count.
+pub fn scan_oam(memory: &Bus, line: u8, height: u8) -> Vec<Sprite> {
+    let line = line as u16 + 16;
+
+    (0..OAM_ENTRIES)
+        .map(|oam_index| Sprite::from_oam(memory, oam_index))
+        .filter(|sprite| {
+            let top = sprite.y as u16;
+            line >= top && line < top + height as u16
+        })
+        .take(MAX_SPRITES_PER_LINE)
+        .collect()
+}
+
+/// On the DMG the sprite with the smallest X is drawn on top.
+/// If the X matches then the sprite earlier in OAM wins.
+pub fn sort_by_priority(sprites: &mut [Sprite]) {
+    sprites.sort_by_key(|sprite| (sprite.x, sprite.oam_index));
+}
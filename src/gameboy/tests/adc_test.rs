@@ -1,7 +1,9 @@
 use crate::gameboy::cpu::CPU;
 use crate::gameboy::memory_adapter::MemoryAdapter;
 use crate::gameboy::opcodes::{Argument, Category};
-use crate::gameboy::{read_flag, write_flag, Flags, OpCode, RegisterLabel8};
+use crate::gameboy::{
+    read_flag, write_flag, Flags, Gameboy, OpCode, RegisterLabel16, RegisterLabel8,
+};
 
 use super::decode_util::decode;
 
@@ -137,3 +139,62 @@ fn test_adc_adds_carry_when_both_operands_are_ff() {
     assert_eq!(read_flag(&cpu, Flags::Z), false);
     assert_eq!(cpu.read_8_bits(RegisterLabel8::A), 0xFF);
 }
+
+#[test]
+fn adc_includes_the_carry_in_the_half_carry() {
+    let opcode = OpCode::new(
+        Category::ADC,
+        [
+            Argument::Register8Constant(RegisterLabel8::B),
+            Argument::None,
+        ],
+    );
+
+    let mut cpu = CPU::new();
+    let mut memory = vec![0; 0xFFFF];
+
+    // 0x0F + 0x00 + 1 = 0x10
+    cpu.write_8_bits(RegisterLabel8::A, 0x0F);
+    cpu.write_8_bits(RegisterLabel8::B, 0x00);
+    write_flag(&mut cpu, Flags::C, true);
+
+    opcode.run(&mut cpu, MemoryAdapter::new(&mut memory));
+
+    assert_eq!(cpu.read_8_bits(RegisterLabel8::A), 0x10);
+    assert!(read_flag(&cpu, Flags::H));
+    assert!(!read_flag(&cpu, Flags::C));
+}
+
+#[test]
+fn adc_hl_and_d8_instructions() {
+    assert_eq!(
+        decode(&[0x8E]),
+        OpCode::new(
+            Category::ADC,
+            [
+                Argument::RegisterIndirect(RegisterLabel16::HL),
+                Argument::None
+            ]
+        )
+    );
+    assert_eq!(
+        decode(&[0xCE, 0x02]),
+        OpCode::new(Category::ADC, [Argument::SmallValue(0x02), Argument::None])
+    );
+
+    // ADC (HL), ADC d8
+    let mut gb = Gameboy::new(vec![0x8E, 0xCE, 0x02]);
+    gb.set_register_8(RegisterLabel8::A, 0x01);
+    gb.set_register_16(RegisterLabel16::HL, 0x4000);
+    gb.set_memory_at(0x4000, 0x01);
+    gb.set_flag(Flags::C, true);
+
+    let cycles = gb.step_once().unwrap();
+    assert_eq!(cycles, 8);
+    assert_eq!(gb.get_register_8(RegisterLabel8::A), 0x03);
+
+    let cycles = gb.step_once().unwrap();
+    assert_eq!(cycles, 8);
+    assert_eq!(gb.get_register_8(RegisterLabel8::A), 0x05);
+    assert_eq!(gb.get_register_16(RegisterLabel16::ProgramCounter), 0x03);
+}
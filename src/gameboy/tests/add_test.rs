@@ -261,3 +261,49 @@ fn add_16_sets_the_carry_flag() {
     // The wrapped value should be in HL
     assert_eq!(cpu.read_16_bits(RegisterLabel16::HL), 0);
 }
+
+#[test]
+fn add_sp_r8_adds_a_signed_offset() {
+    assert_eq!(
+        decode(&[0xE8, 0xFE]),
+        OpCode::new(
+            Category::ADD16,
+            [
+                Argument::Register16Constant(RegisterLabel16::StackPointer),
+                Argument::JumpDistance(-2)
+            ]
+        )
+    );
+
+    let mut gb = Gameboy::new(vec![0xE8, 0xFE, 0xE8, 0x01]);
+    gb.set_register_16(RegisterLabel16::StackPointer, 0xFFF8);
+    gb.set_flag(Flags::Z, true);
+    gb.set_flag(Flags::N, true);
+
+    let cycles = gb.step_once().unwrap();
+
+    assert_eq!(cycles, 16);
+    assert_eq!(gb.get_register_16(RegisterLabel16::ProgramCounter), 0x02);
+    assert_eq!(gb.get_register_16(RegisterLabel16::StackPointer), 0xFFF6);
+
+    // Z & N are always reset. The carries come from the lower byte
+    assert!(!gb.get_flag(Flags::Z));
+    assert!(!gb.get_flag(Flags::N));
+    assert!(gb.get_flag(Flags::H));
+    assert!(gb.get_flag(Flags::C));
+
+    // 0xFFF6 + 1 doesn't carry out of either nibble
+    gb.step_once();
+    assert_eq!(gb.get_register_16(RegisterLabel16::StackPointer), 0xFFF7);
+    assert!(!gb.get_flag(Flags::H));
+    assert!(!gb.get_flag(Flags::C));
+}
+
+#[test]
+fn add_h_flag_is_set_from_the_lower_nibble() {
+    let mut gb = add_fixture_gb(0x86, 0x18, 0x08);
+    gb.step_once();
+
+    assert_eq!(gb.get_register_8(RegisterLabel8::A), 0x20);
+    assert!(gb.get_flag(Flags::H));
+}
@@ -1,14 +1,19 @@
-use crate::gameboy::Gameboy;
+use crate::gameboy::{Gameboy, Labels, StereoFrame};
 
 #[allow(dead_code)]
 pub fn infinite_loop_gb<'a, F>(callback: F) -> Gameboy<'a>
 where
-    F: FnMut(i16) + 'a,
+    F: FnMut(StereoFrame) + 'a,
 {
     // Each loop will be 16 clocks & take 2 steps
     // NOP
     // JR -3
-    let gb = Gameboy::new_with_audio(vec![0x00, 0x18, 0xFD], callback);
+    let mut gb = Gameboy::new_with_audio(vec![0x00, 0x18, 0xFD], callback);
+
+    // Power on the APU with every channel at full volume in both speakers
+    gb.set_memory_at(Labels::SOUND_ON, 0b1000_0000);
+    gb.set_memory_at(Labels::SOUND_VOLUME, 0b0111_0111);
+    gb.set_memory_at(Labels::SOUND_PANNING, 0b1111_1111);
     gb
 }
 
@@ -18,8 +23,8 @@ fn can_construct_gb_with_alu() {
 
     {
         // Put everything in scope to allow us to query audio_data
-        let callback = |val| {
-            audio_data.push(val);
+        let callback = |frame: StereoFrame| {
+            audio_data.push(frame.left);
         };
 
         let mut gb = infinite_loop_gb(callback);
@@ -47,8 +52,8 @@ fn trigger_bit_is_reset_after_frame() {
 fn no_sound_if_volume_0() {
     let mut audio_data: Vec<i16> = Vec::new();
     {
-        let mut gb = infinite_loop_gb(|val| {
-            audio_data.push(val);
+        let mut gb = infinite_loop_gb(|frame: StereoFrame| {
+            audio_data.push(frame.left);
         });
 
         // enable sound
@@ -64,8 +69,8 @@ fn no_sound_if_volume_0() {
 fn setting_volume_enables_output() {
     let mut audio_data: Vec<i16> = Vec::new();
     {
-        let mut gb = infinite_loop_gb(|val| {
-            audio_data.push(val);
+        let mut gb = infinite_loop_gb(|frame: StereoFrame| {
+            audio_data.push(frame.left);
         });
 
         // Set the volume to max
@@ -84,8 +89,8 @@ fn setting_volume_enables_output() {
 fn run_gb_with_settings(vol: u8, freq: u16, duty: u8, period: u8) -> Vec<i16> {
     let mut audio_data: Vec<i16> = Vec::new();
     {
-        let mut gb = infinite_loop_gb(|val| {
-            audio_data.push(val);
+        let mut gb = infinite_loop_gb(|frame: StereoFrame| {
+            audio_data.push(frame.left);
         });
 
         // Set the duty
@@ -166,3 +171,365 @@ fn period_reduces_volume() {
     assert!(no_zeros.len() == 2);
     assert!(no_zeros[0] > no_zeros[1]);
 }
+
+#[test]
+fn channel_2_uses_its_own_registers() {
+    let mut audio_data: Vec<i16> = Vec::new();
+    {
+        let mut gb = infinite_loop_gb(|frame: StereoFrame| {
+            audio_data.push(frame.left);
+        });
+
+        // Set the volume to max & enable sound 2
+        gb.set_memory_at(0xFF17, 0b1111_0000);
+        gb.set_memory_at(0xFF19, 0b1000_0000);
+        gb.tick(1.0 / 60.0);
+
+        // The trigger bit is reset & channel 1 is untouched
+        assert_eq!(gb.get_memory_at(0xFF19) & 0b1000_0000, 0b0000_0000);
+        assert_eq!(gb.get_memory_at(0xFF14), 0b0000_0000);
+    }
+
+    assert!(audio_data.iter().any(|&val| val != 0));
+}
+
+#[test]
+fn both_square_channels_are_mixed() {
+    let mut audio_data: Vec<i16> = Vec::new();
+    {
+        let mut gb = infinite_loop_gb(|frame: StereoFrame| {
+            audio_data.push(frame.left);
+        });
+
+        // Duty 75% on both channels at volume 15
+        gb.set_memory_at(0xFF11, 0b1100_0000);
+        gb.set_memory_at(0xFF12, 0b1111_0000);
+        gb.set_memory_at(0xFF16, 0b1100_0000);
+        gb.set_memory_at(0xFF17, 0b1111_0000);
+
+        gb.set_memory_at(0xFF14, 0b1000_0000);
+        gb.set_memory_at(0xFF19, 0b1000_0000);
+        gb.tick(1.0 / 60.0);
+    }
+
+    assert_eq!(audio_data.iter().max(), Some(&30));
+}
+
+#[test]
+fn length_turns_the_channel_off() {
+    let mut audio_data: Vec<i16> = Vec::new();
+    {
+        let mut gb = infinite_loop_gb(|frame: StereoFrame| {
+            audio_data.push(frame.left);
+        });
+
+        // A length of 1 lasts 1/256th of a second
+        gb.set_memory_at(0xFF16, 0b1100_0000 | 63);
+        gb.set_memory_at(0xFF17, 0b1111_0000);
+
+        // enable sound 2 with the length enabled & a high frequency
+        gb.set_memory_at(0xFF19, 0b1100_0111);
+        gb.tick(1.0 / 60.0);
+    }
+
+    let end = audio_data.len() / 2;
+    assert!(audio_data[..100].iter().any(|&val| val != 0));
+    assert!(audio_data[end..].iter().all(|&val| val == 0));
+}
+
+#[test]
+fn envelope_can_increase_the_volume() {
+    let audio = {
+        let mut audio_data: Vec<i16> = Vec::new();
+        {
+            let mut gb = infinite_loop_gb(|frame: StereoFrame| {
+                audio_data.push(frame.left);
+            });
+
+            // Start at volume 0 & increase every 1/64th of a second
+            gb.set_memory_at(0xFF16, 0b1100_0000);
+            gb.set_memory_at(0xFF17, 0b0000_1001);
+            gb.set_memory_at(0xFF19, 0b1000_0000);
+            gb.tick(1.0 / 30.0);
+        }
+        audio_data
+    };
+
+    let mut no_zeros: Vec<i16> = audio.into_iter().filter(|v| *v != 0).collect();
+    no_zeros.dedup();
+
+    assert_eq!(no_zeros, vec![1, 2]);
+}
+
+fn run_wave_channel(nr30: u8, nr31: u8, nr32: u8, nr34: u8) -> Vec<i16> {
+    let mut audio_data: Vec<i16> = Vec::new();
+    {
+        let mut gb = infinite_loop_gb(|frame: StereoFrame| {
+            audio_data.push(frame.left);
+        });
+
+        // A saw wave from 0 to 15
+        for i in 0..16 {
+            gb.set_memory_at(0xFF30 + i, ((2 * i as u8) << 4) | (2 * i as u8 + 1));
+        }
+
+        gb.set_memory_at(0xFF1A, nr30);
+        gb.set_memory_at(0xFF1B, nr31);
+        gb.set_memory_at(0xFF1C, nr32);
+        gb.set_memory_at(0xFF1D, 0x00);
+        gb.set_memory_at(0xFF1E, nr34);
+        gb.tick(1.0 / 60.0);
+
+        assert_eq!(gb.get_memory_at(0xFF1E) & 0b1000_0000, 0b0000_0000);
+    }
+    audio_data
+}
+
+#[test]
+fn wave_channel_plays_wave_ram() {
+    let audio = run_wave_channel(0b1000_0000, 0, 0b0010_0000, 0b1000_0111);
+
+    assert_eq!(audio.iter().max(), Some(&15));
+    assert_eq!(audio.iter().min(), Some(&0));
+}
+
+#[test]
+fn wave_channel_output_level_shifts_the_samples() {
+    let half = run_wave_channel(0b1000_0000, 0, 0b0100_0000, 0b1000_0111);
+    assert_eq!(half.iter().max(), Some(&7));
+
+    let quarter = run_wave_channel(0b1000_0000, 0, 0b0110_0000, 0b1000_0111);
+    assert_eq!(quarter.iter().max(), Some(&3));
+
+    let mute = run_wave_channel(0b1000_0000, 0, 0b0000_0000, 0b1000_0111);
+    assert!(mute.iter().all(|&val| val == 0));
+}
+
+#[test]
+fn wave_channel_is_silent_with_the_dac_off() {
+    let audio = run_wave_channel(0b0000_0000, 0, 0b0010_0000, 0b1000_0111);
+    assert!(audio.iter().all(|&val| val == 0));
+}
+
+#[test]
+fn wave_channel_length_counts_256_steps() {
+    // A length of 255 lasts 1/256th of a second
+    let audio = run_wave_channel(0b1000_0000, 255, 0b0010_0000, 0b1100_0111);
+
+    let end = audio.len() / 2;
+    assert!(audio[..100].iter().any(|&val| val != 0));
+    assert!(audio[end..].iter().all(|&val| val == 0));
+
+    // A length of 0 lasts a full second
+    let audio = run_wave_channel(0b1000_0000, 0, 0b0010_0000, 0b1100_0111);
+    assert!(audio[end..].iter().any(|&val| val != 0));
+}
+
+fn run_noise_channel(nr41: u8, nr43: u8, nr44: u8) -> Vec<i16> {
+    let mut audio_data: Vec<i16> = Vec::new();
+    {
+        let mut gb = infinite_loop_gb(|frame: StereoFrame| {
+            audio_data.push(frame.left);
+        });
+
+        gb.set_memory_at(0xFF20, nr41);
+        gb.set_memory_at(0xFF21, 0b1111_0000);
+        gb.set_memory_at(0xFF22, nr43);
+        gb.set_memory_at(0xFF23, nr44);
+        gb.tick(1.0 / 60.0);
+
+        assert_eq!(gb.get_memory_at(0xFF23) & 0b1000_0000, 0b0000_0000);
+    }
+    audio_data
+}
+
+#[test]
+fn noise_channel_plays_random_noise() {
+    // Divisor code 3 & clock shift 2
+    let audio = run_noise_channel(0, 0b0010_0011, 0b1000_0000);
+
+    assert!(audio.contains(&15));
+    assert!(audio.contains(&0));
+}
+
+#[test]
+fn noise_channel_stops_with_a_clock_shift_of_14() {
+    let audio = run_noise_channel(0, 0b1110_0000, 0b1000_0000);
+    assert!(audio.iter().all(|&val| val == 0));
+}
+
+#[test]
+fn noise_channel_length_turns_it_off() {
+    let audio = run_noise_channel(63, 0b0010_0011, 0b1100_0000);
+
+    let end = audio.len() / 2;
+    assert!(audio[..100].iter().any(|&val| val != 0));
+    assert!(audio[end..].iter().all(|&val| val == 0));
+}
+
+#[test]
+fn frame_sequencer_is_clocked_by_div() {
+    let mut audio_data: Vec<i16> = Vec::new();
+    {
+        let mut gb = infinite_loop_gb(|frame: StereoFrame| {
+            audio_data.push(frame.left);
+        });
+
+        // Channel 2 at max volume with a length of 1
+        gb.set_memory_at(0xFF16, 0b1100_0000 | 63);
+        gb.set_memory_at(0xFF17, 0b1111_0000);
+        gb.set_memory_at(0xFF19, 0b1100_0111);
+
+        // Keep resetting DIV before bit 4 is set so it never falls
+        for _ in 0..200 {
+            gb.set_memory_at(Labels::DIVIDER, 0);
+            for _ in 0..100 {
+                gb.step_once();
+            }
+        }
+    }
+
+    let end = audio_data.len() / 2;
+    assert!(audio_data[end..].iter().any(|&val| val != 0));
+}
+
+fn run_sweep(nr10: u8, frequency: u16) -> (Vec<i16>, u16) {
+    let mut audio_data: Vec<i16> = Vec::new();
+    let frequency_after;
+    {
+        let mut gb = infinite_loop_gb(|frame: StereoFrame| {
+            audio_data.push(frame.left);
+        });
+
+        let [freq_msb, freq_lsb] = frequency.to_be_bytes();
+        gb.set_memory_at(0xFF10, nr10);
+        gb.set_memory_at(0xFF11, 0b1100_0000);
+        gb.set_memory_at(0xFF12, 0b1111_0000);
+        gb.set_memory_at(0xFF13, freq_lsb);
+        gb.set_memory_at(0xFF14, 0b1000_0000 | freq_msb);
+        gb.tick(1.0 / 60.0);
+
+        frequency_after = u16::from_be_bytes([
+            gb.get_memory_at(0xFF14) & 0b0000_0111,
+            gb.get_memory_at(0xFF13),
+        ]);
+    }
+    (audio_data, frequency_after)
+}
+
+#[test]
+fn sweep_writes_the_new_frequency_back() {
+    // Sweep up every 1/128th of a second by frequency >> 1
+    let (_, frequency) = run_sweep(0b0001_0001, 0x100);
+    assert_eq!(frequency, 0x240);
+
+    // Sweep down by frequency >> 2
+    let (_, frequency) = run_sweep(0b0001_1010, 0x100);
+    assert_eq!(frequency, 0x090);
+
+    // A period of 0 doesn't sweep
+    let (_, frequency) = run_sweep(0b0000_0001, 0x100);
+    assert_eq!(frequency, 0x100);
+}
+
+#[test]
+fn sweep_overflow_turns_channel_1_off() {
+    // The check when triggering overflows
+    let (audio, _) = run_sweep(0b0001_0001, 0x600);
+    assert!(audio.iter().all(|&val| val == 0));
+
+    // The 1st sweep is written back but checking the next one overflows
+    let (audio, frequency) = run_sweep(0b0001_0001, 0x500);
+    let end = audio.len() / 2;
+    assert!(audio[..end].iter().any(|&val| val != 0));
+    assert!(audio[end..].iter().all(|&val| val == 0));
+    assert_eq!(frequency, 0x780);
+}
+
+fn run_stereo(nr50: u8, nr51: u8) -> Vec<StereoFrame> {
+    let mut audio_data: Vec<StereoFrame> = Vec::new();
+    {
+        let mut gb = infinite_loop_gb(|frame| {
+            audio_data.push(frame);
+        });
+
+        gb.set_memory_at(Labels::SOUND_VOLUME, nr50);
+        gb.set_memory_at(Labels::SOUND_PANNING, nr51);
+
+        // Channel 1 with a 75% duty at volume 15
+        gb.set_memory_at(0xFF11, 0b1100_0000);
+        gb.set_memory_at(0xFF12, 0b1111_0000);
+        gb.set_memory_at(0xFF14, 0b1000_0000);
+        gb.tick(1.0 / 60.0);
+    }
+    audio_data
+}
+
+#[test]
+fn nr51_pans_each_channel() {
+    let audio = run_stereo(0b0111_0111, 0b0001_0000);
+    assert!(audio.iter().any(|frame| frame.left == 15));
+    assert!(audio.iter().all(|frame| frame.right == 0));
+
+    let audio = run_stereo(0b0111_0111, 0b0000_0001);
+    assert!(audio.iter().all(|frame| frame.left == 0));
+    assert!(audio.iter().any(|frame| frame.right == 15));
+}
+
+#[test]
+fn nr50_sets_the_volume_of_each_side() {
+    let audio = run_stereo(0b0011_0111, 0b0001_0001);
+    assert_eq!(audio.iter().map(|frame| frame.left).max(), Some(7));
+    assert_eq!(audio.iter().map(|frame| frame.right).max(), Some(15));
+}
+
+#[test]
+fn nr52_reads_back_the_playing_channels() {
+    let mut gb = infinite_loop_gb(|_| {});
+    assert_eq!(gb.get_memory_at(Labels::SOUND_ON) & 0b0000_1111, 0b0000);
+
+    // Channel 2 & the wave channel
+    gb.set_memory_at(0xFF17, 0b1111_0000);
+    gb.set_memory_at(0xFF19, 0b1000_0000);
+    gb.set_memory_at(0xFF1A, 0b1000_0000);
+    gb.set_memory_at(0xFF1E, 0b1000_0000);
+    assert_eq!(gb.get_memory_at(Labels::SOUND_ON) & 0b0000_1111, 0b0110);
+
+    // Turning the DAC off stops the channel
+    gb.set_memory_at(0xFF17, 0b0000_0000);
+    assert_eq!(gb.get_memory_at(Labels::SOUND_ON) & 0b0000_1111, 0b0100);
+}
+
+#[test]
+fn powering_off_clears_the_sound_registers() {
+    let mut audio_data: Vec<StereoFrame> = Vec::new();
+    {
+        let mut gb = infinite_loop_gb(|frame| {
+            audio_data.push(frame);
+        });
+
+        gb.set_memory_at(0xFF30, 0x12);
+        gb.set_memory_at(0xFF12, 0b1111_0000);
+        gb.set_memory_at(0xFF14, 0b1000_0000);
+        gb.set_memory_at(Labels::SOUND_ON, 0b0000_0000);
+
+        assert_eq!(gb.get_memory_at(0xFF12), 0x00);
+        assert_eq!(gb.get_memory_at(Labels::SOUND_PANNING), 0x00);
+        assert_eq!(gb.get_memory_at(Labels::SOUND_ON) & 0b1000_1111, 0x00);
+
+        // Wave RAM isn't a sound register
+        assert_eq!(gb.get_memory_at(0xFF30), 0x12);
+
+        // Triggering while powered off does nothing
+        gb.set_memory_at(0xFF12, 0b1111_0000);
+        gb.set_memory_at(0xFF14, 0b1000_0000);
+        assert_eq!(gb.get_memory_at(Labels::SOUND_ON) & 0b0000_1111, 0b0000);
+
+        gb.tick(1.0 / 60.0);
+    }
+
+    assert!(!audio_data.is_empty());
+    assert!(audio_data
+        .iter()
+        .all(|&frame| frame == StereoFrame::default()));
+}
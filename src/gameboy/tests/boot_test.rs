@@ -0,0 +1,63 @@
+use crate::gameboy::tests::cartridge_test::rom_with_header;
+use crate::gameboy::{Gameboy, Labels, RegisterLabel16};
+
+#[test]
+fn skipping_boot_starts_at_the_cartridge_entry_point() {
+    let gb = Gameboy::new_skip_boot(|_| {}, &rom_with_header(0x00, 0x00, 0x00)).unwrap();
+
+    assert_eq!(gb.get_register_16(RegisterLabel16::ProgramCounter), 0x0100);
+    assert_eq!(gb.get_register_16(RegisterLabel16::StackPointer), 0xFFFE);
+    assert_eq!(gb.get_register_16(RegisterLabel16::BC), 0x0013);
+    assert_eq!(gb.get_register_16(RegisterLabel16::DE), 0x00D8);
+    assert_eq!(gb.get_register_16(RegisterLabel16::HL), 0x014D);
+
+    assert_eq!(gb.get_memory_at(Labels::LCD_CONTROLS), 0x91);
+    assert_eq!(gb.get_memory_at(Labels::BG_PALETTE), 0xFC);
+    assert_eq!(gb.get_memory_at(Labels::DIVIDER), 0xAB);
+    assert_eq!(gb.get_memory_at(Labels::SOUND_ON), 0xF1);
+}
+
+#[test]
+fn skipping_boot_leaves_stat_matching_the_ppu() {
+    let gb = Gameboy::new_skip_boot(|_| {}, &rom_with_header(0x00, 0x00, 0x00)).unwrap();
+
+    // OAM scan on line 0 which matches LYC
+    assert_eq!(gb.get_memory_at(Labels::LCDC_Y), 0x00);
+    assert_eq!(gb.get_memory_at(Labels::LCD_STATUS), 0b1000_0110);
+}
+
+#[test]
+fn skipping_boot_sets_half_carry_from_the_header_checksum() {
+    // "TETRA" gives a non zero checksum
+    let rom = rom_with_header(0x00, 0x00, 0x00);
+    let gb = Gameboy::new_skip_boot(|_| {}, &rom).unwrap();
+    assert_eq!(gb.get_register_16(RegisterLabel16::AF), 0x01B0);
+
+    // A checksum of 0 leaves carry & half carry clear
+    let mut rom = rom_with_header(0x00, 0x00, 0x00);
+    rom[0x134..0x139].copy_from_slice(&[0x00; 5]);
+    rom[0x14D] = 0x00;
+    rom[0x14C] = 0xE7;
+    let gb = Gameboy::new_skip_boot(|_| {}, &rom).unwrap();
+    assert_eq!(gb.get_register_16(RegisterLabel16::AF), 0x0180);
+}
+
+#[test]
+fn boot_roms_run_before_the_cartridge() {
+    // LD A 0x01
+    // LDH (0x50) A
+    let boot_rom = [0x3E, 0x01, 0xE0, 0x50];
+    let mut boot_rom = boot_rom.to_vec();
+    boot_rom.resize(0x100, 0x00);
+
+    let mut rom = rom_with_header(0x00, 0x00, 0x00);
+    rom[0x0000] = 0x12;
+
+    let mut gb = Gameboy::new_with_boot_rom(|_| {}, &boot_rom, &rom).unwrap();
+    assert_eq!(gb.get_register_16(RegisterLabel16::ProgramCounter), 0x0000);
+    assert_eq!(gb.get_memory_at(0x0000), 0x3E);
+
+    gb.step_once();
+    gb.step_once();
+    assert_eq!(gb.get_memory_at(0x0000), 0x12);
+}
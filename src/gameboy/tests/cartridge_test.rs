@@ -0,0 +1,138 @@
+use crate::gameboy::cartridge::{Cartridge, CartridgeError, CgbSupport, Licensee};
+
+/// Build a ROM of the size given in the header, with a valid header checksum
+pub fn rom_with_header(cartridge_type: u8, rom_size: u8, ram_size: u8) -> Vec<u8> {
+    let mut rom = vec![0x00; 0x8000 << rom_size];
+    rom[0x134..0x139].copy_from_slice(b"TETRA");
+    rom[0x147] = cartridge_type;
+    rom[0x148] = rom_size;
+    rom[0x149] = ram_size;
+    fix_header_checksum(&mut rom);
+    rom
+}
+
+pub fn fix_header_checksum(rom: &mut [u8]) {
+    rom[0x14D] = rom[0x134..0x14D]
+        .iter()
+        .fold(0u8, |sum, &byte| sum.wrapping_sub(byte).wrapping_sub(1));
+}
+
+#[test]
+fn header_fields_are_parsed() {
+    let mut rom = rom_with_header(0x13, 0x02, 0x03);
+    rom[0x146] = 0x03;
+    rom[0x14B] = 0x01;
+    rom[0x14C] = 0x02;
+    fix_header_checksum(&mut rom);
+
+    let cartridge = Cartridge::from_bytes(rom).unwrap();
+
+    assert_eq!(cartridge.get_title(), "TETRA");
+    assert_eq!(cartridge.get_cgb_support(), CgbSupport::None);
+    assert!(cartridge.supports_sgb());
+    assert_eq!(cartridge.get_cartridge_type(), 0x13);
+    assert_eq!(cartridge.get_rom_size(), 0x20000);
+    assert_eq!(cartridge.get_ram_size(), 0x8000);
+    assert_eq!(cartridge.get_licensee(), &Licensee::Old(0x01));
+    assert_eq!(cartridge.get_version(), 0x02);
+}
+
+#[test]
+fn cgb_games_have_a_shorter_title_and_new_licensee() {
+    let mut rom = rom_with_header(0x00, 0x00, 0x00);
+    rom[0x134..0x143].copy_from_slice(b"POKEMON_SLVAAXE");
+    rom[0x143] = 0x80;
+    rom[0x144..0x146].copy_from_slice(b"01");
+    rom[0x14B] = 0x33;
+    fix_header_checksum(&mut rom);
+
+    let cartridge = Cartridge::from_bytes(rom).unwrap();
+
+    assert_eq!(cartridge.get_title(), "POKEMON_SLV");
+    assert_eq!(cartridge.get_cgb_support(), CgbSupport::Enhanced);
+    assert_eq!(cartridge.get_licensee(), &Licensee::New("01".to_string()));
+}
+
+#[test]
+fn checksums_are_exposed() {
+    let mut rom = rom_with_header(0x00, 0x00, 0x00);
+    rom[0x200] = 0xFF;
+    rom[0x14E] = 0x12;
+    rom[0x14F] = 0x34;
+
+    let header_checksum = rom[0x14D];
+    let global_checksum = rom
+        .iter()
+        .fold(0u16, |sum, &byte| sum.wrapping_add(byte as u16))
+        - 0x12
+        - 0x34;
+
+    let cartridge = Cartridge::from_bytes(rom).unwrap();
+
+    assert_eq!(cartridge.get_header_checksum(), header_checksum);
+    assert_eq!(cartridge.get_global_checksum(), 0x1234);
+    assert_eq!(cartridge.compute_global_checksum(), global_checksum);
+}
+
+#[test]
+fn roms_without_a_header_are_rejected() {
+    let result = Cartridge::from_bytes(vec![0x00; 0x100]);
+    assert!(matches!(result, Err(CartridgeError::TooSmall(0x100))));
+}
+
+#[test]
+fn corrupt_headers_are_rejected() {
+    let mut rom = rom_with_header(0x00, 0x00, 0x00);
+    rom[0x148] = 0x20;
+    fix_header_checksum(&mut rom);
+    let result = Cartridge::from_bytes(rom);
+    assert!(matches!(result, Err(CartridgeError::UnknownRomSize(0x20))));
+
+    let mut rom = rom_with_header(0x00, 0x00, 0x00);
+    rom[0x149] = 0x07;
+    fix_header_checksum(&mut rom);
+    let result = Cartridge::from_bytes(rom);
+    assert!(matches!(result, Err(CartridgeError::UnknownRamSize(0x07))));
+}
+
+#[test]
+fn header_checksum_mismatches_are_reported_but_not_fatal() {
+    let cartridge = Cartridge::from_bytes(rom_with_header(0x00, 0x00, 0x00)).unwrap();
+    assert!(cartridge.check_header_checksum().is_ok());
+
+    let mut rom = rom_with_header(0x00, 0x00, 0x00);
+    let header_checksum = rom[0x14D];
+    rom[0x14D] = header_checksum.wrapping_add(1);
+
+    let cartridge = Cartridge::from_bytes(rom).unwrap();
+    assert_eq!(cartridge.get_title(), "TETRA");
+    assert!(matches!(
+        cartridge.check_header_checksum(),
+        Err(CartridgeError::HeaderChecksumMismatch { expected, actual })
+            if expected == header_checksum.wrapping_add(1) && actual == header_checksum
+    ));
+}
+
+#[test]
+fn truncated_roms_are_rejected() {
+    let mut rom = rom_with_header(0x01, 0x01, 0x00);
+    rom.truncate(0x8000);
+
+    let result = Cartridge::from_bytes(rom);
+    assert!(matches!(
+        result,
+        Err(CartridgeError::Truncated {
+            expected: 0x10000,
+            actual: 0x8000
+        })
+    ));
+}
+
+#[test]
+fn battery_is_read_from_the_cartridge_type() {
+    let cartridge = Cartridge::from_bytes(rom_with_header(0x03, 0x00, 0x02)).unwrap();
+    assert!(cartridge.has_battery());
+
+    let cartridge = Cartridge::from_bytes(rom_with_header(0x02, 0x00, 0x02)).unwrap();
+    assert!(!cartridge.has_battery());
+}
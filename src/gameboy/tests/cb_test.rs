@@ -2,7 +2,7 @@ use crate::gameboy::{
     cpu::CPU,
     memory_adapter::MemoryAdapter,
     opcodes::{Argument, Category, Decoder},
-    read_flag, Flags, OpCode, RegisterLabel16, RegisterLabel8,
+    read_flag, write_flag, Flags, Gameboy, OpCode, RegisterLabel16, RegisterLabel8,
 };
 
 #[test]
@@ -63,7 +63,7 @@ fn swap_switches_top_and_bottom_nibbles_of_register() {
         .unwrap();
 
     assert_eq!(cycles, 8);
-    assert_eq!(cpu.read_16_bits(RegisterLabel16::ProgramCounter), 0x1);
+    assert_eq!(cpu.read_16_bits(RegisterLabel16::ProgramCounter), 0x2);
 
     assert_eq!(cpu.read_8_bits(RegisterLabel8::A), 0xED);
 
@@ -116,3 +116,240 @@ fn swap_sets_flags_correctly() {
     assert_eq!(read_flag(&cpu, Flags::C), false);
     assert_eq!(read_flag(&cpu, Flags::N), false);
 }
+
+fn cb_op(category: Category, register: RegisterLabel8) -> OpCode {
+    OpCode::new(
+        category,
+        [Argument::Register8Constant(register), Argument::None],
+    )
+}
+
+fn bit_op(category: Category, bit: u8, register: RegisterLabel8) -> OpCode {
+    OpCode::new(
+        category,
+        [Argument::Bit(bit), Argument::Register8Constant(register)],
+    )
+}
+
+#[test]
+fn every_cb_opcode_can_be_decoded() {
+    for code in 0x00..=0xFF {
+        let opcode = Decoder::decode_instruction(0x00, &[0xCB, code]).unwrap();
+        assert_eq!(opcode.size(), 2);
+    }
+}
+
+#[test]
+fn decode_cb_rotates_shifts_and_bits() {
+    let decode = |memory| Decoder::decode_instruction(0x00, memory).unwrap();
+
+    assert_eq!(
+        decode(&[0xCB, 0x00]),
+        cb_op(Category::RLC, RegisterLabel8::B)
+    );
+    assert_eq!(
+        decode(&[0xCB, 0x09]),
+        cb_op(Category::RRC, RegisterLabel8::C)
+    );
+    assert_eq!(
+        decode(&[0xCB, 0x12]),
+        cb_op(Category::RL, RegisterLabel8::D)
+    );
+    assert_eq!(
+        decode(&[0xCB, 0x1B]),
+        cb_op(Category::RR, RegisterLabel8::E)
+    );
+    assert_eq!(
+        decode(&[0xCB, 0x24]),
+        cb_op(Category::SLA, RegisterLabel8::H)
+    );
+    assert_eq!(
+        decode(&[0xCB, 0x2D]),
+        cb_op(Category::SRA, RegisterLabel8::L)
+    );
+    assert_eq!(
+        decode(&[0xCB, 0x3F]),
+        cb_op(Category::SRL, RegisterLabel8::A)
+    );
+
+    assert_eq!(
+        decode(&[0xCB, 0x47]),
+        bit_op(Category::BIT, 0, RegisterLabel8::A)
+    );
+    assert_eq!(
+        decode(&[0xCB, 0x88]),
+        bit_op(Category::RES, 1, RegisterLabel8::B)
+    );
+    assert_eq!(
+        decode(&[0xCB, 0xFD]),
+        bit_op(Category::SET, 7, RegisterLabel8::L)
+    );
+    assert_eq!(
+        decode(&[0xCB, 0x06]),
+        OpCode::new(
+            Category::RLC,
+            [
+                Argument::RegisterIndirect(RegisterLabel16::HL),
+                Argument::None
+            ]
+        )
+    );
+    assert_eq!(
+        decode(&[0xCB, 0xC6]),
+        OpCode::new(
+            Category::SET,
+            [
+                Argument::Bit(0),
+                Argument::RegisterIndirect(RegisterLabel16::HL)
+            ]
+        )
+    );
+}
+
+#[test]
+fn rlc_and_rrc_rotate_the_register() {
+    let mut cpu = CPU::new();
+    let mut memory = vec![0x0; 0xFFFF];
+
+    cpu.write_8_bits(RegisterLabel8::B, 0b1000_0101);
+    let cycles = cb_op(Category::RLC, RegisterLabel8::B)
+        .run(&mut cpu, MemoryAdapter::new(&mut memory))
+        .unwrap();
+
+    assert_eq!(cycles, 8);
+    assert_eq!(cpu.read_16_bits(RegisterLabel16::ProgramCounter), 0x2);
+    assert_eq!(cpu.read_8_bits(RegisterLabel8::B), 0b0000_1011);
+    assert!(read_flag(&cpu, Flags::C));
+    assert!(!read_flag(&cpu, Flags::Z));
+
+    cb_op(Category::RRC, RegisterLabel8::B).run(&mut cpu, MemoryAdapter::new(&mut memory));
+
+    assert_eq!(cpu.read_8_bits(RegisterLabel8::B), 0b1000_0101);
+    assert!(read_flag(&cpu, Flags::C));
+}
+
+#[test]
+fn rr_rotates_through_the_carry() {
+    let mut cpu = CPU::new();
+    let mut memory = vec![0x0; 0xFFFF];
+
+    cpu.write_8_bits(RegisterLabel8::C, 0b0000_0001);
+    write_flag(&mut cpu, Flags::C, false);
+    cb_op(Category::RR, RegisterLabel8::C).run(&mut cpu, MemoryAdapter::new(&mut memory));
+
+    assert_eq!(cpu.read_8_bits(RegisterLabel8::C), 0x00);
+    assert!(read_flag(&cpu, Flags::C));
+    assert!(read_flag(&cpu, Flags::Z));
+    assert!(!read_flag(&cpu, Flags::N));
+    assert!(!read_flag(&cpu, Flags::H));
+
+    cb_op(Category::RR, RegisterLabel8::C).run(&mut cpu, MemoryAdapter::new(&mut memory));
+
+    assert_eq!(cpu.read_8_bits(RegisterLabel8::C), 0b1000_0000);
+    assert!(!read_flag(&cpu, Flags::C));
+    assert!(!read_flag(&cpu, Flags::Z));
+}
+
+#[test]
+fn rl_sets_the_zero_flag() {
+    let mut cpu = CPU::new();
+    let mut memory = vec![0x0; 0xFFFF];
+
+    cpu.write_8_bits(RegisterLabel8::D, 0b1000_0000);
+    write_flag(&mut cpu, Flags::C, false);
+    cb_op(Category::RL, RegisterLabel8::D).run(&mut cpu, MemoryAdapter::new(&mut memory));
+
+    assert_eq!(cpu.read_8_bits(RegisterLabel8::D), 0x00);
+    assert!(read_flag(&cpu, Flags::C));
+    assert!(read_flag(&cpu, Flags::Z));
+}
+
+#[test]
+fn shifts_move_bits_into_the_carry() {
+    let mut cpu = CPU::new();
+    let mut memory = vec![0x0; 0xFFFF];
+
+    // SLA
+    cpu.write_8_bits(RegisterLabel8::A, 0b1100_0001);
+    cb_op(Category::SLA, RegisterLabel8::A).run(&mut cpu, MemoryAdapter::new(&mut memory));
+    assert_eq!(cpu.read_8_bits(RegisterLabel8::A), 0b1000_0010);
+    assert!(read_flag(&cpu, Flags::C));
+
+    // SRA keeps bit 7
+    cpu.write_8_bits(RegisterLabel8::A, 0b1000_0011);
+    cb_op(Category::SRA, RegisterLabel8::A).run(&mut cpu, MemoryAdapter::new(&mut memory));
+    assert_eq!(cpu.read_8_bits(RegisterLabel8::A), 0b1100_0001);
+    assert!(read_flag(&cpu, Flags::C));
+
+    // SRL clears bit 7
+    cpu.write_8_bits(RegisterLabel8::A, 0b1000_0010);
+    cb_op(Category::SRL, RegisterLabel8::A).run(&mut cpu, MemoryAdapter::new(&mut memory));
+    assert_eq!(cpu.read_8_bits(RegisterLabel8::A), 0b0100_0001);
+    assert!(!read_flag(&cpu, Flags::C));
+
+    cpu.write_8_bits(RegisterLabel8::A, 0b0000_0001);
+    cb_op(Category::SRL, RegisterLabel8::A).run(&mut cpu, MemoryAdapter::new(&mut memory));
+    assert_eq!(cpu.read_8_bits(RegisterLabel8::A), 0x00);
+    assert!(read_flag(&cpu, Flags::Z));
+    assert!(read_flag(&cpu, Flags::C));
+}
+
+#[test]
+fn bit_tests_the_lower_bits() {
+    let mut cpu = CPU::new();
+    let mut memory = vec![0x0; 0xFFFF];
+
+    cpu.write_8_bits(RegisterLabel8::E, 0b0000_0001);
+    write_flag(&mut cpu, Flags::C, true);
+
+    bit_op(Category::BIT, 0, RegisterLabel8::E).run(&mut cpu, MemoryAdapter::new(&mut memory));
+    assert!(!read_flag(&cpu, Flags::Z));
+
+    bit_op(Category::BIT, 1, RegisterLabel8::E).run(&mut cpu, MemoryAdapter::new(&mut memory));
+    assert!(read_flag(&cpu, Flags::Z));
+    assert!(read_flag(&cpu, Flags::H));
+    assert!(!read_flag(&cpu, Flags::N));
+    assert!(read_flag(&cpu, Flags::C));
+}
+
+#[test]
+fn res_and_set_change_a_single_bit() {
+    let mut cpu = CPU::new();
+    let mut memory = vec![0x0; 0xFFFF];
+
+    cpu.write_8_bits(RegisterLabel8::H, 0xFF);
+    let flags = cpu.read_8_bits(RegisterLabel8::F);
+
+    let cycles = bit_op(Category::RES, 3, RegisterLabel8::H)
+        .run(&mut cpu, MemoryAdapter::new(&mut memory))
+        .unwrap();
+    assert_eq!(cycles, 8);
+    assert_eq!(cpu.read_8_bits(RegisterLabel8::H), 0b1111_0111);
+
+    bit_op(Category::SET, 3, RegisterLabel8::H).run(&mut cpu, MemoryAdapter::new(&mut memory));
+    assert_eq!(cpu.read_8_bits(RegisterLabel8::H), 0xFF);
+
+    // Flags are unaffected
+    assert_eq!(cpu.read_8_bits(RegisterLabel8::F), flags);
+}
+
+#[test]
+fn cb_instructions_on_hl_use_memory() {
+    // RLC (HL), BIT 0,(HL), SET 7,(HL)
+    let mut gb = Gameboy::new(vec![0xCB, 0x06, 0xCB, 0x46, 0xCB, 0xFE]);
+    gb.set_register_16(RegisterLabel16::HL, 0xC000);
+    gb.set_memory_at(0xC000, 0b0100_0000);
+
+    let cycles = gb.step_once().unwrap();
+    assert_eq!(cycles, 16);
+    assert_eq!(gb.get_memory_at(0xC000), 0b1000_0000);
+
+    let cycles = gb.step_once().unwrap();
+    assert_eq!(cycles, 12);
+    assert!(gb.get_flag(Flags::Z));
+
+    let cycles = gb.step_once().unwrap();
+    assert_eq!(cycles, 16);
+    assert_eq!(gb.get_memory_at(0xC000), 0b1000_0000);
+    assert_eq!(gb.get_register_16(RegisterLabel16::ProgramCounter), 0x06);
+}
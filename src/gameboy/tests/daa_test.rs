@@ -0,0 +1,63 @@
+use crate::gameboy::opcodes::{Argument, Category};
+use crate::gameboy::tests::decode_util::decode;
+use crate::gameboy::{Flags, Gameboy, OpCode, RegisterLabel16, RegisterLabel8};
+
+#[test]
+fn decode_daa_instruction() {
+    assert_eq!(
+        decode(&[0x27]),
+        OpCode::new(Category::DAA, [Argument::None, Argument::None])
+    );
+}
+
+#[test]
+fn daa_adjusts_the_result_of_an_addition() {
+    // ADD A B, DAA
+    let mut gb = Gameboy::new(vec![0x80, 0x27]);
+    gb.set_register_8(RegisterLabel8::A, 0x15);
+    gb.set_register_8(RegisterLabel8::B, 0x27);
+
+    gb.step_once();
+    let cycles = gb.step_once().unwrap();
+
+    assert_eq!(cycles, 4);
+    assert_eq!(gb.get_register_16(RegisterLabel16::ProgramCounter), 0x02);
+
+    // 15 + 27 = 42 in BCD
+    assert_eq!(gb.get_register_8(RegisterLabel8::A), 0x42);
+    assert!(!gb.get_flag(Flags::C));
+    assert!(!gb.get_flag(Flags::H));
+    assert!(!gb.get_flag(Flags::Z));
+}
+
+#[test]
+fn daa_sets_carry_when_the_addition_passes_99() {
+    // ADD A B, DAA
+    let mut gb = Gameboy::new(vec![0x80, 0x27]);
+    gb.set_register_8(RegisterLabel8::A, 0x99);
+    gb.set_register_8(RegisterLabel8::B, 0x01);
+
+    gb.step_once();
+    gb.step_once();
+
+    // 99 + 1 = 100 so the result is 00 with the carry set
+    assert_eq!(gb.get_register_8(RegisterLabel8::A), 0x00);
+    assert!(gb.get_flag(Flags::C));
+    assert!(gb.get_flag(Flags::Z));
+}
+
+#[test]
+fn daa_adjusts_the_result_of_a_subtraction() {
+    // SUB B, DAA
+    let mut gb = Gameboy::new(vec![0x90, 0x27]);
+    gb.set_register_8(RegisterLabel8::A, 0x42);
+    gb.set_register_8(RegisterLabel8::B, 0x15);
+
+    gb.step_once();
+    gb.step_once();
+
+    // 42 - 15 = 27 in BCD
+    assert_eq!(gb.get_register_8(RegisterLabel8::A), 0x27);
+    assert!(gb.get_flag(Flags::N));
+    assert!(!gb.get_flag(Flags::C));
+}
@@ -161,11 +161,11 @@ mod dec_test {
     #[test]
     fn dec_hl_offset_should_underflow() {
         let mut gb = Gameboy::new(vec![0x35]);
-        gb.set_register_16(RegisterLabel16::HL, 0xFF);
-        gb.set_memory_at(0xFF, 0);
+        gb.set_register_16(RegisterLabel16::HL, 0xC0FF);
+        gb.set_memory_at(0xC0FF, 0);
         let _ = gb.step_once();
 
-        assert_eq!(gb.get_memory_at(0xFF), 0xFF);
+        assert_eq!(gb.get_memory_at(0xC0FF), 0xFF);
     }
 
     #[test]
@@ -0,0 +1,137 @@
+use crate::gameboy::cpu::PowerState;
+use crate::gameboy::{Gameboy, Labels, RegisterLabel16, RegisterLabel8};
+
+#[test]
+fn halt_stops_fetching_until_an_interrupt_is_pending() {
+    // HALT, LD A 0x01
+    let mut gb = Gameboy::new(vec![0x76, 0x3E, 0x01]);
+
+    // Enable VBlank interrupt
+    gb.set_memory_at(Labels::INTERRUPT_ENABLE, 0b0000_0001);
+
+    gb.step_once();
+    assert_eq!(gb.get_power_state(), PowerState::Halted);
+    assert_eq!(gb.get_register_16(RegisterLabel16::ProgramCounter), 0x01);
+
+    // Nothing is fetched while halted
+    for _ in 0..10 {
+        let cycles = gb.step_once().unwrap();
+        assert_eq!(cycles, 4);
+    }
+    assert_eq!(gb.get_register_16(RegisterLabel16::ProgramCounter), 0x01);
+
+    // A pending interrupt wakes the CPU even though IME isn't set
+    gb.set_memory_at(Labels::INTERRUPT_TRIGGER, 0b0000_0001);
+    gb.step_once();
+
+    assert_eq!(gb.get_power_state(), PowerState::Running);
+    assert_eq!(gb.get_register_8(RegisterLabel8::A), 0x01);
+    assert_eq!(gb.get_register_16(RegisterLabel16::ProgramCounter), 0x03);
+
+    // The interrupt isn't serviced so the flag stays set
+    assert_eq!(gb.get_memory_at(Labels::INTERRUPT_TRIGGER), 0b0000_0001);
+}
+
+#[test]
+fn halt_wakes_to_service_an_interrupt_when_ime_is_set() {
+    // EI, NOP, HALT
+    let mut gb = Gameboy::new(vec![0xFB, 0x00, 0x76]);
+    gb.set_register_16(RegisterLabel16::StackPointer, 0xC055);
+    gb.set_memory_at(Labels::INTERRUPT_ENABLE, 0b0000_0001);
+
+    gb.step_once();
+    gb.step_once();
+    gb.step_once();
+    assert_eq!(gb.get_power_state(), PowerState::Halted);
+
+    gb.set_memory_at(Labels::INTERRUPT_TRIGGER, 0b0000_0001);
+    let cycles = gb.step_once().unwrap();
+
+    assert_eq!(cycles, 20);
+    assert_eq!(gb.get_register_16(RegisterLabel16::ProgramCounter), 0x40);
+
+    // The return address is the instruction after HALT
+    assert_eq!(gb.get_memory_at(0xC053), 0x03);
+}
+
+#[test]
+fn halt_keeps_the_ppu_running() {
+    // HALT
+    let mut gb = Gameboy::new(vec![0x76]);
+    gb.set_memory_at(Labels::LCD_CONTROLS, 0b1000_0000);
+
+    gb.step_once();
+
+    // One line takes 456 cycles
+    for _ in 0..(456 / 4) {
+        gb.step_once();
+    }
+
+    assert_eq!(gb.get_power_state(), PowerState::Halted);
+    assert_eq!(gb.get_memory_at(Labels::LCDC_Y), 1);
+}
+
+#[test]
+fn halt_bug_repeats_the_next_byte() {
+    // HALT, INC A, NOP
+    let mut gb = Gameboy::new(vec![0x76, 0x3C, 0x00]);
+
+    // IME is off & an interrupt is already pending
+    gb.set_memory_at(Labels::INTERRUPT_ENABLE, 0b0000_0001);
+    gb.set_memory_at(Labels::INTERRUPT_TRIGGER, 0b0000_0001);
+
+    gb.step_once();
+    assert_eq!(gb.get_power_state(), PowerState::Running);
+
+    // INC A is run twice
+    gb.step_once();
+    assert_eq!(gb.get_register_16(RegisterLabel16::ProgramCounter), 0x01);
+    gb.step_once();
+    assert_eq!(gb.get_register_16(RegisterLabel16::ProgramCounter), 0x02);
+
+    assert_eq!(gb.get_register_8(RegisterLabel8::A), 0x02);
+}
+
+#[test]
+fn halt_bug_reads_the_opcode_as_its_own_argument() {
+    // HALT, LD A 0x14
+    let mut gb = Gameboy::new(vec![0x76, 0x3E, 0x14]);
+
+    gb.set_memory_at(Labels::INTERRUPT_ENABLE, 0b0000_0100);
+    gb.set_memory_at(Labels::INTERRUPT_TRIGGER, 0b0000_0100);
+
+    gb.step_once();
+    let cycles = gb.step_once().unwrap();
+
+    // The instruction becomes LD A 0x3E & 0x14 (INC D) is next
+    assert_eq!(cycles, 8);
+    assert_eq!(gb.get_register_8(RegisterLabel8::A), 0x3E);
+    assert_eq!(gb.get_register_16(RegisterLabel16::ProgramCounter), 0x02);
+    assert_eq!(gb.get_current_instruction().unwrap(), "INC D");
+}
+
+#[test]
+fn stop_waits_for_a_joypad_press() {
+    // STOP 0, NOP
+    let mut gb = Gameboy::new(vec![0x10, 0x00, 0x00]);
+    gb.set_memory_at(Labels::LCD_CONTROLS, 0b1000_0000);
+
+    gb.step_once();
+    assert_eq!(gb.get_power_state(), PowerState::Stopped);
+    assert_eq!(gb.get_register_16(RegisterLabel16::ProgramCounter), 0x02);
+
+    // Interrupts don't wake the CPU from STOP
+    gb.set_memory_at(Labels::INTERRUPT_ENABLE, 0b0000_0001);
+    gb.set_memory_at(Labels::INTERRUPT_TRIGGER, 0b0000_0001);
+    for _ in 0..(456 / 4) {
+        gb.step_once();
+    }
+    assert_eq!(gb.get_register_16(RegisterLabel16::ProgramCounter), 0x02);
+    assert_eq!(gb.get_memory_at(Labels::LCDC_Y), 0);
+
+    gb.joypad_pressed();
+    gb.step_once();
+
+    assert_eq!(gb.get_power_state(), PowerState::Running);
+    assert_eq!(gb.get_register_16(RegisterLabel16::ProgramCounter), 0x03);
+}
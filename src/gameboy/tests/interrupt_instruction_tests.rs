@@ -137,3 +137,117 @@ fn rst_works_like_call() {
     assert_eq!(cpu.read_16_bits(RegisterLabel16::ProgramCounter), 0x0020);
     assert_eq!(cpu.read_16_bits(RegisterLabel16::StackPointer), 0xA00D);
 }
+
+fn gb_with_interrupts_enabled() -> Gameboy<'static> {
+    // EI, NOP
+    let mut gb = Gameboy::new(vec![0xFB, 0x00]);
+    gb.set_register_16(RegisterLabel16::StackPointer, 0xC055);
+
+    gb.step_once();
+    gb.step_once();
+    assert!(gb.get_ime_flag());
+
+    gb
+}
+
+#[test]
+fn every_interrupt_jumps_to_its_routine() {
+    let routines = [
+        (0b0000_0001, 0x40),
+        (0b0000_0010, 0x48),
+        (0b0000_0100, 0x50),
+        (0b0000_1000, 0x58),
+        (0b0001_0000, 0x60),
+    ];
+
+    for (bit, address) in routines.iter() {
+        let mut gb = gb_with_interrupts_enabled();
+        gb.set_memory_at(Labels::INTERRUPT_ENABLE, 0b0001_1111);
+        gb.set_memory_at(Labels::INTERRUPT_TRIGGER, *bit);
+
+        let cycles = gb.step_once().unwrap();
+
+        assert_eq!(cycles, 20);
+        assert_eq!(
+            gb.get_register_16(RegisterLabel16::ProgramCounter),
+            *address
+        );
+        assert_eq!(gb.get_memory_at(Labels::INTERRUPT_TRIGGER), 0b0000_0000);
+    }
+}
+
+#[test]
+fn interrupts_are_serviced_in_priority_order() {
+    let mut gb = gb_with_interrupts_enabled();
+
+    // Timer, Serial & Joypad all requested
+    gb.set_memory_at(Labels::INTERRUPT_ENABLE, 0b0001_1111);
+    gb.set_memory_at(Labels::INTERRUPT_TRIGGER, 0b0001_1100);
+
+    gb.step_once();
+    assert_eq!(gb.get_register_16(RegisterLabel16::ProgramCounter), 0x50);
+    assert_eq!(gb.get_memory_at(Labels::INTERRUPT_TRIGGER), 0b0001_1000);
+
+    // Disabled interrupts are skipped
+    let mut gb = gb_with_interrupts_enabled();
+    gb.set_memory_at(Labels::INTERRUPT_ENABLE, 0b0001_0010);
+    gb.set_memory_at(Labels::INTERRUPT_TRIGGER, 0b0001_0001);
+
+    gb.step_once();
+    assert_eq!(gb.get_register_16(RegisterLabel16::ProgramCounter), 0x60);
+    assert_eq!(gb.get_memory_at(Labels::INTERRUPT_TRIGGER), 0b0000_0001);
+}
+
+#[test]
+fn unused_interrupt_trigger_bits_read_as_1() {
+    // LDH A (0x0F), LD B A, LDH A (0x0F)
+    let mut gb = Gameboy::new(vec![0xF0, 0x0F, 0x47, 0xF0, 0x0F]);
+
+    gb.step_once();
+    gb.step_once();
+    assert_eq!(gb.get_register_8(RegisterLabel8::B), 0b1110_0000);
+
+    gb.set_memory_at(Labels::INTERRUPT_TRIGGER, 0b0000_0101);
+    gb.step_once();
+    assert_eq!(gb.get_register_8(RegisterLabel8::A), 0b1110_0101);
+}
+
+#[test]
+fn pushing_over_ie_changes_the_serviced_interrupt() {
+    let mut gb = gb_with_interrupts_enabled();
+
+    // The high byte of the program counter is pushed to IE
+    gb.set_register_16(RegisterLabel16::StackPointer, 0x0000);
+    gb.set_register_16(RegisterLabel16::ProgramCounter, 0x0402);
+
+    // VBlank is enabled but the push will only enable Timer
+    gb.set_memory_at(Labels::INTERRUPT_ENABLE, 0b0000_0001);
+    gb.set_memory_at(Labels::INTERRUPT_TRIGGER, 0b0000_0101);
+
+    let cycles = gb.step_once().unwrap();
+
+    assert_eq!(cycles, 20);
+    assert_eq!(gb.get_register_16(RegisterLabel16::ProgramCounter), 0x50);
+    assert_eq!(gb.get_memory_at(Labels::INTERRUPT_ENABLE), 0x04);
+    assert_eq!(gb.get_memory_at(0xFFFE), 0x02);
+
+    // Only the Timer request is acknowledged
+    assert_eq!(gb.get_memory_at(Labels::INTERRUPT_TRIGGER), 0b0000_0001);
+}
+
+#[test]
+fn pushing_over_ie_can_cancel_the_interrupt() {
+    let mut gb = gb_with_interrupts_enabled();
+
+    gb.set_register_16(RegisterLabel16::StackPointer, 0x0000);
+    gb.set_memory_at(Labels::INTERRUPT_ENABLE, 0b0000_0001);
+    gb.set_memory_at(Labels::INTERRUPT_TRIGGER, 0b0000_0001);
+
+    gb.step_once();
+
+    // IE is cleared by the push so the CPU jumps to 0x0000 instead
+    assert_eq!(gb.get_register_16(RegisterLabel16::ProgramCounter), 0x0000);
+    assert_eq!(gb.get_memory_at(Labels::INTERRUPT_ENABLE), 0x00);
+    assert_eq!(gb.get_memory_at(Labels::INTERRUPT_TRIGGER), 0b0000_0001);
+    assert!(!gb.get_ime_flag());
+}
@@ -0,0 +1,97 @@
+use crate::gameboy::bus::{Bus, Memory};
+use crate::gameboy::{Gameboy, JoypadButton, Labels, RegisterLabel16};
+
+const SELECT_DIRECTIONS: u8 = 0b0010_0000;
+const SELECT_ACTIONS: u8 = 0b0001_0000;
+const SELECT_NONE: u8 = 0b0011_0000;
+
+fn joypad_interrupt_requested(bus: &Bus) -> bool {
+    bus.read_raw(Labels::INTERRUPT_TRIGGER) & 0b0001_0000 != 0
+}
+
+#[test]
+fn no_buttons_pressed_reads_all_ones() {
+    let mut bus = Bus::new(&[]).unwrap();
+
+    bus.write(Labels::JOYPAD, 0x00);
+    assert_eq!(bus.read(Labels::JOYPAD), 0b1100_1111);
+}
+
+#[test]
+fn pressed_buttons_read_as_0_in_the_selected_row() {
+    let mut bus = Bus::new(&[]).unwrap();
+    bus.set_button(JoypadButton::Down, true);
+    bus.set_button(JoypadButton::A, true);
+
+    bus.write(Labels::JOYPAD, SELECT_DIRECTIONS);
+    assert_eq!(bus.read(Labels::JOYPAD), 0b1110_0111);
+
+    bus.write(Labels::JOYPAD, SELECT_ACTIONS);
+    assert_eq!(bus.read(Labels::JOYPAD), 0b1101_1110);
+
+    // Selecting both rows combines them
+    bus.write(Labels::JOYPAD, 0x00);
+    assert_eq!(bus.read(Labels::JOYPAD), 0b1100_0110);
+
+    bus.write(Labels::JOYPAD, SELECT_NONE);
+    assert_eq!(bus.read(Labels::JOYPAD), 0b1111_1111);
+
+    bus.set_button(JoypadButton::Down, false);
+    bus.write(Labels::JOYPAD, SELECT_DIRECTIONS);
+    assert_eq!(bus.read(Labels::JOYPAD), 0b1110_1111);
+}
+
+#[test]
+fn only_the_select_bits_can_be_written() {
+    let mut bus = Bus::new(&[]).unwrap();
+    bus.set_button(JoypadButton::Start, true);
+
+    bus.write(Labels::JOYPAD, 0b1100_1111 | SELECT_ACTIONS);
+    assert_eq!(bus.read(Labels::JOYPAD), 0b1101_0111);
+}
+
+#[test]
+fn pressing_a_selected_button_requests_the_joypad_interrupt() {
+    let mut bus = Bus::new(&[]).unwrap();
+    bus.write(Labels::JOYPAD, SELECT_ACTIONS);
+
+    // Buttons in the other row don't change P1
+    bus.set_button(JoypadButton::Left, true);
+    assert!(!joypad_interrupt_requested(&bus));
+
+    bus.set_button(JoypadButton::B, true);
+    assert!(joypad_interrupt_requested(&bus));
+
+    // Releasing is a rising edge
+    bus.write_raw(Labels::INTERRUPT_TRIGGER, 0x00);
+    bus.set_button(JoypadButton::B, false);
+    assert!(!joypad_interrupt_requested(&bus));
+}
+
+#[test]
+fn selecting_a_row_with_a_held_button_requests_the_joypad_interrupt() {
+    let mut bus = Bus::new(&[]).unwrap();
+    bus.write(Labels::JOYPAD, SELECT_NONE);
+    bus.set_button(JoypadButton::Up, true);
+    assert!(!joypad_interrupt_requested(&bus));
+
+    bus.write(Labels::JOYPAD, SELECT_DIRECTIONS);
+    assert!(joypad_interrupt_requested(&bus));
+}
+
+#[test]
+fn pressing_a_button_wakes_the_cpu_from_stop() {
+    // STOP
+    // NOP
+    let mut gb = Gameboy::new(vec![0x10, 0x00, 0x00]);
+
+    gb.step_once();
+    gb.step_once();
+    assert_eq!(gb.get_register_16(RegisterLabel16::ProgramCounter), 0x02);
+
+    gb.press_button(JoypadButton::Start);
+    gb.step_once();
+    assert_eq!(gb.get_register_16(RegisterLabel16::ProgramCounter), 0x03);
+
+    gb.release_button(JoypadButton::Start);
+}
@@ -71,7 +71,7 @@ fn the_jr_with_no_conditions_always_jumps() {
 
     let cycles = gb.step_once().unwrap();
     use crate::gameboy::opcodes::Decoder;
-    let opcode = Decoder::decode_instruction(0x01, gb.get_memory_slice_at(0, 0xFFFF)).unwrap();
+    let opcode = Decoder::decode_instruction(0x01, &gb.get_memory_slice_at(0, 0xFFFF)).unwrap();
     assert_eq!(opcode.size(), 2);
 
     assert_eq!(cycles, 12);
@@ -229,3 +229,31 @@ fn jmp_a16_takes_12_cycles_if_no_jump() {
 
     assert_eq!(cycles, 12);
 }
+
+#[test]
+fn jr_nc_jumps_when_carry_is_not_set() {
+    assert_eq!(
+        decode(&[0x30, 0x05]),
+        OpCode::new(
+            Category::JP,
+            [
+                Argument::JumpCondition(JumpCondition::NotCarry),
+                Argument::JumpDistance(0x05)
+            ]
+        )
+    );
+
+    let mut gb = Gameboy::new(vec![0x30, 0x05]);
+    let cycles = gb.step_once().unwrap();
+
+    assert_eq!(cycles, 12);
+    assert_eq!(gb.get_register_16(RegisterLabel16::ProgramCounter), 0x07);
+
+    // With the carry flag set the jump is skipped
+    let mut gb = Gameboy::new(vec![0x30, 0x05]);
+    gb.set_flag(Flags::C, true);
+    let cycles = gb.step_once().unwrap();
+
+    assert_eq!(cycles, 8);
+    assert_eq!(gb.get_register_16(RegisterLabel16::ProgramCounter), 0x02);
+}
@@ -132,7 +132,7 @@ mod load16_test {
         let _ = opcode.run(&mut cpu, MemoryAdapter::new(&mut memory));
 
         assert_eq!(read_flag(&cpu, Flags::C), true);
-        assert_eq!(read_flag(&cpu, Flags::H), false);
+        assert_eq!(read_flag(&cpu, Flags::H), true);
         assert_eq!(cpu.read_16_bits(RegisterLabel16::HL), 0x0103);
 
         // Make sure half carry works
@@ -145,6 +145,51 @@ mod load16_test {
         assert_eq!(read_flag(&cpu, Flags::C), false);
     }
 
+    fn run_spr8(sp: u16, offset: i8) -> CPU {
+        let mut memory = vec![0; 0xFFFF];
+        let mut cpu = CPU::new();
+        cpu.write_16_bits(RegisterLabel16::StackPointer, sp);
+        cpu.write_8_bits(RegisterLabel8::F, 0xFF);
+
+        let _ = create_ld16_sp_offset_opcode(offset).run(&mut cpu, MemoryAdapter::new(&mut memory));
+        cpu
+    }
+
+    #[test]
+    fn spr8_flags_come_from_the_low_byte() {
+        // The carries come from the low byte even with a large SP
+        let cpu = run_spr8(0xFFF8, 8);
+        assert_eq!(cpu.read_16_bits(RegisterLabel16::HL), 0x0000);
+        assert!(read_flag(&cpu, Flags::H));
+        assert!(read_flag(&cpu, Flags::C));
+        assert!(!read_flag(&cpu, Flags::Z));
+        assert!(!read_flag(&cpu, Flags::N));
+
+        let cpu = run_spr8(0x1234, 0x0B);
+        assert_eq!(cpu.read_16_bits(RegisterLabel16::HL), 0x123F);
+        assert!(!read_flag(&cpu, Flags::H));
+        assert!(!read_flag(&cpu, Flags::C));
+    }
+
+    #[test]
+    fn spr8_flags_with_negative_offsets() {
+        // -1 is added as 0xFF to the low byte
+        let cpu = run_spr8(0x0010, -1);
+        assert_eq!(cpu.read_16_bits(RegisterLabel16::HL), 0x000F);
+        assert!(!read_flag(&cpu, Flags::H));
+        assert!(read_flag(&cpu, Flags::C));
+
+        let cpu = run_spr8(0xFFFF, -1);
+        assert_eq!(cpu.read_16_bits(RegisterLabel16::HL), 0xFFFE);
+        assert!(read_flag(&cpu, Flags::H));
+        assert!(read_flag(&cpu, Flags::C));
+
+        let cpu = run_spr8(0x0000, -16);
+        assert_eq!(cpu.read_16_bits(RegisterLabel16::HL), 0xFFF0);
+        assert!(!read_flag(&cpu, Flags::H));
+        assert!(!read_flag(&cpu, Flags::C));
+    }
+
     fn ld_opcode(dest: RegisterLabel16, val: u16) -> OpCode {
         OpCode::new(
             Category::LD16,
@@ -12,12 +12,12 @@ mod load8_test {
         {
             // LD (HL-) A
             let mut gb = Gameboy::new(vec![0x32, 0x00]);
-            gb.set_register_16(RegisterLabel16::HL, 0x0001);
+            gb.set_register_16(RegisterLabel16::HL, 0xC001);
             gb.set_register_8(RegisterLabel8::A, 0x01);
             let cycles = gb.step_once().unwrap();
 
-            assert_eq!(gb.get_register_16(RegisterLabel16::HL), 0x0000);
-            assert_eq!(gb.get_memory_at(1), 0x01);
+            assert_eq!(gb.get_register_16(RegisterLabel16::HL), 0xC000);
+            assert_eq!(gb.get_memory_at(0xC001), 0x01);
             assert_eq!(gb.get_register_16(RegisterLabel16::ProgramCounter), 0x0001);
             assert_eq!(cycles, 8);
         }
@@ -59,14 +59,14 @@ mod load8_test {
     fn ld8_hl_plus_a() {
         // LD (HL+), A
         let mut gb = Gameboy::new(vec![0x22, 0x00]);
-        gb.set_register_16(RegisterLabel16::HL, 0x0001);
+        gb.set_register_16(RegisterLabel16::HL, 0xC001);
         gb.set_register_8(RegisterLabel8::A, 0x12);
 
         let cycles = gb.step_once().unwrap();
 
         assert_eq!(cycles, 8);
-        assert_eq!(gb.get_register_16(RegisterLabel16::HL), 0x02);
-        assert_eq!(gb.get_memory_at(0x01), 0x12);
+        assert_eq!(gb.get_register_16(RegisterLabel16::HL), 0xC002);
+        assert_eq!(gb.get_memory_at(0xC001), 0x12);
         assert_eq!(gb.get_register_16(RegisterLabel16::ProgramCounter), 0x01);
     }
 
@@ -85,8 +85,8 @@ mod load8_test {
 
     #[test]
     fn ldh_a_a8() {
-        let mut gb = Gameboy::new(vec![0xF0, 0x02]);
-        gb.set_memory_at(0xFF02, 0x34);
+        let mut gb = Gameboy::new(vec![0xF0, 0x82]);
+        gb.set_memory_at(0xFF82, 0x34);
 
         let cycles = gb.step_once().unwrap();
 
@@ -97,6 +97,20 @@ mod load8_test {
         assert_eq!(gb.get_register_16(RegisterLabel16::ProgramCounter), 0x02);
     }
 
+    #[test]
+    fn ldh_a_c() {
+        // LDH A (C)
+        let mut gb = Gameboy::new(vec![0xF2]);
+        gb.set_register_8(RegisterLabel8::C, 0x80);
+        gb.set_memory_at(0xFF80, 0x56);
+
+        let cycles = gb.step_once().unwrap();
+
+        assert_eq!(gb.get_register_8(RegisterLabel8::A), 0x56);
+        assert_eq!(cycles, 8);
+        assert_eq!(gb.get_register_16(RegisterLabel16::ProgramCounter), 0x01);
+    }
+
     #[test]
     fn ld8_into_address_address() {
         let mut gb = Gameboy::new(vec![0xEA, 0x10, 0x99]); // LD8 ($9910), A
@@ -124,13 +138,13 @@ mod load8_test {
     #[test]
     fn ld8_a_hlplus() {
         let mut gb = Gameboy::new(vec![0x2A]);
-        gb.set_register_16(RegisterLabel16::HL, 0xFF00);
-        gb.set_memory_at(0xFF00, 0x12);
+        gb.set_register_16(RegisterLabel16::HL, 0xFF80);
+        gb.set_memory_at(0xFF80, 0x12);
         let cycles = gb.step_once().unwrap();
 
         assert_eq!(cycles, 8);
         assert_eq!(gb.get_register_16(RegisterLabel16::ProgramCounter), 0x01);
-        assert_eq!(gb.get_register_16(RegisterLabel16::HL), 0xFF01);
+        assert_eq!(gb.get_register_16(RegisterLabel16::HL), 0xFF81);
         assert_eq!(gb.get_register_8(RegisterLabel8::A), 0x12);
     }
 
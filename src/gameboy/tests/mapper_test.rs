@@ -0,0 +1,164 @@
+use crate::gameboy::bus::{Bus, Memory};
+use crate::gameboy::cartridge::CartridgeError;
+use crate::gameboy::tests::cartridge_test::rom_with_header;
+use crate::gameboy::{Gameboy, RegisterLabel16};
+
+/// A ROM with every bank starting with the low byte of its bank number
+/// & the second byte holding the high byte
+fn banked_rom(cartridge_type: u8, rom_size: u8, ram_size: u8) -> Vec<u8> {
+    let mut rom = rom_with_header(cartridge_type, rom_size, ram_size);
+    for bank in 0..rom.len() / 0x4000 {
+        rom[bank * 0x4000] = bank as u8;
+        rom[bank * 0x4000 + 1] = (bank >> 8) as u8;
+    }
+    rom
+}
+
+#[test]
+fn rom_and_ram_carts_have_ram_always_enabled() {
+    let mut bus = Bus::new(&banked_rom(0x08, 0x00, 0x02)).unwrap();
+
+    bus.write(0xA000, 0x12);
+    bus.write(0xBFFF, 0x34);
+    assert_eq!(bus.read(0xA000), 0x12);
+    assert_eq!(bus.read(0xBFFF), 0x34);
+
+    // Writing to ROM does nothing
+    bus.write(0x2000, 0x01);
+    assert_eq!(bus.read(0x4000), 0x01);
+}
+
+#[test]
+fn rom_only_carts_without_ram_read_0xff() {
+    let mut bus = Bus::new(&banked_rom(0x00, 0x00, 0x00)).unwrap();
+
+    bus.write(0xA000, 0x12);
+    assert_eq!(bus.read(0xA000), 0xFF);
+}
+
+#[test]
+fn mbc2_uses_address_bit_8_to_pick_the_register() {
+    let mut bus = Bus::new(&banked_rom(0x06, 0x03, 0x00)).unwrap();
+
+    // Bit 8 clear enables RAM
+    bus.write(0x2000, 0x0A);
+    assert_eq!(bus.read(0x4000), 0x01);
+    bus.write(0xA000, 0x05);
+    assert_eq!(bus.read(0xA000), 0xF5);
+
+    // Bit 8 set selects the ROM bank
+    bus.write(0x0100, 0x0A);
+    assert_eq!(bus.read(0x4000), 0x0A);
+    assert_eq!(bus.read(0xA000), 0xF5);
+
+    bus.write(0x3FFF, 0x00);
+    assert_eq!(bus.read(0x4000), 0x01);
+
+    bus.write(0x0000, 0x00);
+    assert_eq!(bus.read(0xA000), 0xFF);
+}
+
+#[test]
+fn mbc2_ram_is_4_bits_and_repeats() {
+    let mut bus = Bus::new(&banked_rom(0x06, 0x00, 0x00)).unwrap();
+    bus.write(0x0000, 0x0A);
+
+    bus.write(0xA001, 0xAB);
+    assert_eq!(bus.read(0xA001), 0xFB);
+    assert_eq!(bus.read(0xA201), 0xFB);
+    assert_eq!(bus.read(0xBE01), 0xFB);
+}
+
+#[test]
+fn mbc5_uses_9_bit_rom_banks() {
+    // 8MiB
+    let mut bus = Bus::new(&banked_rom(0x19, 0x08, 0x00)).unwrap();
+
+    bus.write(0x2000, 0x23);
+    bus.write(0x3000, 0x01);
+    assert_eq!(bus.read(0x4000), 0x23);
+    assert_eq!(bus.read(0x4001), 0x01);
+
+    // Bank 0 can be mapped to 0x4000-0x7FFF
+    bus.write(0x2000, 0x00);
+    bus.write(0x3000, 0x00);
+    assert_eq!(bus.read(0x4000), 0x00);
+    assert_eq!(bus.read(0x4001), 0x00);
+}
+
+#[test]
+fn mbc5_has_16_ram_banks() {
+    // 128KiB of RAM
+    let mut bus = Bus::new(&banked_rom(0x1B, 0x00, 0x04)).unwrap();
+
+    bus.write(0xA000, 0x12);
+    assert_eq!(bus.read(0xA000), 0xFF);
+
+    bus.write(0x0000, 0x0A);
+    bus.write(0x4000, 0x0F);
+    bus.write(0xA000, 0x0F);
+    bus.write(0x4000, 0x01);
+    bus.write(0xA000, 0x01);
+
+    bus.write(0x4000, 0x0F);
+    assert_eq!(bus.read(0xA000), 0x0F);
+    bus.write(0x4000, 0x01);
+    assert_eq!(bus.read(0xA000), 0x01);
+}
+
+#[test]
+fn mbc5_rumble_uses_bit_3_of_the_ram_bank() {
+    let mut bus = Bus::new(&banked_rom(0x1E, 0x00, 0x03)).unwrap();
+    bus.write(0x0000, 0x0A);
+
+    bus.write(0x4000, 0x01);
+    bus.write(0xA000, 0x12);
+    assert!(!bus.is_rumbling());
+
+    bus.write(0x4000, 0x09);
+    assert!(bus.is_rumbling());
+    assert_eq!(bus.read(0xA000), 0x12);
+
+    bus.write(0x4000, 0x01);
+    assert!(!bus.is_rumbling());
+}
+
+#[test]
+fn unsupported_cartridge_types_are_an_error() {
+    // HuC1
+    let result = Bus::new(&banked_rom(0xFF, 0x00, 0x00));
+    assert!(matches!(
+        result,
+        Err(CartridgeError::UnsupportedCartridgeType(0xFF))
+    ));
+}
+
+#[test]
+fn save_data_is_the_cartridge_ram() {
+    let mut bus = Bus::new(&banked_rom(0x03, 0x00, 0x03)).unwrap();
+    bus.write(0x0000, 0x0A);
+    bus.write(0xA010, 0x12);
+
+    let save = bus.get_save_data();
+    assert_eq!(save.len(), 0x8000);
+    assert_eq!(save[0x10], 0x12);
+
+    let mut loaded = Bus::new(&banked_rom(0x03, 0x00, 0x03)).unwrap();
+    loaded.load_save_data(&save);
+    loaded.write(0x0000, 0x0A);
+    assert_eq!(loaded.read(0xA010), 0x12);
+}
+
+#[test]
+fn writing_cartridge_ram_marks_the_save_dirty() {
+    // LD (HL) A
+    let mut gb = Gameboy::new(banked_rom(0x09, 0x00, 0x02));
+    gb.set_memory_at(0x0000, 0x77);
+    gb.set_register_16(RegisterLabel16::HL, 0xA000);
+
+    assert!(!gb.take_save_dirty());
+
+    gb.step_once();
+    assert!(gb.take_save_dirty());
+    assert!(!gb.take_save_dirty());
+}
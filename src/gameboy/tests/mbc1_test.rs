@@ -0,0 +1,141 @@
+use crate::gameboy::bus::{Bus, Memory};
+use crate::gameboy::tests::cartridge_test::rom_with_header;
+use crate::gameboy::{Gameboy, RegisterLabel8};
+
+/// An MBC1+RAM+BATTERY ROM with every bank starting with its bank number
+fn mbc1_rom(rom_size: u8, ram_size: u8) -> Vec<u8> {
+    let mut rom = rom_with_header(0x03, rom_size, ram_size);
+    for bank in 0..rom.len() / 0x4000 {
+        rom[bank * 0x4000] = bank as u8;
+    }
+    rom
+}
+
+#[test]
+fn rom_bank_is_selected_through_0x2000() {
+    let mut bus = Bus::new(&mbc1_rom(0x04, 0x00)).unwrap();
+
+    // Bank 1 is mapped to begin with
+    assert_eq!(bus.read(0x4000), 0x01);
+
+    bus.write(0x2000, 0x05);
+    assert_eq!(bus.read(0x4000), 0x05);
+    assert_eq!(bus.read(0x0000), 0x00);
+
+    // Selecting bank 0 gives bank 1
+    bus.write(0x3FFF, 0x00);
+    assert_eq!(bus.read(0x4000), 0x01);
+
+    // Only the bottom 5 bits are used
+    bus.write(0x2000, 0xE3);
+    assert_eq!(bus.read(0x4000), 0x03);
+}
+
+#[test]
+fn rom_bank_is_masked_to_the_rom_size() {
+    // 256KiB has 16 banks
+    let mut bus = Bus::new(&mbc1_rom(0x03, 0x00)).unwrap();
+
+    bus.write(0x2000, 0x1F);
+    assert_eq!(bus.read(0x4000), 0x0F);
+
+    // Bank 0x10 wraps around to bank 0 because only the full 5 bits are checked for 0
+    bus.write(0x2000, 0x10);
+    assert_eq!(bus.read(0x4000), 0x00);
+}
+
+#[test]
+fn upper_bits_select_large_rom_banks() {
+    // 2MiB
+    let mut bus = Bus::new(&mbc1_rom(0x06, 0x00)).unwrap();
+
+    bus.write(0x4000, 0x01);
+    bus.write(0x2000, 0x00);
+    assert_eq!(bus.read(0x4000), 0x21);
+    assert_eq!(bus.read(0x0000), 0x00);
+
+    // In mode 1 the upper bits also apply to 0x0000-0x3FFF
+    bus.write(0x6000, 0x01);
+    assert_eq!(bus.read(0x0000), 0x20);
+
+    bus.write(0x4000, 0x03);
+    assert_eq!(bus.read(0x0000), 0x60);
+    assert_eq!(bus.read(0x4000), 0x61);
+}
+
+#[test]
+fn ram_must_be_enabled() {
+    let mut bus = Bus::new(&mbc1_rom(0x00, 0x02)).unwrap();
+
+    bus.write(0xA000, 0x12);
+    assert_eq!(bus.read(0xA000), 0xFF);
+
+    bus.write(0x0000, 0x0A);
+    bus.write(0xA000, 0x12);
+    assert_eq!(bus.read(0xA000), 0x12);
+
+    // Any value without 0xA in the low bits disables RAM
+    bus.write(0x1FFF, 0x1B);
+    assert_eq!(bus.read(0xA000), 0xFF);
+
+    bus.write(0x1FFF, 0xFA);
+    assert_eq!(bus.read(0xA000), 0x12);
+}
+
+#[test]
+fn ram_banks_are_selected_in_mode_1() {
+    // 32KiB of RAM
+    let mut bus = Bus::new(&mbc1_rom(0x00, 0x03)).unwrap();
+    bus.write(0x0000, 0x0A);
+
+    bus.write(0xA000, 0x01);
+
+    // In mode 0 bank 0 is always used
+    bus.write(0x4000, 0x02);
+    assert_eq!(bus.read(0xA000), 0x01);
+
+    bus.write(0x6000, 0x01);
+    assert_eq!(bus.read(0xA000), 0x00);
+    bus.write(0xA000, 0x02);
+
+    bus.write(0x6000, 0x00);
+    assert_eq!(bus.read(0xA000), 0x01);
+
+    bus.write(0x6000, 0x01);
+    assert_eq!(bus.read(0xA000), 0x02);
+}
+
+#[test]
+fn multicarts_use_4_bit_rom_banks() {
+    // 1MiB with a second header at bank 0x10
+    let mut rom = mbc1_rom(0x05, 0x00);
+    let logo = [0xCE, 0xED, 0x66, 0x66, 0xCC, 0x0D, 0x00, 0x0B];
+    rom[0x104..0x10C].copy_from_slice(&logo);
+    rom[0x40104..0x4010C].copy_from_slice(&logo);
+
+    let mut bus = Bus::new(&rom).unwrap();
+
+    bus.write(0x4000, 0x01);
+    bus.write(0x2000, 0x12);
+    assert_eq!(bus.read(0x4000), 0x12);
+
+    // The second game's bank 0 is mapped in mode 1
+    bus.write(0x6000, 0x01);
+    assert_eq!(bus.read(0x0000), 0x10);
+}
+
+#[test]
+fn cpu_can_switch_banks() {
+    // LD A 0x02
+    // LD (0x2000) A
+    // LD A (0x4000)
+    let mut rom = mbc1_rom(0x02, 0x00);
+    rom[0x00..0x08].copy_from_slice(&[0x3E, 0x02, 0xEA, 0x00, 0x20, 0xFA, 0x00, 0x40]);
+
+    let mut gb = Gameboy::new(rom);
+    gb.step_once();
+    gb.step_once();
+    gb.step_once();
+
+    assert_eq!(gb.get_register_8(RegisterLabel8::A), 0x02);
+}
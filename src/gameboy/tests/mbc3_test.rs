@@ -0,0 +1,176 @@
+use std::cell::Cell;
+use std::rc::Rc;
+
+use crate::gameboy::bus::{Bus, Memory};
+use crate::gameboy::mbc::rtc::Clock;
+use crate::gameboy::mbc::{Mapper, Mbc3};
+use crate::gameboy::tests::cartridge_test::rom_with_header;
+
+const CYCLES_PER_SECOND: u32 = 4_194_304;
+
+/// A clock which only moves when the test says so
+#[derive(Clone)]
+struct TestClock {
+    time: Rc<Cell<u64>>,
+}
+
+impl Clock for TestClock {
+    fn now(&self) -> u64 {
+        self.time.get()
+    }
+}
+
+fn test_clock() -> TestClock {
+    TestClock {
+        time: Rc::new(Cell::new(1_000_000)),
+    }
+}
+
+/// MBC3+TIMER+RAM+BATTERY with the RAM enabled
+fn mbc3_with_clock(clock: &TestClock) -> Mbc3 {
+    let rom = rom_with_header(0x10, 0x00, 0x03);
+    let mut mbc = Mbc3::new(&rom, 0x8000, true, Box::new(clock.clone()));
+    mbc.write_control(0x0000, 0x0A);
+    mbc
+}
+
+fn latch(mbc: &mut Mbc3) {
+    mbc.write_control(0x6000, 0x00);
+    mbc.write_control(0x6000, 0x01);
+}
+
+fn read_rtc(mbc: &mut Mbc3, register: u8) -> u8 {
+    mbc.write_control(0x4000, register);
+    mbc.read_ram(0xA000)
+}
+
+fn write_rtc(mbc: &mut Mbc3, register: u8, value: u8) {
+    mbc.write_control(0x4000, register);
+    mbc.write_ram(0xA000, value);
+}
+
+#[test]
+fn rom_banks_use_7_bits() {
+    // 2MiB MBC3+RAM+BATTERY
+    let mut rom = rom_with_header(0x13, 0x06, 0x02);
+    rom[0x45 * 0x4000] = 0x45;
+    rom[0x4000] = 0x01;
+    let mut bus = Bus::new(&rom).unwrap();
+
+    bus.write(0x2000, 0x45);
+    assert_eq!(bus.read(0x4000), 0x45);
+
+    bus.write(0x2000, 0x00);
+    assert_eq!(bus.read(0x4000), 0x01);
+}
+
+#[test]
+fn ram_banks_are_selected_through_0x4000() {
+    let mut mbc = mbc3_with_clock(&test_clock());
+
+    mbc.write_control(0x4000, 0x00);
+    mbc.write_ram(0xA000, 0x12);
+    mbc.write_control(0x4000, 0x03);
+    mbc.write_ram(0xA000, 0x34);
+
+    mbc.write_control(0x4000, 0x00);
+    assert_eq!(mbc.read_ram(0xA000), 0x12);
+    mbc.write_control(0x4000, 0x03);
+    assert_eq!(mbc.read_ram(0xA000), 0x34);
+
+    mbc.write_control(0x0000, 0x00);
+    assert_eq!(mbc.read_ram(0xA000), 0xFF);
+}
+
+#[test]
+fn rtc_only_changes_when_latched() {
+    let mut mbc = mbc3_with_clock(&test_clock());
+
+    mbc.tick(CYCLES_PER_SECOND * 61);
+    assert_eq!(read_rtc(&mut mbc, 0x08), 0x00);
+
+    latch(&mut mbc);
+    assert_eq!(read_rtc(&mut mbc, 0x08), 0x01);
+    assert_eq!(read_rtc(&mut mbc, 0x09), 0x01);
+
+    // Writing 1 again doesn't latch
+    mbc.tick(CYCLES_PER_SECOND);
+    mbc.write_control(0x6000, 0x01);
+    assert_eq!(read_rtc(&mut mbc, 0x08), 0x01);
+}
+
+#[test]
+fn rtc_counts_into_days_and_sets_the_carry() {
+    let mut mbc = mbc3_with_clock(&test_clock());
+
+    write_rtc(&mut mbc, 0x08, 59);
+    write_rtc(&mut mbc, 0x09, 59);
+    write_rtc(&mut mbc, 0x0A, 23);
+    write_rtc(&mut mbc, 0x0B, 0xFF);
+    write_rtc(&mut mbc, 0x0C, 0x01);
+
+    mbc.tick(CYCLES_PER_SECOND);
+    latch(&mut mbc);
+
+    assert_eq!(read_rtc(&mut mbc, 0x08), 0);
+    assert_eq!(read_rtc(&mut mbc, 0x09), 0);
+    assert_eq!(read_rtc(&mut mbc, 0x0A), 0);
+    assert_eq!(read_rtc(&mut mbc, 0x0B), 0);
+    assert_eq!(read_rtc(&mut mbc, 0x0C), 0b1000_0000);
+}
+
+#[test]
+fn rtc_halt_stops_the_clock() {
+    let mut mbc = mbc3_with_clock(&test_clock());
+
+    write_rtc(&mut mbc, 0x0C, 0b0100_0000);
+    mbc.tick(CYCLES_PER_SECOND * 2);
+    latch(&mut mbc);
+    assert_eq!(read_rtc(&mut mbc, 0x08), 0);
+
+    write_rtc(&mut mbc, 0x0C, 0x00);
+    mbc.tick(CYCLES_PER_SECOND * 2);
+    latch(&mut mbc);
+    assert_eq!(read_rtc(&mut mbc, 0x08), 2);
+}
+
+#[test]
+fn rtc_is_saved_in_the_bgb_format() {
+    let mut mbc = mbc3_with_clock(&test_clock());
+    mbc.write_ram(0xA000, 0x12);
+    write_rtc(&mut mbc, 0x08, 30);
+    write_rtc(&mut mbc, 0x0B, 0x05);
+
+    let save = mbc.save_data();
+
+    assert_eq!(save.len(), 0x8000 + 48);
+    assert_eq!(save[0], 0x12);
+    assert_eq!(&save[0x8000..0x8004], &[30, 0, 0, 0]);
+    assert_eq!(&save[0x800C..0x8010], &[0x05, 0, 0, 0]);
+    // Latched registers
+    assert_eq!(&save[0x8014..0x8018], &[30, 0, 0, 0]);
+    assert_eq!(&save[0x8028..0x8030], &1_000_000u64.to_le_bytes());
+}
+
+#[test]
+fn rtc_catches_up_with_the_host_clock_on_load() {
+    let clock = test_clock();
+    let mut mbc = mbc3_with_clock(&clock);
+    mbc.write_ram(0xA000, 0x12);
+    write_rtc(&mut mbc, 0x08, 30);
+    let save = mbc.save_data();
+
+    // A day, an hour & 40 seconds later
+    clock.time.set(1_000_000 + 24 * 60 * 60 + 60 * 60 + 40);
+
+    let mut loaded = mbc3_with_clock(&clock);
+    loaded.load_save_data(&save);
+    latch(&mut loaded);
+
+    loaded.write_control(0x4000, 0x00);
+    assert_eq!(loaded.read_ram(0xA000), 0x12);
+    assert_eq!(read_rtc(&mut loaded, 0x08), 10);
+    assert_eq!(read_rtc(&mut loaded, 0x09), 1);
+    assert_eq!(read_rtc(&mut loaded, 0x0A), 1);
+    assert_eq!(read_rtc(&mut loaded, 0x0B), 1);
+}
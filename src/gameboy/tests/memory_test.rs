@@ -1,14 +1,186 @@
 use super::Gameboy;
+use crate::gameboy::bus::{Bus, Memory};
+use crate::gameboy::memory_adapter::MemoryAdapter;
+use crate::gameboy::tests::cartridge_test::rom_with_header;
+use crate::gameboy::{Labels, RegisterLabel16, RegisterLabel8};
 
 #[test]
 fn writing_to_dma_starts_copy() {
     // LD 0xFF46 A
     let mut gb = Gameboy::new(vec![0xE0, 0x46]);
-    gb.set_register_8(crate::gameboy::RegisterLabel8::A, 0x10);
+    gb.set_register_8(RegisterLabel8::A, 0x10);
     gb.set_memory_at(0x1001, 0x12);
 
-    // DMA will copy 0x1000-0x109F to FE00-FE9F
+    // DMA will start copying 0x1000-0x109F to FE00-FE9F
     gb.step_once();
 
     assert_eq!(gb.get_memory_at(0xFE01), 0x12);
 }
+
+fn bus_in_mode(mode: u8) -> Bus {
+    let mut bus = Bus::new(&rom_with_header(0x00, 0x00, 0x00)).unwrap();
+    bus.write_raw(Labels::LCD_STATUS, mode);
+    bus
+}
+
+#[test]
+fn vram_is_locked_while_drawing() {
+    let mut bus = bus_in_mode(3);
+    bus.write_raw(0x8000, 0x12);
+
+    bus.write(0x8000, 0x34);
+    assert_eq!(bus.read(0x8000), 0xFF);
+    assert_eq!(bus.read_raw(0x8000), 0x12);
+
+    // Work RAM isn't affected
+    bus.write(0xC000, 0x34);
+    assert_eq!(bus.read(0xC000), 0x34);
+}
+
+#[test]
+fn oam_is_locked_while_scanning_and_drawing() {
+    for mode in [2, 3] {
+        let mut bus = bus_in_mode(mode);
+        bus.write_raw(0xFE00, 0x12);
+
+        bus.write(0xFE00, 0x34);
+        assert_eq!(bus.read(0xFE00), 0xFF);
+        assert_eq!(bus.read_raw(0xFE00), 0x12);
+    }
+
+    // VRAM can still be used during OAM scan
+    let mut bus = bus_in_mode(2);
+    bus.write(0x8000, 0x34);
+    assert_eq!(bus.read(0x8000), 0x34);
+}
+
+#[test]
+fn vram_and_oam_are_unlocked_during_the_blanks() {
+    for mode in [0, 1] {
+        let mut bus = bus_in_mode(mode);
+
+        bus.write(0x8000, 0x12);
+        bus.write(0xFE00, 0x34);
+        assert_eq!(bus.read(0x8000), 0x12);
+        assert_eq!(bus.read(0xFE00), 0x34);
+    }
+}
+
+#[test]
+fn only_hram_can_be_used_during_dma() {
+    let mut bus = bus_in_mode(0);
+    bus.write_raw(0xC000, 0x12);
+
+    bus.start_dma(0xC0);
+
+    bus.write(0xC001, 0x34);
+    bus.write(0xFF80, 0x56);
+    assert_eq!(bus.read(0xC000), 0xFF);
+    assert_eq!(bus.read(0x0147), 0xFF);
+    assert_eq!(bus.read_raw(0xC001), 0x00);
+    assert_eq!(bus.read(0xFF80), 0x56);
+
+    // 160 M-cycles later everything is back
+    bus.tick_dma(636);
+    assert_eq!(bus.read(0xC000), 0xFF);
+    bus.tick_dma(4);
+    assert_eq!(bus.read(0xC000), 0x12);
+}
+
+#[test]
+fn dma_locks_the_bus_from_the_cpu() {
+    // LD 0xFF46 A
+    // LD A (HL)
+    let mut gb = Gameboy::new(vec![0xE0, 0x46]);
+    gb.set_memory_at(0xFF80, 0x7E);
+    gb.set_register_8(RegisterLabel8::A, 0xC0);
+    gb.set_register_16(RegisterLabel16::HL, 0xC000);
+    gb.set_memory_at(0xC000, 0x12);
+
+    gb.step_once();
+
+    // Running from HRAM can't see work RAM
+    gb.set_register_16(RegisterLabel16::ProgramCounter, 0xFF80);
+    gb.step_once();
+    assert_eq!(gb.get_register_8(RegisterLabel8::A), 0xFF);
+}
+
+#[test]
+fn writes_blocked_by_dma_have_no_side_effects() {
+    let mut bus = bus_in_mode(0);
+    bus.start_dma(0xC0);
+
+    let mut written = Vec::new();
+    {
+        let mut adapter = MemoryAdapter::new(&mut bus);
+        adapter.add_range_callback(0xFF00..=0xFF7F, |address, _| written.push(address));
+
+        adapter.set_memory_at(Labels::DIVIDER, 0x00);
+        adapter.set_memory_at(0xFF14, 0b1000_0000);
+        adapter.set_memory_at(Labels::BG_PALETTE, 0x00);
+
+        // Writing DMA again restarts the transfer
+        adapter.set_memory_at(Labels::DMA, 0xC1);
+    }
+
+    assert_eq!(written, vec![Labels::DMA]);
+    assert_eq!(bus.read_raw(Labels::DMA), 0xC1);
+}
+
+#[test]
+fn dma_copies_all_160_bytes_a_byte_per_m_cycle() {
+    let mut bus = bus_in_mode(0);
+    for offset in 0..0xA0 {
+        bus.write_raw(0xC100 + offset, offset as u8 + 1);
+    }
+
+    bus.start_dma(0xC1);
+
+    bus.tick_dma(8);
+    assert_eq!(bus.read_raw(0xFE01), 0x02);
+    assert_eq!(bus.read_raw(0xFE02), 0x00);
+
+    bus.tick_dma(628);
+    assert_eq!(bus.read_raw(0xFE9E), 0x9F);
+    assert_eq!(bus.read_raw(0xFE9F), 0x00);
+
+    bus.tick_dma(4);
+    assert_eq!(bus.read_raw(0xFE9F), 0xA0);
+    assert_eq!(bus.read(0xFE9F), 0xA0);
+}
+
+#[test]
+fn starting_dma_again_restarts_the_transfer() {
+    let mut bus = bus_in_mode(0);
+    bus.write_raw(0xC000, 0x12);
+    bus.write_raw(0xC100, 0x34);
+    bus.write_raw(0xC19F, 0x56);
+
+    bus.start_dma(0xC0);
+    bus.tick_dma(320);
+    assert_eq!(bus.read_raw(0xFE00), 0x12);
+
+    bus.start_dma(0xC1);
+    bus.tick_dma(320);
+    assert_eq!(bus.read(0xC000), 0xFF);
+    assert_eq!(bus.read_raw(0xFE00), 0x34);
+
+    bus.tick_dma(320);
+    assert_eq!(bus.read_raw(0xFE9F), 0x56);
+    assert_eq!(bus.read(0xC000), 0x12);
+}
+
+#[test]
+fn dma_from_above_0xdfff_reads_work_ram() {
+    let mut bus = bus_in_mode(0);
+    bus.write_raw(0xC000, 0x12);
+    bus.write_raw(0xDF00, 0x34);
+
+    bus.start_dma(0xE0);
+    bus.tick_dma(4);
+    assert_eq!(bus.read_raw(0xFE00), 0x12);
+
+    bus.start_dma(0xFF);
+    bus.tick_dma(4);
+    assert_eq!(bus.read_raw(0xFE00), 0x34);
+}
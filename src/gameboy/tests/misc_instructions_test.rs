@@ -1,7 +1,7 @@
 use crate::gameboy::{
     cpu::CPU,
     memory_adapter::MemoryAdapter,
-    opcodes::{Argument, Category},
+    opcodes::{Argument, Category, Decoder},
     read_flag,
     tests::decode_util::decode,
     write_flag, Flags, OpCode,
@@ -46,3 +46,68 @@ fn scf_sets_the_carry_flag_and_others() {
     assert_eq!(read_flag(&cpu, Flags::H), false);
     assert_eq!(read_flag(&cpu, Flags::C), true);
 }
+
+#[test]
+fn ccf_flips_the_carry_flag() {
+    let opcode = OpCode::new(Category::CCF, [Argument::None, Argument::None]);
+    assert_eq!(decode(&[0x3F]), opcode);
+
+    let mut cpu = CPU::new();
+    write_flag(&mut cpu, Flags::N, true);
+    write_flag(&mut cpu, Flags::H, true);
+    write_flag(&mut cpu, Flags::C, true);
+
+    let mut memory = vec![0x0; 0xFF];
+
+    let cycles = opcode
+        .run(&mut cpu, MemoryAdapter::new(&mut memory))
+        .unwrap();
+
+    assert_eq!(cycles, 4);
+    assert!(!read_flag(&cpu, Flags::N));
+    assert!(!read_flag(&cpu, Flags::H));
+    assert!(!read_flag(&cpu, Flags::C));
+
+    opcode.run(&mut cpu, MemoryAdapter::new(&mut memory));
+
+    assert!(read_flag(&cpu, Flags::C));
+}
+
+#[test]
+fn decode_halt_and_stop_instructions() {
+    let halt = OpCode::new(Category::HALT, [Argument::None, Argument::None]);
+    assert_eq!(decode(&[0x76]), halt);
+    assert_eq!(halt.size(), 1);
+
+    // STOP is followed by an extra byte which is skipped
+    let stop = decode(&[0x10, 0x00]);
+    assert_eq!(
+        stop,
+        OpCode::new(Category::STOP, [Argument::SmallValue(0x00), Argument::None])
+    );
+    assert_eq!(stop.size(), 2);
+}
+
+#[test]
+fn every_documented_opcode_can_be_decoded() {
+    // These codes don't exist on the gameboy CPU
+    let illegal_codes = [
+        0xD3, 0xDB, 0xDD, 0xE3, 0xE4, 0xEB, 0xEC, 0xED, 0xF4, 0xFC, 0xFD,
+    ];
+
+    for code in 0x00..=0xFFu8 {
+        // 0xCB is the prefix for the extended instruction set
+        if code == 0xCB {
+            continue;
+        }
+
+        let memory = [code, 0x00, 0x00];
+        let result = Decoder::decode_instruction(0x00, &memory);
+
+        if illegal_codes.contains(&code) {
+            assert!(result.is_err(), "{:#X} should not decode", code);
+        } else {
+            assert!(result.is_ok(), "{:#X} failed to decode", code);
+        }
+    }
+}
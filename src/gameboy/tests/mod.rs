@@ -2,25 +2,38 @@ mod adc_test;
 mod add_test;
 mod alu_test;
 mod and_test;
+mod boot_test;
 mod call_test;
+mod cartridge_test;
 mod cb_test;
 mod cp_test;
 mod cpl_test;
+mod daa_test;
 mod dec_test;
 mod decode_util;
+mod halt_test;
 mod inc_test;
 mod interrupt_instruction_tests;
+mod joypad_test;
 mod jump_test;
 mod load16_test;
 mod load8_test;
+mod mapper_test;
+mod mbc1_test;
+mod mbc3_test;
 mod memory_test;
 mod misc_instructions_test;
 mod opcode_printer_test;
 mod or;
+mod pixel_fifo_test;
 mod ppu_test;
 mod push_pop_test;
 mod ret_test;
+mod rotate_a_test;
+mod sbc_test;
+mod stat_test;
 mod sub_test;
+mod timer_test;
 mod timing;
 mod xor_test;
 
@@ -34,6 +47,11 @@ fn infinite_loop_gb() -> Gameboy<'static> {
     gb
 }
 
+/// Missing ROM reads as 0xFF (RST 38) so fill it with NOPs
+fn nop_gb() -> Gameboy<'static> {
+    Gameboy::new(vec![0x00; 0x8000])
+}
+
 use crate::gameboy::flags_register::*;
 use crate::gameboy::register::{RegisterLabel16, RegisterLabel8};
 use crate::gameboy::Labels;
@@ -84,7 +102,7 @@ fn bit_instruction() {
         assert_eq!(gb.get_flag(Flags::C), carry_flag); // The carry flag is unaffected
 
         assert_eq!(gb.get_register_16(RegisterLabel16::ProgramCounter), 0x2);
-        assert_eq!(cycles, 12);
+        assert_eq!(cycles, 8);
     }
     {
         // Check the bit flag when the bit is 0
@@ -93,7 +111,7 @@ fn bit_instruction() {
         let cycles = gb.step_once().unwrap();
 
         assert_eq!(gb.get_flag(Flags::Z), true);
-        assert_eq!(cycles, 12);
+        assert_eq!(cycles, 8);
     }
 }
 
@@ -206,7 +224,7 @@ fn get_memory_slice_at_works() {
 #[test]
 fn set_ff50_to_disable_bootloader() {
     let audio = |_| {};
-    let mut gb = Gameboy::new_with_bootloader(audio, &vec![0x01; 32_000]);
+    let mut gb = Gameboy::new_with_bootloader(audio, &vec![0x01; 32_000]).unwrap();
 
     assert_ne!(gb.get_memory_at(0x00), 0x01);
     gb.set_memory_at(Labels::BOOTLOADER_DISABLE, 1);
@@ -216,7 +234,7 @@ fn set_ff50_to_disable_bootloader() {
 #[test]
 fn run_ldh50_to_disable_bootloader() {
     let audio = |_| {};
-    let mut gb = Gameboy::new_with_bootloader(audio, &vec![0x01; 32_000]);
+    let mut gb = Gameboy::new_with_bootloader(audio, &vec![0x01; 32_000]).unwrap();
     gb.set_memory_at(0x0, 0xE0);
     gb.set_memory_at(0x01, 0x50);
 
@@ -224,3 +242,11 @@ fn run_ldh50_to_disable_bootloader() {
 
     assert_eq!(gb.get_memory_at(0x00), 0x01);
 }
+
+#[test]
+fn memory_slice_can_reach_the_end_of_memory() {
+    let gb = Gameboy::new(vec![]);
+
+    let slice = gb.get_memory_slice_at(0xFFF0, 16);
+    assert_eq!(slice.len(), 16);
+}
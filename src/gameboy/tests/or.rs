@@ -59,3 +59,22 @@ fn writing_to_a_works() {
     assert_eq!(gb.get_flag(Flags::Z), false);
     assert_eq!(gb.get_register_8(RegisterLabel8::A), 1);
 }
+
+#[test]
+fn or_hl_and_d8_instructions() {
+    // OR (HL), OR d8
+    let mut gb = Gameboy::new(vec![0xB6, 0xF6, 0b0000_0100]);
+    gb.set_register_8(RegisterLabel8::A, 0b0000_0001);
+    gb.set_register_16(RegisterLabel16::HL, 0x4000);
+    gb.set_memory_at(0x4000, 0b0000_0010);
+
+    let cycles = gb.step_once().unwrap();
+    assert_eq!(cycles, 8);
+    assert_eq!(gb.get_register_8(RegisterLabel8::A), 0b0000_0011);
+
+    let cycles = gb.step_once().unwrap();
+    assert_eq!(cycles, 8);
+    assert_eq!(gb.get_register_8(RegisterLabel8::A), 0b0000_0111);
+    assert_eq!(gb.get_register_16(RegisterLabel16::ProgramCounter), 0x03);
+    assert!(!gb.get_flag(Flags::Z));
+}
@@ -0,0 +1,122 @@
+use super::nop_gb;
+use crate::gameboy::{Gameboy, Labels, Renderer, ScreenColor};
+
+// Screen on, window on using 0x9C00, sprites on & tile data at 0x8000
+const LCDC_EVERYTHING: u8 = 0b1111_0011;
+
+/// A Gameboy running NOPs with a background, window & sprites set up
+fn scene_gb(renderer: Renderer) -> Gameboy<'static> {
+    let mut gb = nop_gb().with_renderer(renderer);
+    gb.set_memory_at(Labels::BG_PALETTE, 0b1110_0100);
+    gb.set_memory_at(Labels::OBJ_PALETTE_0, 0b1110_0100);
+
+    // Tile 1 has stripes of every color & tile 2 is solid color 3
+    let stripes = [0x55, 0x33];
+    for row in 0..8 {
+        gb.set_memory_at(Labels::CHARACTER_RAM_START + 0x10 + row * 2, stripes[0]);
+        gb.set_memory_at(Labels::CHARACTER_RAM_START + 0x11 + row * 2, stripes[1]);
+        gb.set_memory_at(Labels::CHARACTER_RAM_START + 0x20 + row * 2, 0xFF);
+        gb.set_memory_at(Labels::CHARACTER_RAM_START + 0x21 + row * 2, 0xFF);
+    }
+    for index in 0..32 {
+        gb.set_memory_at(Labels::BG_MAP_DATA_1_START + index, (index % 2) as u8);
+    }
+    gb.set_memory_at(Labels::BG_MAP_DATA_2_START, 1);
+
+    gb.set_memory_at(Labels::SCROLL_X, 3);
+    gb.set_memory_at(Labels::WINDOW_X, 7 + 100);
+
+    // One sprite in front of the background & one behind it
+    let sprites = [[16, 20, 2, 0x00], [16, 61, 2, 0x80]];
+    for (index, sprite) in sprites.iter().enumerate() {
+        for (offset, value) in sprite.iter().enumerate() {
+            gb.set_memory_at(Labels::OAM_START + (index * 4 + offset) as u16, *value);
+        }
+    }
+
+    gb.set_memory_at(Labels::LCD_CONTROLS, LCDC_EVERYTHING);
+    gb
+}
+
+/// Each NOP is 4 dots
+fn run_dots(gb: &mut Gameboy, dots: u32) {
+    for _ in 0..dots / 4 {
+        gb.step_once();
+    }
+}
+
+fn first_line(gb: &Gameboy) -> Vec<ScreenColor> {
+    gb.get_screen_data()[0..160].to_vec()
+}
+
+#[test]
+fn the_pixel_fifo_draws_the_same_as_the_scanline_renderer() {
+    let mut scanline = scene_gb(Renderer::Scanline);
+    let mut fifo = scene_gb(Renderer::PixelFifo);
+
+    run_dots(&mut scanline, 456);
+    run_dots(&mut fifo, 456);
+
+    assert_eq!(first_line(&fifo), first_line(&scanline));
+    assert_eq!(first_line(&fifo)[12..20], [ScreenColor::Black; 8]);
+    assert_eq!(
+        first_line(&fifo)[100..104],
+        [
+            ScreenColor::White,
+            ScreenColor::Light,
+            ScreenColor::Dark,
+            ScreenColor::Black
+        ]
+    );
+}
+
+#[test]
+fn palette_changes_during_drawing_show_up_partway_along_the_line() {
+    let mut gb = scene_gb(Renderer::PixelFifo);
+    gb.set_memory_at(Labels::LCD_CONTROLS, 0b1001_0001);
+    gb.set_memory_at(Labels::BG_MAP_DATA_1_START, 2);
+    gb.set_memory_at(Labels::BG_MAP_DATA_1_START + 19, 2);
+    gb.set_memory_at(Labels::SCROLL_X, 0);
+
+    // Part way through drawing
+    run_dots(&mut gb, 80 + 100);
+    gb.set_memory_at(Labels::BG_PALETTE, 0b0011_1111);
+    run_dots(&mut gb, 276);
+
+    let line = first_line(&gb);
+    assert_eq!(line[0], ScreenColor::Black);
+    assert_eq!(line[159], ScreenColor::White);
+}
+
+#[test]
+fn sprites_make_drawing_take_longer() {
+    let mut gb = scene_gb(Renderer::PixelFifo);
+    gb.set_memory_at(Labels::LCD_CONTROLS, LCDC_EVERYTHING & !0b0000_0010);
+
+    let mut with_sprites = scene_gb(Renderer::PixelFifo);
+
+    // 80 + 172 + SCX 3 + the window
+    run_dots(&mut gb, 264);
+    run_dots(&mut with_sprites, 264);
+
+    let mode = |gb: &Gameboy| gb.get_memory_at(Labels::LCD_STATUS) & 0b0000_0011;
+    assert_eq!(mode(&gb), 0);
+    assert_eq!(mode(&with_sprites), 3);
+
+    run_dots(&mut with_sprites, 12);
+    assert_eq!(mode(&with_sprites), 0);
+
+    // A third sprite at the same x as the first costs another 6 dots, like the scanline renderer
+    for renderer in [Renderer::PixelFifo, Renderer::Scanline] {
+        let mut stacked = scene_gb(renderer);
+        for (offset, value) in [16, 20, 1, 0x00].iter().enumerate() {
+            stacked.set_memory_at(Labels::OAM_START + 8 + offset as u16, *value);
+        }
+
+        run_dots(&mut stacked, 276);
+        assert_eq!(mode(&stacked), 3);
+
+        run_dots(&mut stacked, 8);
+        assert_eq!(mode(&stacked), 0);
+    }
+}
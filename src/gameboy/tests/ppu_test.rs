@@ -1,4 +1,5 @@
 use super::infinite_loop_gb;
+use crate::gameboy::ppu::TileMap;
 use crate::gameboy::{Gameboy, Labels, RegisterLabel8, RegisterLabel16, ScreenColor};
 
 // Default palette:
@@ -23,7 +24,7 @@ fn a_default_gameboy_will_have_a_white_screen() {
     let gb = Gameboy::new(vec![]);
 
     // Get the VRAM to find the set pixel
-    let pixels = gb.get_vram_data();
+    let pixels = gb.get_vram_data(TileMap::Map9800);
 
     assert_eq!(pixels.len(), 256 * 256);
 }
@@ -42,7 +43,7 @@ fn tiles_are_displayed_correctly() {
     gb.set_memory_at(Labels::BG_MAP_DATA_1_START, 0x01);
 
     // Get the VRAM to find the set pixel
-    let pixels = gb.get_vram_data();
+    let pixels = gb.get_vram_data(TileMap::Map9800);
     let desired_pixels = vec![ScreenColor::Black; 8];
     assert_eq!(pixels[0..8], desired_pixels[..]);
 }
@@ -64,7 +65,7 @@ fn tile_gets_drawn_the_right_way_around() {
     gb.set_memory_at(Labels::CHARACTER_RAM_START + 0xC, 0x42);
     gb.set_memory_at(Labels::CHARACTER_RAM_START + 0xE, 0x3C);
 
-    let pixels = gb.get_vram_data();
+    let pixels = gb.get_vram_data(TileMap::Map9800);
 
     let mut tile_pixels = Vec::<Vec<ScreenColor>>::new();
 
@@ -94,7 +95,11 @@ fn a_tile_with_multiple_shades_comes_out_correctly() {
     gb.set_memory_at(Labels::BG_PALETTE, DEFAULT_PALLETE);
     gb.set_memory_at(Labels::LCD_CONTROLS, 0b1001_0001);
 
-    let vram: Vec<ScreenColor> = gb.get_vram_data().into_iter().take(8).collect();
+    let vram: Vec<ScreenColor> = gb
+        .get_vram_data(TileMap::Map9800)
+        .into_iter()
+        .take(8)
+        .collect();
     assert_eq!(vram, colors(vec![0, 1, 2, 3, 0, 1, 2, 3]));
 }
 
@@ -121,7 +126,11 @@ fn setting_the_palette_color_sets_the_colors_of_the_sprites() {
     // 0, 1, 2, 3
     // So the resulting pixels should be WHITE, BLACK, BLACK, BLACK
 
-    let vram_pixels: Vec<ScreenColor> = gb.get_vram_data().into_iter().take(4).collect();
+    let vram_pixels: Vec<ScreenColor> = gb
+        .get_vram_data(TileMap::Map9800)
+        .into_iter()
+        .take(4)
+        .collect();
 
     assert_eq!(vram_pixels, colors(vec![0, 3, 3, 3]));
 }
@@ -147,7 +156,11 @@ fn use_instruction_to_load_a_palette() {
     // 0, 1, 2, 3
     // So the resulting pixels should be WHITE, LIGHT, DARK, BLACK
 
-    let vram_pixels: Vec<ScreenColor> = gb.get_vram_data().into_iter().take(4).collect();
+    let vram_pixels: Vec<ScreenColor> = gb
+        .get_vram_data(TileMap::Map9800)
+        .into_iter()
+        .take(4)
+        .collect();
 
     assert_eq!(vram_pixels, colors(vec![0, 1, 2, 3]));
 }
@@ -281,11 +294,11 @@ fn screen_draws_correctly_when_it_wraps_around_the_side_and_bottom() {
 
     print_screen_data(&screen_data);
 
-    // The image will be at 0, 0 of the screen
-    let mut first_pixels = vec![ScreenColor::White; 4];
-    first_pixels.copy_from_slice(&screen_data[0..4]);
+    // The map is 256 pixels wide so the image will be at 1, 1 of the screen
+    let mut first_pixels = vec![ScreenColor::White; 5];
+    first_pixels.copy_from_slice(&screen_data[160..165]);
 
-    assert_eq!(first_pixels, colors(vec![0, 1, 2, 3]));
+    assert_eq!(first_pixels, colors(vec![0, 0, 1, 2, 3]));
 }
 
 #[test]
@@ -296,15 +309,18 @@ fn test_that_vlank_is_triggered() {
     gb.set_memory_at(Labels::LCD_CONTROLS, 0b1001_0001);
     gb.set_memory_at(Labels::BG_PALETTE, DEFAULT_PALLETE);
 
+    // Setup the stack so the interrupt doesn't push over IE
+    gb.set_register_16(RegisterLabel16::StackPointer, 0xFFFE);
+
     // Enable vblank interrupt
     gb.set_memory_at(0xFFFF, 0b0000_0001);
 
     // Render the whole screen
-    for _ in 0..142 {
+    for _ in 0..141 {
         render_line(&mut gb);
     }
 
-    for _ in 0..31 {
+    for _ in 0..33 {
         gb.step_once();
     }
 
@@ -319,6 +335,9 @@ fn vblank_triggered_only_once() {
     gb.set_memory_at(Labels::LCD_CONTROLS, 0b1001_0001);
     gb.set_memory_at(Labels::BG_PALETTE, DEFAULT_PALLETE);
 
+    // Setup the stack so the interrupt doesn't push over IE
+    gb.set_register_16(RegisterLabel16::StackPointer, 0xFFFE);
+
     // Enable vblank interrupt
     gb.set_memory_at(0xFFFF, 0b0000_0001);
 
@@ -327,11 +346,11 @@ fn vblank_triggered_only_once() {
     gb.set_memory_at(0x41, 0xC9);
 
     // Render the whole screen
-    for _ in 0..142 {
+    for _ in 0..141 {
         render_line(&mut gb);
     }
 
-    for _ in 0..31 {
+    for _ in 0..33 {
         gb.step_once();
     }
 
@@ -370,7 +389,381 @@ fn lcdc_controls_set_where_tile_data_comes_from() {
 }
 
 #[test]
-fn lcdc_controls_where_tile_map_data_comes_from() {}
+fn signed_tile_indices_from_0x80_use_the_8800_block() {
+    let mut gb = ppu_infinite_loop_gb();
+    gb.set_memory_at(Labels::LCD_CONTROLS, 0b1000_0001);
+
+    // Tile 0x80 is at 0x8800 & tile 0xFF is at 0x8FF0
+    for (index, val) in solid_tile(3).iter().enumerate() {
+        gb.set_memory_at(0x8800 + index as u16, *val);
+    }
+    for (index, val) in solid_tile(1).iter().enumerate() {
+        gb.set_memory_at(0x8FF0 + index as u16, *val);
+    }
+    gb.set_memory_at(Labels::BG_MAP_DATA_1_START, 0x80);
+    gb.set_memory_at(Labels::BG_MAP_DATA_1_START + 1, 0xFF);
+
+    render_line(&mut gb);
+
+    let expected = colors([vec![3; 8], vec![1; 8]].concat());
+    assert_eq!(first_line(&gb)[0..16], expected[..]);
+    assert_eq!(gb.get_vram_data(TileMap::Map9800)[0..16], expected[..]);
+}
+
+#[test]
+fn lcdc_controls_where_tile_map_data_comes_from() {
+    let mut gb = ppu_infinite_loop_gb();
+    // Use the 9C00 map for the background
+    gb.set_memory_at(Labels::LCD_CONTROLS, 0b1001_1001);
+
+    add_sprite_to_vram(&mut gb, 1, &solid_tile(3));
+    add_sprite_to_vram(&mut gb, 2, &solid_tile(1));
+    gb.set_memory_at(Labels::BG_MAP_DATA_1_START, 1);
+    gb.set_memory_at(Labels::BG_MAP_DATA_2_START, 2);
+
+    render_line(&mut gb);
+
+    assert_eq!(first_line(&gb)[0..8], colors(vec![1; 8])[..]);
+
+    // Both maps can be viewed
+    assert_eq!(
+        gb.get_vram_data(TileMap::Map9800)[0..8],
+        colors(vec![3; 8])[..]
+    );
+    assert_eq!(
+        gb.get_vram_data(TileMap::Map9C00)[0..8],
+        colors(vec![1; 8])[..]
+    );
+}
+
+#[test]
+fn lcdc_bit_0_blanks_the_background_but_not_sprites() {
+    let mut gb = sprite_gb();
+    gb.set_memory_at(Labels::LCD_CONTROLS, LCDC_WITH_SPRITES & !0b0000_0001);
+
+    add_sprite_to_vram(&mut gb, 1, &solid_tile(3));
+    gb.set_memory_at(Labels::BG_MAP_DATA_1_START, 1);
+
+    // This would be hidden by the background if it was on
+    add_sprite_to_vram(&mut gb, 2, &solid_tile(1));
+    add_oam_entry(&mut gb, 0, 16, 12, 2, 0b1000_0000);
+
+    render_line(&mut gb);
+
+    assert_eq!(
+        first_line(&gb)[0..8],
+        colors(vec![0, 0, 0, 0, 1, 1, 1, 1])[..]
+    );
+}
+
+// Screen on, sprites on & tile data at 0x8000
+const LCDC_WITH_SPRITES: u8 = 0b1001_0011;
+
+/// A tile where every pixel has the given value
+fn solid_tile(value: u8) -> [u8; 16] {
+    let low = if value & 1 != 0 { 0xFF } else { 0x00 };
+    let high = if value & 2 != 0 { 0xFF } else { 0x00 };
+    let mut tile = [0; 16];
+    for row in tile.chunks_mut(2) {
+        row.copy_from_slice(&[low, high]);
+    }
+    tile
+}
+
+fn add_oam_entry(gb: &mut Gameboy, index: u16, y: u8, x: u8, tile: u8, flags: u8) {
+    let address = Labels::OAM_START + index * 4;
+    gb.set_memory_at(address, y);
+    gb.set_memory_at(address + 1, x);
+    gb.set_memory_at(address + 2, tile);
+    gb.set_memory_at(address + 3, flags);
+}
+
+fn sprite_gb() -> Gameboy<'static> {
+    let mut gb = ppu_infinite_loop_gb();
+    gb.set_memory_at(Labels::LCD_CONTROLS, LCDC_WITH_SPRITES);
+    gb.set_memory_at(Labels::OBJ_PALETTE_0, DEFAULT_PALLETE);
+    gb
+}
+
+fn first_line(gb: &Gameboy) -> Vec<ScreenColor> {
+    gb.get_screen_data()[0..160].to_vec()
+}
+
+#[test]
+fn sprites_are_drawn_over_the_background() {
+    let mut gb = sprite_gb();
+
+    // The ® symbol from the top of a tile with a sprite at screen (4, 0)
+    add_sprite_to_vram(&mut gb, 1, &[0x3C, 0x00]);
+    add_oam_entry(&mut gb, 0, 16, 12, 1, 0);
+
+    render_line(&mut gb);
+
+    assert_eq!(
+        first_line(&gb)[0..12],
+        colors(vec![0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 0, 0])[..]
+    );
+}
+
+#[test]
+fn sprites_are_hidden_when_lcdc_bit_1_is_clear() {
+    let mut gb = sprite_gb();
+    gb.set_memory_at(Labels::LCD_CONTROLS, LCDC_WITH_SPRITES & !0b0000_0010);
+
+    add_sprite_to_vram(&mut gb, 1, &solid_tile(3));
+    add_oam_entry(&mut gb, 0, 16, 8, 1, 0);
+
+    render_line(&mut gb);
+
+    assert_eq!(first_line(&gb)[0..8], colors(vec![0; 8])[..]);
+}
+
+#[test]
+fn sprites_can_be_flipped() {
+    let mut gb = sprite_gb();
+
+    // Only the top left pixel is set
+    let mut tile = [0; 16];
+    tile[0] = 0x80;
+    add_sprite_to_vram(&mut gb, 1, &tile);
+
+    // X flip moves the pixel to the right of the sprite
+    add_oam_entry(&mut gb, 0, 16, 8, 1, 0b0010_0000);
+    render_line(&mut gb);
+    assert_eq!(
+        first_line(&gb)[0..8],
+        colors(vec![0, 0, 0, 0, 0, 0, 0, 1])[..]
+    );
+
+    // Y flip moves the bottom row of the sprite to the top
+    let mut gb = sprite_gb();
+    let mut tile = [0; 16];
+    tile[14] = 0x80;
+    add_sprite_to_vram(&mut gb, 1, &tile);
+    add_oam_entry(&mut gb, 0, 16, 8, 1, 0b0100_0000);
+    render_line(&mut gb);
+    assert_eq!(
+        first_line(&gb)[0..8],
+        colors(vec![1, 0, 0, 0, 0, 0, 0, 0])[..]
+    );
+}
+
+#[test]
+fn sprites_use_the_palette_in_their_flags() {
+    let mut gb = sprite_gb();
+    gb.set_memory_at(Labels::OBJ_PALETTE_1, 0b0000_1000);
+
+    add_sprite_to_vram(&mut gb, 1, &solid_tile(1));
+    add_oam_entry(&mut gb, 0, 16, 8, 1, 0);
+    add_oam_entry(&mut gb, 1, 16, 16, 1, 0b0001_0000);
+
+    render_line(&mut gb);
+
+    let line = first_line(&gb);
+    assert_eq!(line[0..8], colors(vec![1; 8])[..]);
+    assert_eq!(line[8..16], colors(vec![2; 8])[..]);
+}
+
+#[test]
+fn background_priority_only_hides_sprites_behind_colors_1_to_3() {
+    let mut gb = sprite_gb();
+
+    // Background is color 0 for the first tile & color 2 for the second
+    add_sprite_to_vram(&mut gb, 2, &solid_tile(2));
+    gb.set_memory_at(Labels::BG_MAP_DATA_1_START + 1, 2);
+
+    add_sprite_to_vram(&mut gb, 1, &solid_tile(3));
+    add_oam_entry(&mut gb, 0, 16, 12, 1, 0b1000_0000);
+
+    render_line(&mut gb);
+
+    let line = first_line(&gb);
+    assert_eq!(line[0..8], colors(vec![0, 0, 0, 0, 3, 3, 3, 3])[..]);
+    assert_eq!(line[8..16], colors(vec![2; 8])[..]);
+}
+
+#[test]
+fn sprites_with_a_smaller_x_are_drawn_on_top() {
+    let mut gb = sprite_gb();
+
+    add_sprite_to_vram(&mut gb, 1, &solid_tile(1));
+    add_sprite_to_vram(&mut gb, 2, &solid_tile(2));
+
+    // The later OAM entry is further left so it wins
+    add_oam_entry(&mut gb, 0, 16, 12, 1, 0);
+    add_oam_entry(&mut gb, 1, 16, 8, 2, 0);
+
+    // With the same X the earlier OAM entry wins
+    add_oam_entry(&mut gb, 2, 16, 40, 2, 0);
+    add_oam_entry(&mut gb, 3, 16, 40, 1, 0);
+
+    render_line(&mut gb);
+
+    let line = first_line(&gb);
+    assert_eq!(
+        line[0..12],
+        colors(vec![2, 2, 2, 2, 2, 2, 2, 2, 1, 1, 1, 1])[..]
+    );
+    assert_eq!(line[32..40], colors(vec![2; 8])[..]);
+}
+
+#[test]
+fn transparent_sprite_pixels_show_the_sprite_below() {
+    let mut gb = sprite_gb();
+
+    // Left half is transparent
+    let mut tile = [0; 16];
+    tile[0] = 0x0F;
+    add_sprite_to_vram(&mut gb, 1, &tile);
+    add_sprite_to_vram(&mut gb, 2, &solid_tile(2));
+
+    add_oam_entry(&mut gb, 0, 16, 8, 1, 0);
+    add_oam_entry(&mut gb, 1, 16, 8, 2, 0);
+
+    render_line(&mut gb);
+
+    assert_eq!(
+        first_line(&gb)[0..8],
+        colors(vec![2, 2, 2, 2, 1, 1, 1, 1])[..]
+    );
+}
+
+#[test]
+fn only_10_sprites_are_drawn_on_a_line() {
+    let mut gb = sprite_gb();
+    add_sprite_to_vram(&mut gb, 1, &solid_tile(3));
+
+    // Sprites off the screen still count towards the limit
+    add_oam_entry(&mut gb, 0, 16, 0, 1, 0);
+    for index in 1..12 {
+        add_oam_entry(&mut gb, index, 16, index as u8 * 8, 1, 0);
+    }
+
+    render_line(&mut gb);
+
+    let line = first_line(&gb);
+    assert_eq!(line[64..72], colors(vec![3; 8])[..]);
+    assert_eq!(line[72..88], colors(vec![0; 16])[..]);
+}
+
+#[test]
+fn tall_sprites_use_2_tiles() {
+    let mut gb = sprite_gb();
+    gb.set_memory_at(Labels::LCD_CONTROLS, LCDC_WITH_SPRITES | 0b0000_0100);
+
+    add_sprite_to_vram(&mut gb, 2, &solid_tile(1));
+    add_sprite_to_vram(&mut gb, 3, &solid_tile(2));
+
+    // The bottom bit of the tile index is ignored
+    add_oam_entry(&mut gb, 0, 16, 8, 3, 0);
+    // Flipped so the bottom tile is on top
+    add_oam_entry(&mut gb, 1, 16, 16, 3, 0b0100_0000);
+    // Starts 8 lines above the screen so only the bottom tile is visible
+    add_oam_entry(&mut gb, 2, 8, 24, 2, 0);
+
+    render_line(&mut gb);
+
+    let line = first_line(&gb);
+    assert_eq!(line[0..8], colors(vec![1; 8])[..]);
+    assert_eq!(line[8..16], colors(vec![2; 8])[..]);
+    assert_eq!(line[16..24], colors(vec![2; 8])[..]);
+}
+
+// Screen on, window on using the 0x9C00 map & tile data at 0x8000
+const LCDC_WITH_WINDOW: u8 = 0b1111_0001;
+
+#[test]
+fn the_window_is_drawn_from_wx_minus_7() {
+    let mut gb = ppu_infinite_loop_gb();
+    gb.set_memory_at(Labels::LCD_CONTROLS, LCDC_WITH_WINDOW);
+
+    add_sprite_to_vram(&mut gb, 1, &solid_tile(3));
+    for index in 0..32 {
+        gb.set_memory_at(Labels::BG_MAP_DATA_2_START + index, 1);
+    }
+
+    gb.set_memory_at(Labels::WINDOW_X, 7 + 4);
+    gb.set_memory_at(Labels::WINDOW_Y, 0);
+
+    render_line(&mut gb);
+
+    let line = first_line(&gb);
+    assert_eq!(line[0..8], colors(vec![0, 0, 0, 0, 3, 3, 3, 3])[..]);
+    assert_eq!(line[152..160], colors(vec![3; 8])[..]);
+}
+
+#[test]
+fn the_window_uses_the_map_selected_by_lcdc_bit_6() {
+    let mut gb = ppu_infinite_loop_gb();
+    gb.set_memory_at(Labels::LCD_CONTROLS, LCDC_WITH_WINDOW & !0b0100_0000);
+
+    add_sprite_to_vram(&mut gb, 1, &solid_tile(2));
+    gb.set_memory_at(Labels::BG_MAP_DATA_1_START + 1, 1);
+    gb.set_memory_at(Labels::WINDOW_X, 7);
+
+    // The window doesn't scroll with the background
+    gb.set_memory_at(Labels::SCROLL_X, 8);
+
+    render_line(&mut gb);
+
+    let line = first_line(&gb);
+    assert_eq!(
+        line[0..16],
+        colors(vec![0, 0, 0, 0, 0, 0, 0, 0, 2, 2, 2, 2, 2, 2, 2, 2])[..]
+    );
+}
+
+#[test]
+fn the_window_is_hidden_above_wy_or_when_disabled() {
+    let mut gb = ppu_infinite_loop_gb();
+    gb.set_memory_at(Labels::LCD_CONTROLS, LCDC_WITH_WINDOW & !0b0010_0000);
+
+    add_sprite_to_vram(&mut gb, 1, &solid_tile(3));
+    gb.set_memory_at(Labels::BG_MAP_DATA_2_START, 1);
+    gb.set_memory_at(Labels::WINDOW_X, 7);
+
+    render_line(&mut gb);
+    assert_eq!(first_line(&gb)[0..8], colors(vec![0; 8])[..]);
+
+    gb.set_memory_at(Labels::LCD_CONTROLS, LCDC_WITH_WINDOW);
+    gb.set_memory_at(Labels::WINDOW_Y, 1);
+
+    // Restart the frame & draw line 0 again
+    gb.set_memory_at(Labels::LCDC_Y, 0);
+    render_line(&mut gb);
+    assert_eq!(first_line(&gb)[0..8], colors(vec![0; 8])[..]);
+}
+
+#[test]
+fn the_window_line_only_moves_when_the_window_is_drawn() {
+    let mut gb = ppu_infinite_loop_gb();
+    gb.set_memory_at(Labels::LCD_CONTROLS, LCDC_WITH_WINDOW);
+
+    // Window row 1 is color 1 & row 2 is color 2
+    let mut tile = [0; 16];
+    tile[2] = 0xFF;
+    tile[5] = 0xFF;
+    add_sprite_to_vram(&mut gb, 1, &tile);
+    gb.set_memory_at(Labels::BG_MAP_DATA_2_START, 1);
+    gb.set_memory_at(Labels::WINDOW_X, 7);
+
+    // Lines 0 & 1 use window rows 0 & 1
+    render_line(&mut gb);
+    render_line(&mut gb);
+
+    // Hide the window for line 2
+    gb.set_memory_at(Labels::WINDOW_X, 200);
+    render_line(&mut gb);
+
+    // Line 3 carries on from window row 2
+    gb.set_memory_at(Labels::WINDOW_X, 7);
+    render_line(&mut gb);
+
+    let screen = gb.get_screen_data();
+    assert_eq!(screen[160..168], colors(vec![1; 8])[..]);
+    assert_eq!(screen[320..328], colors(vec![0; 8])[..]);
+    assert_eq!(screen[480..488], colors(vec![2; 8])[..]);
+}
 
 fn render_line(gb: &mut Gameboy) {
     // Tick the gb for 456 clocks at which point the
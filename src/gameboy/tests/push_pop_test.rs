@@ -29,18 +29,41 @@ fn pop_instruction_moves_the_stack_pointer() {
     assert_eq!(bc, 0x2301);
 }
 
+#[test]
+fn pop_instruction_writes_into_the_given_register() {
+    // POP DE
+    let mut gb = Gameboy::new(vec![0xD1, 0x00, 0x01, 0x23]);
+    gb.set_register_16(RegisterLabel16::StackPointer, 0x02);
+
+    gb.step_once();
+
+    assert_eq!(gb.get_register_16(RegisterLabel16::DE), 0x2301);
+    assert_eq!(gb.get_register_16(RegisterLabel16::BC), 0x0000);
+}
+
+#[test]
+fn pop_af_ignores_the_lower_bits_of_f() {
+    // POP AF
+    let mut gb = Gameboy::new(vec![0xF1, 0x00, 0xFF, 0x12]);
+    gb.set_register_16(RegisterLabel16::StackPointer, 0x02);
+
+    gb.step_once();
+
+    assert_eq!(gb.get_register_16(RegisterLabel16::AF), 0x12F0);
+}
+
 #[test]
 fn push_instruction_tests_push_moves_2_bytes_onto_the_stack() {
     let mut gb = Gameboy::new(vec![0xC5, 0x00, 0x00]);
     gb.set_register_16(RegisterLabel16::BC, 0x1234);
-    gb.set_register_16(RegisterLabel16::StackPointer, 0x03);
+    gb.set_register_16(RegisterLabel16::StackPointer, 0xC003);
 
     let cycles = gb.step_once().unwrap();
 
-    assert_eq!(gb.get_memory_at(1), 0x34);
-    assert_eq!(gb.get_memory_at(2), 0x12);
+    assert_eq!(gb.get_memory_at(0xC001), 0x34);
+    assert_eq!(gb.get_memory_at(0xC002), 0x12);
 
-    assert_eq!(gb.get_register_16(RegisterLabel16::StackPointer), 0x01);
+    assert_eq!(gb.get_register_16(RegisterLabel16::StackPointer), 0xC001);
 
     // The cycles and the size are correct
     assert_eq!(cycles, 16);
@@ -0,0 +1,81 @@
+use crate::gameboy::opcodes::{Argument, Category};
+use crate::gameboy::tests::decode_util::decode;
+use crate::gameboy::{Flags, Gameboy, OpCode, RegisterLabel16, RegisterLabel8};
+
+#[test]
+fn decode_rotate_a_instructions() {
+    let op = |category| OpCode::new(category, [Argument::None, Argument::None]);
+
+    assert_eq!(decode(&[0x07]), op(Category::RLCA));
+    assert_eq!(decode(&[0x0F]), op(Category::RRCA));
+    assert_eq!(decode(&[0x17]), op(Category::RLA));
+    assert_eq!(decode(&[0x1F]), op(Category::RRA));
+}
+
+#[test]
+fn rlca_rotates_bit_7_into_bit_0_and_carry() {
+    let mut gb = Gameboy::new(vec![0x07]);
+
+    // Before run:
+    // C A
+    // 0 1000_0101
+    // After run:
+    // 1 0000_1011
+    gb.set_register_8(RegisterLabel8::A, 0b1000_0101);
+    gb.set_flag(Flags::Z, true);
+
+    let cycles = gb.step_once().unwrap();
+
+    assert_eq!(cycles, 4);
+    assert_eq!(gb.get_register_16(RegisterLabel16::ProgramCounter), 0x01);
+    assert_eq!(gb.get_register_8(RegisterLabel8::A), 0b0000_1011);
+    assert!(gb.get_flag(Flags::C));
+    assert!(!gb.get_flag(Flags::Z));
+    assert!(!gb.get_flag(Flags::N));
+    assert!(!gb.get_flag(Flags::H));
+}
+
+#[test]
+fn rrca_rotates_bit_0_into_bit_7_and_carry() {
+    let mut gb = Gameboy::new(vec![0x0F]);
+
+    // Before run:
+    // C A
+    // 0 0000_0011
+    // After run:
+    // 1 1000_0001
+    gb.set_register_8(RegisterLabel8::A, 0b0000_0011);
+
+    let cycles = gb.step_once().unwrap();
+
+    assert_eq!(cycles, 4);
+    assert_eq!(gb.get_register_8(RegisterLabel8::A), 0b1000_0001);
+    assert!(gb.get_flag(Flags::C));
+}
+
+#[test]
+fn rra_rotates_right_through_the_carry_flag() {
+    let mut gb = Gameboy::new(vec![0x1F, 0x1F]);
+
+    // Before run:
+    // C A
+    // 1 0101_0100
+    // After run:
+    // 0 1010_1010
+    gb.set_register_8(RegisterLabel8::A, 0b0101_0100);
+    gb.set_flag(Flags::C, true);
+
+    let cycles = gb.step_once().unwrap();
+
+    assert_eq!(cycles, 4);
+    assert_eq!(gb.get_register_8(RegisterLabel8::A), 0b1010_1010);
+    assert!(!gb.get_flag(Flags::C));
+
+    // The zero flag is never set even when A becomes zero
+    gb.set_register_8(RegisterLabel8::A, 0b0000_0001);
+    gb.step_once();
+
+    assert_eq!(gb.get_register_8(RegisterLabel8::A), 0x00);
+    assert!(gb.get_flag(Flags::C));
+    assert!(!gb.get_flag(Flags::Z));
+}
@@ -0,0 +1,113 @@
+use crate::gameboy::cpu::CPU;
+use crate::gameboy::memory_adapter::MemoryAdapter;
+use crate::gameboy::opcodes::{Argument, Category};
+use crate::gameboy::{
+    read_flag, write_flag, Flags, Gameboy, OpCode, RegisterLabel16, RegisterLabel8,
+};
+
+use super::decode_util::decode;
+
+fn sbc_r8(register: RegisterLabel8) -> OpCode {
+    OpCode::new(
+        Category::SBC,
+        [Argument::Register8Constant(register), Argument::None],
+    )
+}
+
+#[test]
+fn decode_sbc_instructions() {
+    assert_eq!(decode(&[0x98]), sbc_r8(RegisterLabel8::B));
+    assert_eq!(decode(&[0x99]), sbc_r8(RegisterLabel8::C));
+    assert_eq!(decode(&[0x9A]), sbc_r8(RegisterLabel8::D));
+    assert_eq!(decode(&[0x9B]), sbc_r8(RegisterLabel8::E));
+    assert_eq!(decode(&[0x9C]), sbc_r8(RegisterLabel8::H));
+    assert_eq!(decode(&[0x9D]), sbc_r8(RegisterLabel8::L));
+    assert_eq!(decode(&[0x9F]), sbc_r8(RegisterLabel8::A));
+
+    assert_eq!(
+        decode(&[0x9E]),
+        OpCode::new(
+            Category::SBC,
+            [
+                Argument::RegisterIndirect(RegisterLabel16::HL),
+                Argument::None
+            ]
+        )
+    );
+    assert_eq!(
+        decode(&[0xDE, 0x12]),
+        OpCode::new(Category::SBC, [Argument::SmallValue(0x12), Argument::None])
+    );
+}
+
+#[test]
+fn sbc_subtracts_the_carry_flag_as_well() {
+    let opcode = sbc_r8(RegisterLabel8::B);
+
+    let mut cpu = CPU::new();
+    let mut memory = vec![0x0; 0xFFFF];
+
+    cpu.write_8_bits(RegisterLabel8::A, 0x10);
+    cpu.write_8_bits(RegisterLabel8::B, 0x01);
+    write_flag(&mut cpu, Flags::C, true);
+
+    let cycles = opcode
+        .run(&mut cpu, MemoryAdapter::new(&mut memory))
+        .unwrap();
+
+    assert_eq!(cycles, 4);
+    assert_eq!(cpu.read_16_bits(RegisterLabel16::ProgramCounter), 0x01);
+    assert_eq!(cpu.read_8_bits(RegisterLabel8::A), 0x0E);
+
+    assert!(!read_flag(&cpu, Flags::Z));
+    assert!(read_flag(&cpu, Flags::N));
+    assert!(read_flag(&cpu, Flags::H));
+    assert!(!read_flag(&cpu, Flags::C));
+}
+
+#[test]
+fn sbc_sets_zero_and_carry_flags() {
+    let opcode = sbc_r8(RegisterLabel8::C);
+
+    let mut cpu = CPU::new();
+    let mut memory = vec![0x0; 0xFFFF];
+
+    // 0x05 - 0x04 - 1 = 0
+    cpu.write_8_bits(RegisterLabel8::A, 0x05);
+    cpu.write_8_bits(RegisterLabel8::C, 0x04);
+    write_flag(&mut cpu, Flags::C, true);
+
+    opcode.run(&mut cpu, MemoryAdapter::new(&mut memory));
+
+    assert_eq!(cpu.read_8_bits(RegisterLabel8::A), 0x00);
+    assert!(read_flag(&cpu, Flags::Z));
+    assert!(!read_flag(&cpu, Flags::C));
+
+    // 0x00 - 0x00 - 1 wraps around and borrows
+    cpu.write_8_bits(RegisterLabel8::C, 0x00);
+    write_flag(&mut cpu, Flags::C, true);
+
+    opcode.run(&mut cpu, MemoryAdapter::new(&mut memory));
+
+    assert_eq!(cpu.read_8_bits(RegisterLabel8::A), 0xFF);
+    assert!(!read_flag(&cpu, Flags::Z));
+    assert!(read_flag(&cpu, Flags::H));
+    assert!(read_flag(&cpu, Flags::C));
+}
+
+#[test]
+fn sbc_hl_and_d8_take_8_cycles() {
+    let mut gb = Gameboy::new(vec![0x9E, 0xDE, 0x02]);
+    gb.set_register_8(RegisterLabel8::A, 0x10);
+    gb.set_register_16(RegisterLabel16::HL, 0x4000);
+    gb.set_memory_at(0x4000, 0x03);
+
+    let cycles = gb.step_once().unwrap();
+    assert_eq!(cycles, 8);
+    assert_eq!(gb.get_register_8(RegisterLabel8::A), 0x0D);
+
+    let cycles = gb.step_once().unwrap();
+    assert_eq!(cycles, 8);
+    assert_eq!(gb.get_register_8(RegisterLabel8::A), 0x0B);
+    assert_eq!(gb.get_register_16(RegisterLabel16::ProgramCounter), 0x03);
+}
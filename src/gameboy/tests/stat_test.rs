@@ -0,0 +1,152 @@
+use super::nop_gb;
+use crate::gameboy::{Gameboy, Labels};
+
+// Screen on with the background at 0x8000
+const LCDC_ON: u8 = 0b1001_0001;
+
+const HBLANK_INTERRUPT: u8 = 0b0000_1000;
+const VBLANK_INTERRUPT: u8 = 0b0001_0000;
+const OAM_INTERRUPT: u8 = 0b0010_0000;
+const LYC_INTERRUPT: u8 = 0b0100_0000;
+
+/// A Gameboy running NOPs with the screen on
+fn stat_gb() -> Gameboy<'static> {
+    let mut gb = nop_gb();
+    gb.set_memory_at(Labels::LCD_CONTROLS, LCDC_ON);
+    gb
+}
+
+/// Each NOP is 4 dots
+fn run_dots(gb: &mut Gameboy, dots: u32) {
+    for _ in 0..dots / 4 {
+        gb.step_once();
+    }
+}
+
+fn mode(gb: &Gameboy) -> u8 {
+    gb.get_memory_at(Labels::LCD_STATUS) & 0b0000_0011
+}
+
+fn stat_requested(gb: &Gameboy) -> bool {
+    gb.get_memory_at(Labels::INTERRUPT_TRIGGER) & 0b0000_0010 != 0
+}
+
+#[test]
+fn each_line_goes_through_oam_scan_drawing_and_hblank() {
+    let mut gb = stat_gb();
+
+    run_dots(&mut gb, 4);
+    assert_eq!(mode(&gb), 2);
+
+    run_dots(&mut gb, 76);
+    assert_eq!(mode(&gb), 3);
+
+    // Drawing takes 172 dots without scrolling, sprites or the window
+    run_dots(&mut gb, 168);
+    assert_eq!(mode(&gb), 3);
+    run_dots(&mut gb, 4);
+    assert_eq!(mode(&gb), 0);
+
+    run_dots(&mut gb, 204);
+    assert_eq!(mode(&gb), 2);
+    assert_eq!(gb.get_memory_at(Labels::LCDC_Y), 1);
+}
+
+#[test]
+fn vblank_is_mode_1_for_the_last_10_lines() {
+    let mut gb = stat_gb();
+
+    run_dots(&mut gb, 144 * 456);
+    assert_eq!(gb.get_memory_at(Labels::LCDC_Y), 144);
+    assert_eq!(mode(&gb), 1);
+
+    run_dots(&mut gb, 9 * 456);
+    assert_eq!(gb.get_memory_at(Labels::LCDC_Y), 153);
+    assert_eq!(mode(&gb), 1);
+
+    run_dots(&mut gb, 456);
+    assert_eq!(gb.get_memory_at(Labels::LCDC_Y), 0);
+    assert_eq!(mode(&gb), 2);
+}
+
+#[test]
+fn scrolling_and_sprites_make_drawing_longer() {
+    let mut gb = stat_gb();
+    gb.set_memory_at(Labels::SCROLL_X, 4);
+    gb.set_memory_at(Labels::LCD_CONTROLS, LCDC_ON | 0b0000_0010);
+
+    // A sprite on line 0
+    gb.set_memory_at(Labels::OAM_START, 16);
+
+    // 80 + 172 + 4 + 6
+    run_dots(&mut gb, 260);
+    assert_eq!(mode(&gb), 3);
+    run_dots(&mut gb, 4);
+    assert_eq!(mode(&gb), 0);
+}
+
+#[test]
+fn the_coincidence_bit_is_set_when_ly_matches_lyc() {
+    let mut gb = stat_gb();
+    gb.set_memory_at(Labels::LY_COMPARE, 2);
+    gb.set_memory_at(Labels::LCD_STATUS, LYC_INTERRUPT);
+
+    run_dots(&mut gb, 456 + 4);
+    assert_eq!(gb.get_memory_at(Labels::LCD_STATUS) & 0b0000_0100, 0);
+    assert!(!stat_requested(&gb));
+
+    run_dots(&mut gb, 456);
+    assert_ne!(gb.get_memory_at(Labels::LCD_STATUS) & 0b0000_0100, 0);
+    assert!(stat_requested(&gb));
+}
+
+#[test]
+fn mode_interrupts_are_requested_when_enabled() {
+    let mut gb = stat_gb();
+    gb.set_memory_at(Labels::LCD_STATUS, VBLANK_INTERRUPT);
+
+    run_dots(&mut gb, 143 * 456);
+    assert!(!stat_requested(&gb));
+
+    run_dots(&mut gb, 456);
+    assert!(stat_requested(&gb));
+}
+
+#[test]
+fn stat_blocking_stops_back_to_back_interrupts() {
+    let mut gb = stat_gb();
+    gb.set_memory_at(Labels::LCD_STATUS, HBLANK_INTERRUPT | OAM_INTERRUPT);
+
+    // HBlank of line 0
+    run_dots(&mut gb, 256);
+    assert!(stat_requested(&gb));
+    gb.set_memory_at(Labels::INTERRUPT_TRIGGER, 0);
+
+    // OAM scan of line 1 follows straight on from HBlank so the line stays high
+    run_dots(&mut gb, 204);
+    assert_eq!(mode(&gb), 2);
+    assert!(!stat_requested(&gb));
+
+    // Drawing lowers the line so the next HBlank requests it again
+    run_dots(&mut gb, 80 + 172);
+    assert_eq!(mode(&gb), 0);
+    assert!(stat_requested(&gb));
+}
+
+#[test]
+fn turning_off_the_screen_reports_hblank() {
+    let mut gb = stat_gb();
+
+    run_dots(&mut gb, 100);
+    assert_eq!(mode(&gb), 3);
+
+    gb.set_memory_at(Labels::LCD_CONTROLS, 0);
+    run_dots(&mut gb, 4);
+    assert_eq!(mode(&gb), 0);
+    assert_eq!(gb.get_memory_at(Labels::LCDC_Y), 0);
+
+    // Turning it back on starts a new frame
+    gb.set_memory_at(Labels::LCD_CONTROLS, LCDC_ON);
+    run_dots(&mut gb, 4);
+    assert_eq!(mode(&gb), 2);
+}
@@ -94,4 +94,50 @@ mod sub_test {
         assert_eq!(decode(&[0x95]), sub_opcode(L));
         assert_eq!(decode(&[0x97]), sub_opcode(A));
     }
+
+    #[test]
+    fn sub_wraps_around_when_the_result_is_negative() {
+        let mut gb = Gameboy::new(vec![0x90]);
+        gb.set_register_8(RegisterLabel8::A, 0x01);
+        gb.set_register_8(RegisterLabel8::B, 0x02);
+
+        let _ = gb.step_once();
+
+        assert_eq!(gb.get_register_8(RegisterLabel8::A), 0xFF);
+        assert!(gb.get_flag(Flags::C));
+        assert!(gb.get_flag(Flags::H));
+    }
+
+    #[test]
+    fn sub_hl_and_d8_instructions() {
+        assert_eq!(
+            decode(&[0x96]),
+            OpCode::new(
+                Category::SUB,
+                [
+                    Argument::RegisterIndirect(RegisterLabel16::HL),
+                    Argument::None
+                ]
+            )
+        );
+        assert_eq!(
+            decode(&[0xD6, 0x01]),
+            OpCode::new(Category::SUB, [Argument::SmallValue(0x01), Argument::None])
+        );
+
+        // SUB (HL), SUB d8
+        let mut gb = Gameboy::new(vec![0x96, 0xD6, 0x01]);
+        gb.set_register_8(RegisterLabel8::A, 0x05);
+        gb.set_register_16(RegisterLabel16::HL, 0x4000);
+        gb.set_memory_at(0x4000, 0x02);
+
+        let cycles = gb.step_once().unwrap();
+        assert_eq!(cycles, 8);
+        assert_eq!(gb.get_register_8(RegisterLabel8::A), 0x03);
+
+        let cycles = gb.step_once().unwrap();
+        assert_eq!(cycles, 8);
+        assert_eq!(gb.get_register_8(RegisterLabel8::A), 0x02);
+        assert_eq!(gb.get_register_16(RegisterLabel16::ProgramCounter), 0x03);
+    }
 }
@@ -0,0 +1,153 @@
+use super::nop_gb;
+use crate::gameboy::{Gameboy, Labels, RegisterLabel16};
+
+/// Run a number of NOP instructions which take 4 cycles each
+fn run_nops(gb: &mut Gameboy, count: u32) {
+    for _ in 0..count {
+        gb.step_once();
+    }
+}
+
+#[test]
+fn div_increments_every_256_cycles() {
+    let mut gb = nop_gb();
+
+    run_nops(&mut gb, 63);
+    assert_eq!(gb.get_memory_at(Labels::DIVIDER), 0);
+
+    run_nops(&mut gb, 1);
+    assert_eq!(gb.get_memory_at(Labels::DIVIDER), 1);
+
+    run_nops(&mut gb, 64 * 2);
+    assert_eq!(gb.get_memory_at(Labels::DIVIDER), 3);
+}
+
+#[test]
+fn writing_to_div_resets_it() {
+    // 70 NOPs then LDH (0x04) A
+    let mut program = vec![0x00; 70];
+    program.extend_from_slice(&[0xE0, 0x04]);
+    program.resize(0x8000, 0x00);
+    let mut gb = Gameboy::new(program);
+
+    run_nops(&mut gb, 70);
+    assert_eq!(gb.get_memory_at(Labels::DIVIDER), 1);
+
+    gb.step_once();
+    assert_eq!(gb.get_memory_at(Labels::DIVIDER), 0);
+
+    run_nops(&mut gb, 64);
+    gb.set_memory_at(Labels::DIVIDER, 0x55);
+    assert_eq!(gb.get_memory_at(Labels::DIVIDER), 0);
+}
+
+#[test]
+fn tima_increments_at_the_selected_rate() {
+    let rates = [
+        (0b0000_0100, 1024),
+        (0b0000_0101, 16),
+        (0b0000_0110, 64),
+        (0b0000_0111, 256),
+    ];
+
+    for (control, cycles) in rates.iter() {
+        let mut gb = nop_gb();
+        gb.set_memory_at(Labels::TIMER_CONTROL, *control);
+
+        run_nops(&mut gb, cycles / 4 - 1);
+        assert_eq!(gb.get_memory_at(Labels::TIMER_COUNTER), 0);
+
+        run_nops(&mut gb, 1);
+        assert_eq!(gb.get_memory_at(Labels::TIMER_COUNTER), 1);
+
+        run_nops(&mut gb, cycles / 4);
+        assert_eq!(gb.get_memory_at(Labels::TIMER_COUNTER), 2);
+    }
+}
+
+#[test]
+fn tima_does_not_increment_when_disabled() {
+    let mut gb = nop_gb();
+    gb.set_memory_at(Labels::TIMER_CONTROL, 0b0000_0001);
+
+    run_nops(&mut gb, 100);
+    assert_eq!(gb.get_memory_at(Labels::TIMER_COUNTER), 0);
+}
+
+#[test]
+fn tima_is_reloaded_from_tma_one_machine_cycle_after_overflowing() {
+    let mut gb = nop_gb();
+    gb.set_memory_at(Labels::TIMER_COUNTER, 0xFF);
+    gb.set_memory_at(Labels::TIMER_MODULO, 0x20);
+    gb.set_memory_at(Labels::TIMER_CONTROL, 0b0000_0101);
+
+    run_nops(&mut gb, 4);
+
+    // TIMA reads 0 for one machine cycle
+    assert_eq!(gb.get_memory_at(Labels::TIMER_COUNTER), 0x00);
+    assert_eq!(gb.get_memory_at(Labels::INTERRUPT_TRIGGER), 0b0000_0000);
+
+    run_nops(&mut gb, 1);
+    assert_eq!(gb.get_memory_at(Labels::TIMER_COUNTER), 0x20);
+    assert_eq!(gb.get_memory_at(Labels::INTERRUPT_TRIGGER), 0b0000_0100);
+}
+
+#[test]
+fn writing_tima_during_the_reload_delay_cancels_the_reload() {
+    let mut gb = nop_gb();
+    gb.set_memory_at(Labels::TIMER_COUNTER, 0xFF);
+    gb.set_memory_at(Labels::TIMER_MODULO, 0x20);
+    gb.set_memory_at(Labels::TIMER_CONTROL, 0b0000_0101);
+
+    run_nops(&mut gb, 4);
+    gb.set_memory_at(Labels::TIMER_COUNTER, 0x10);
+    run_nops(&mut gb, 1);
+
+    assert_eq!(gb.get_memory_at(Labels::TIMER_COUNTER), 0x10);
+    assert_eq!(gb.get_memory_at(Labels::INTERRUPT_TRIGGER), 0b0000_0000);
+}
+
+#[test]
+fn resetting_div_can_increment_tima() {
+    let mut gb = nop_gb();
+    gb.set_memory_at(Labels::TIMER_CONTROL, 0b0000_0101);
+
+    // Bit 3 of the divider is now set
+    run_nops(&mut gb, 2);
+    assert_eq!(gb.get_memory_at(Labels::TIMER_COUNTER), 0);
+
+    // The selected bit falls to 0
+    gb.set_memory_at(Labels::DIVIDER, 0x00);
+    assert_eq!(gb.get_memory_at(Labels::TIMER_COUNTER), 1);
+}
+
+#[test]
+fn changing_tac_can_increment_tima() {
+    let mut gb = nop_gb();
+    gb.set_memory_at(Labels::TIMER_CONTROL, 0b0000_0101);
+
+    run_nops(&mut gb, 2);
+
+    // Bit 9 of the divider is 0 so the signal falls
+    gb.set_memory_at(Labels::TIMER_CONTROL, 0b0000_0100);
+    assert_eq!(gb.get_memory_at(Labels::TIMER_COUNTER), 1);
+}
+
+#[test]
+fn timer_overflow_calls_the_timer_interrupt() {
+    // EI then NOPs
+    let mut program = vec![0x00; 0x8000];
+    program[0] = 0xFB;
+    let mut gb = Gameboy::new(program);
+    gb.set_register_16(RegisterLabel16::StackPointer, 0xFFFE);
+    gb.set_memory_at(Labels::INTERRUPT_ENABLE, 0b0000_0100);
+    gb.set_memory_at(Labels::TIMER_COUNTER, 0xFF);
+    gb.set_memory_at(Labels::TIMER_CONTROL, 0b0000_0101);
+
+    // EI + 3 NOPs overflow TIMA & the next instruction reloads it
+    run_nops(&mut gb, 5);
+
+    let cycles = gb.step_once().unwrap();
+    assert_eq!(cycles, 20);
+    assert_eq!(gb.get_register_16(RegisterLabel16::ProgramCounter), 0x50);
+}
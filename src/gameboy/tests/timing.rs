@@ -4,7 +4,7 @@ mod ret_test {
 
     #[test]
     fn calling_tick_up_to_a_breakpoint_will_cause_the_gb_to_stop() {
-        let mut gb = Gameboy::new(vec![]); // Gameboy full of nop's
+        let mut gb = Gameboy::new(vec![0x00; 0x8000]); // Gameboy full of nop's
 
         let dt = 1.0 / 60.0;
         let breakpoints = vec![0x05];
@@ -31,7 +31,7 @@ mod ret_test {
 
     #[test]
     fn ticking_from_a_breakpoint_will_continue_to_the_next_breakpoint() {
-        let mut gb = Gameboy::new(vec![]);
+        let mut gb = Gameboy::new(vec![0x00; 0x8000]);
 
         let dt = 1.0 / 60.0;
         let breakpoints = vec![0x01, 0x03];
@@ -124,3 +124,36 @@ fn decode_xor_test() {
     assert_eq!(decode(&[0xAD]), xor_opcode(L));
     assert_eq!(decode(&[0xAF]), xor_opcode(A));
 }
+
+#[test]
+fn xor_hl_and_d8_instructions() {
+    assert_eq!(
+        decode(&[0xAE]),
+        OpCode::new(
+            Category::XOR,
+            [
+                Argument::RegisterIndirect(RegisterLabel16::HL),
+                Argument::None
+            ]
+        )
+    );
+    assert_eq!(
+        decode(&[0xEE, 0x0F]),
+        OpCode::new(Category::XOR, [Argument::SmallValue(0x0F), Argument::None])
+    );
+
+    // XOR (HL), XOR d8
+    let mut gb = Gameboy::new(vec![0xAE, 0xEE, 0xF0]);
+    gb.set_register_8(RegisterLabel8::A, 0xFF);
+    gb.set_register_16(RegisterLabel16::HL, 0x4000);
+    gb.set_memory_at(0x4000, 0x0F);
+
+    let cycles = gb.step_once().unwrap();
+    assert_eq!(cycles, 8);
+    assert_eq!(gb.get_register_8(RegisterLabel8::A), 0xF0);
+
+    let cycles = gb.step_once().unwrap();
+    assert_eq!(cycles, 8);
+    assert_eq!(gb.get_register_8(RegisterLabel8::A), 0x00);
+    assert!(gb.get_flag(Flags::Z));
+}
@@ -0,0 +1,127 @@
+use super::bus::Bus;
+use super::interrupt_controller::{request_interrupt, Interrupt};
+use super::memory_labels::Labels;
+
+/// Emulates DIV, TIMA, TMA & TAC.
+///
+/// DIV is the top 8 bits of an internal 16 bit counter which goes up every cycle.
+/// TIMA is incremented whenever the divider bit selected by TAC falls from 1 to 0.
+pub struct Timer {
+    divider: u16,
+    /// TIMA overflowed during the last machine cycle & is waiting to be reloaded from TMA
+    reload_pending: bool,
+    /// Times DIV bit 4 has fallen since the APU last took them
+    frame_sequencer_clocks: u32,
+}
+
+/// The timer only changes once every machine cycle
+const CYCLES_PER_STEP: u32 = 4;
+
+/// DIV bit 4 falling clocks the APU frame sequencer at 512Hz
+const FRAME_SEQUENCER_BIT: u16 = 0x1 << 12;
+
+impl Timer {
+    pub fn new() -> Timer {
+        Timer {
+            divider: 0,
+            reload_pending: false,
+            frame_sequencer_clocks: 0,
+        }
+    }
+
+    pub fn tick(&mut self, cycles: u32, memory: &mut Bus) {
+        for _ in 0..(cycles / CYCLES_PER_STEP) {
+            self.step(memory);
+        }
+    }
+
+    /// Writing any value to DIV resets the whole internal counter
+    pub fn reset_divider(&mut self, memory: &mut Bus) {
+        let previous_signal = timer_signal(self.divider, memory.read_raw(Labels::TIMER_CONTROL));
+
+        if self.divider & FRAME_SEQUENCER_BIT != 0 {
+            self.frame_sequencer_clocks += 1;
+        }
+
+        self.divider = 0;
+        memory.write_raw(Labels::DIVIDER, 0);
+
+        // Resetting the counter can cause a falling edge
+        if previous_signal {
+            self.increment_counter(memory);
+        }
+    }
+
+    /// Set the whole internal counter. Used to start in the state the boot ROM leaves it.
+    pub fn set_divider(&mut self, divider: u16, memory: &mut Bus) {
+        self.divider = divider;
+        memory.write_raw(Labels::DIVIDER, (divider >> 8) as u8);
+    }
+
+    /// Changing TAC can cause a falling edge on the selected bit
+    pub fn control_changed(&mut self, previous_control: u8, memory: &mut Bus) {
+        let previous_signal = timer_signal(self.divider, previous_control);
+        let signal = timer_signal(self.divider, memory.read_raw(Labels::TIMER_CONTROL));
+
+        if previous_signal && !signal {
+            self.increment_counter(memory);
+        }
+    }
+
+    /// The number of times the frame sequencer has been clocked since the last call
+    pub fn take_frame_sequencer_clocks(&mut self) -> u32 {
+        std::mem::replace(&mut self.frame_sequencer_clocks, 0)
+    }
+
+    /// Writing to TIMA while waiting for the reload cancels the reload & the interrupt
+    pub fn counter_written(&mut self) {
+        self.reload_pending = false;
+    }
+
+    fn step(&mut self, memory: &mut Bus) {
+        // TIMA is reloaded one machine cycle after it overflows
+        if self.reload_pending {
+            self.reload_pending = false;
+            memory.write_raw(Labels::TIMER_COUNTER, memory.read_raw(Labels::TIMER_MODULO));
+            request_interrupt(memory, Interrupt::Timer);
+        }
+
+        let control = memory.read_raw(Labels::TIMER_CONTROL);
+        let previous_signal = timer_signal(self.divider, control);
+        let previous_divider = self.divider;
+
+        self.divider = self.divider.wrapping_add(CYCLES_PER_STEP as u16);
+        memory.write_raw(Labels::DIVIDER, (self.divider >> 8) as u8);
+
+        if previous_divider & FRAME_SEQUENCER_BIT != 0 && self.divider & FRAME_SEQUENCER_BIT == 0 {
+            self.frame_sequencer_clocks += 1;
+        }
+
+        if previous_signal && !timer_signal(self.divider, control) {
+            self.increment_counter(memory);
+        }
+    }
+
+    fn increment_counter(&mut self, memory: &mut Bus) {
+        let (counter, overflow) = memory.read_raw(Labels::TIMER_COUNTER).overflowing_add(1);
+        memory.write_raw(Labels::TIMER_COUNTER, counter);
+
+        if overflow {
+            self.reload_pending = true;
+        }
+    }
+}
+
+/// Returns the divider bit selected by TAC ANDed with the timer enable bit
+fn timer_signal(divider: u16, control: u8) -> bool {
+    let enabled = (control & 0b0000_0100) != 0;
+
+    let bit = match control & 0b0000_0011 {
+        0b00 => 9, // 4096 Hz
+        0b01 => 3, // 262144 Hz
+        0b10 => 5, // 65536 Hz
+        _ => 7,    // 16384 Hz
+    };
+
+    enabled && (divider & (0x1 << bit)) != 0
+}
@@ -5,26 +5,32 @@ extern crate image as img;
 #[macro_use]
 extern crate lazy_static;
 
+mod config;
 mod debug_cli;
 mod gameboy;
+mod save_file;
 
+use crate::config::{Action, Config, ControllerInput, Hotkey, axis_inputs};
 use crate::debug_cli::{DebugControls, OpcodeWriter, update};
-use crate::gameboy::{Gameboy, ScreenColor, TickResult};
+use crate::gameboy::{Cartridge, Gameboy, Renderer, ScreenColor, StereoFrame, TickResult};
+use crate::save_file::SaveFile;
 use clap::{Arg, ArgAction, value_parser};
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
 use cpal::{SampleRate, StreamConfig};
-use fs::File;
 use graphics::{Image, Transformed};
-use std::fs;
-use std::io::Read;
+use std::collections::HashSet;
 use std::path::PathBuf;
 use std::sync::mpsc::{Receiver, channel};
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use gl::load_with;
 use glutin_window::GlutinWindow;
 use opengl_graphics::{GlGraphics, OpenGL, Texture, TextureSettings};
 use piston::event_loop::{EventSettings, Events};
-use piston::input::{RenderArgs, RenderEvent, UpdateArgs, UpdateEvent};
+use piston::input::{
+    Button, ControllerAxisEvent, PressEvent, ReleaseEvent, RenderArgs, RenderEvent, UpdateArgs,
+    UpdateEvent,
+};
 use piston::window::WindowSettings;
 use piston::{EventLoop, OpenGLWindow};
 
@@ -41,6 +47,9 @@ const SCREEN_WIDTH: u32 = 160;
 const SCREEN_HEIGHT: u32 = 144;
 const WINDOW_SCALING: u32 = 4;
 
+/// How many times faster than normal the emulator runs while fast forward is held
+const FAST_FORWARD_SPEED: f64 = 4.0;
+
 #[derive(PartialEq)]
 enum AppResult {
     Continue,
@@ -55,10 +64,17 @@ pub struct App<'a> {
     is_debug: bool,
     breakpoints: Vec<u16>,
     opcode_writer: Option<OpcodeCallback<'a>>,
+    save_file: Option<SaveFile>,
+    config: Config,
+    paused: bool,
+    fast_forward: bool,
+    /// Axis directions which are past the threshold, so only changes are acted on
+    held_axes: HashSet<ControllerInput>,
 }
 
 impl<'a> App<'a> {
-    fn render(&mut self, args: &RenderArgs) {
+    /// The screen as RGBA bytes
+    fn screen_buffer(&self) -> Vec<u8> {
         let mut buffer: Vec<u8> = vec![0x00; (SCREEN_WIDTH * SCREEN_HEIGHT * 4) as usize];
 
         // Put the screen data into the buffer
@@ -75,6 +91,11 @@ impl<'a> App<'a> {
                 buffer[scaled + 3] = val[3];
             });
 
+        buffer
+    }
+
+    fn render(&mut self, args: &RenderArgs) {
+        let buffer = self.screen_buffer();
         let canvas = img::ImageBuffer::from_vec(SCREEN_WIDTH, SCREEN_HEIGHT, buffer).unwrap();
 
         let mut texture_settings = TextureSettings::new();
@@ -95,7 +116,69 @@ impl<'a> App<'a> {
         });
     }
 
+    /// Write the screen to a PNG in the working directory
+    fn save_screenshot(&self) {
+        let seconds = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |time| time.as_secs());
+        let path = format!("screenshot-{}.png", seconds);
+
+        match img::save_buffer(
+            &path,
+            &self.screen_buffer(),
+            SCREEN_WIDTH,
+            SCREEN_HEIGHT,
+            img::ColorType::Rgba8,
+        ) {
+            Ok(_) => println!("Saved screenshot {}", path),
+            Err(err) => println!("Failed to save screenshot {} with error {}", path, err),
+        }
+    }
+
+    fn input(&mut self, button: Button, pressed: bool) {
+        let action = match button {
+            Button::Keyboard(key) => self.config.key_action(key),
+            Button::Controller(button) => self
+                .config
+                .controller_action(ControllerInput::Button(button.button)),
+            _ => None,
+        };
+
+        if let Some(action) = action {
+            self.action(action, pressed);
+        }
+    }
+
+    fn axis_moved(&mut self, axis: u8, position: f64) {
+        for (input, held) in axis_inputs(axis, position) {
+            let changed = if held {
+                self.held_axes.insert(input)
+            } else {
+                self.held_axes.remove(&input)
+            };
+
+            if changed && let Some(action) = self.config.controller_action(input) {
+                self.action(action, held);
+            }
+        }
+    }
+
+    fn action(&mut self, action: Action, pressed: bool) {
+        match action {
+            Action::Button(button) if pressed => self.gb.press_button(button),
+            Action::Button(button) => self.gb.release_button(button),
+            Action::Hotkey(Hotkey::Pause) if pressed => self.paused = !self.paused,
+            Action::Hotkey(Hotkey::FastForward) => self.fast_forward = pressed,
+            Action::Hotkey(Hotkey::Screenshot) if pressed => self.save_screenshot(),
+            Action::Hotkey(_) => {}
+        }
+    }
+
     fn update(&mut self, args: UpdateArgs) -> AppResult {
+        if self.paused {
+            return AppResult::Continue;
+        }
+
         if self.is_debug {
             let debug_controls = update(&self.gb, &mut self.breakpoints);
 
@@ -107,9 +190,14 @@ impl<'a> App<'a> {
         if self.is_debug {
             self.gb.step_once();
         } else {
+            let dt = if self.fast_forward {
+                args.dt * FAST_FORWARD_SPEED
+            } else {
+                args.dt
+            };
             let stop_reason =
                 self.gb
-                    .tick_with_breaks(args.dt, &self.breakpoints, &mut self.opcode_writer);
+                    .tick_with_breaks(dt, &self.breakpoints, &mut self.opcode_writer);
 
             match stop_reason {
                 TickResult::HitBreakpoint => {
@@ -121,6 +209,10 @@ impl<'a> App<'a> {
                 _ => {}
             }
         }
+
+        if let Some(save_file) = &mut self.save_file {
+            save_file.update(&mut self.gb);
+        }
         return AppResult::Continue;
     }
 }
@@ -131,27 +223,29 @@ fn build_audio_event_loop() -> impl DeviceTrait {
     host.default_output_device().unwrap()
 }
 
-fn create_audio_thread<T>(device: T, receiver: Receiver<i16>) -> Option<impl StreamTrait>
+fn create_audio_thread<T>(device: T, receiver: Receiver<StereoFrame>) -> Option<impl StreamTrait>
 where
     T: DeviceTrait + Send + Sync + 'static,
 {
     let my_config = StreamConfig {
-        channels: 1,
+        channels: 2,
         buffer_size: cpal::BufferSize::Default,
         sample_rate: SampleRate(44100),
     };
     device
         .build_output_stream(
             &my_config,
-            move |data, _| {
-                for elem in data.iter_mut() {
+            move |data: &mut [f32], _| {
+                // Samples are interleaved left then right
+                for elem in data.chunks_mut(2) {
                     // Keep pulling values until no more are left. Then add 0s
                     match receiver.recv() {
-                        Ok(data) => {
-                            *elem = data as f32 / 100.0;
+                        Ok(frame) => {
+                            elem[0] = frame.left as f32 / 100.0;
+                            elem[1] = frame.right as f32 / 100.0;
                         }
                         Err(_) => {
-                            *elem = 0.0;
+                            elem.fill(0.0);
                         }
                     }
                 }
@@ -162,11 +256,18 @@ where
         .ok()
 }
 
-fn load_rom(file_name: &str) -> std::io::Result<Vec<u8>> {
-    let mut rom_file = File::open(file_name)?;
-    let mut rom_data = Vec::new();
-    rom_file.read_to_end(&mut rom_data)?;
-    Ok(rom_data)
+/// DMG & MGB boot ROMs are 256 bytes & CGB boot ROMs are 2304 bytes
+fn load_boot_rom(path: &PathBuf) -> Result<Vec<u8>, String> {
+    let boot_rom = std::fs::read(path).map_err(|err| err.to_string())?;
+
+    match boot_rom.len() {
+        0x100 | 0x900 => Ok(boot_rom),
+        size => Err(format!(
+            "{} is {} bytes which isn't a boot ROM size",
+            path.display(),
+            size
+        )),
+    }
 }
 
 fn main() {
@@ -192,14 +293,66 @@ fn main() {
                 .value_parser(value_parser!(PathBuf))
                 .required(false),
         )
+        .arg(
+            Arg::new("save")
+                .short('s')
+                .long("save")
+                .help("Keep battery saves in FILE instead of next to the ROM")
+                .action(ArgAction::Set)
+                .value_name("FILE")
+                .value_parser(value_parser!(PathBuf))
+                .required(false),
+        )
+        .arg(
+            Arg::new("no-save")
+                .long("no-save")
+                .help("Don't load or write battery saves")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("save")
+                .required(false),
+        )
+        .arg(
+            Arg::new("boot-rom")
+                .long("boot-rom")
+                .help("Run a DMG, MGB or CGB boot ROM from FILE instead of the built in one")
+                .action(ArgAction::Set)
+                .value_name("FILE")
+                .value_parser(value_parser!(PathBuf))
+                .required(false),
+        )
+        .arg(
+            Arg::new("skip-boot")
+                .long("skip-boot")
+                .help("Start the game straight away in the state the boot ROM leaves")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("boot-rom")
+                .required(false),
+        )
+        .arg(
+            Arg::new("pixel-fifo")
+                .long("pixel-fifo")
+                .help("Draw the screen a dot at a time. Slower but shows mid line effects")
+                .action(ArgAction::SetTrue)
+                .required(false),
+        )
+        .arg(
+            Arg::new("config")
+                .short('c')
+                .long("config")
+                .help("Read key & controller bindings from FILE instead of the default config")
+                .action(ArgAction::Set)
+                .value_name("FILE")
+                .value_parser(value_parser!(PathBuf))
+                .required(false),
+        )
         .arg(Arg::new("ROM").required(true).help("Start with rom"))
         .get_matches();
 
     // Create a channel which takes audio data
-    let (sender, receiver) = channel::<i16>();
+    let (sender, receiver) = channel::<StereoFrame>();
 
     let mut sent_audio_error = false;
-    let audio_callback = move |val| match sender.send(val) {
+    let audio_callback = move |frame| match sender.send(frame) {
         Ok(_) => {}
         Err(err) => {
             if !sent_audio_error {
@@ -209,10 +362,66 @@ fn main() {
         }
     };
 
+    // Load the ROM
+    let rom_file_name = matches.get_one::<String>("ROM").unwrap();
+    let cartridge = match Cartridge::load(rom_file_name) {
+        Ok(cartridge) => cartridge,
+        Err(err) => {
+            println!("Failed to load ROM with error {}", err);
+            return;
+        }
+    };
+
+    let boot_rom = match matches.get_one::<PathBuf>("boot-rom").map(load_boot_rom) {
+        Some(Ok(boot_rom)) => Some(boot_rom),
+        Some(Err(err)) => {
+            println!("Failed to load boot ROM with error {}", err);
+            return;
+        }
+        None => None,
+    };
+
+    // A missing config in the default location means the default bindings are used
+    let config = match matches.get_one::<PathBuf>("config") {
+        Some(path) => Config::load(path).map_err(|err| (path.clone(), err)),
+        None => match Config::default_path() {
+            Some(path) if path.exists() => Config::load(&path).map_err(|err| (path, err)),
+            _ => Ok(Config::default()),
+        },
+    };
+    let config = match config {
+        Ok(config) => config,
+        Err((path, err)) => {
+            println!(
+                "Failed to load config {} with error {}",
+                path.display(),
+                err
+            );
+            return;
+        }
+    };
+
+    // The boot ROM refuses to start a game with a bad header checksum
+    if let Err(err) = cartridge.check_header_checksum() {
+        if matches.get_flag("skip-boot") {
+            println!("Warning: {}. The boot ROM wouldn't run this ROM", err);
+        } else {
+            println!(
+                "Failed to load ROM with error {}. Use --skip-boot to run it anyway",
+                err
+            );
+            return;
+        }
+    }
+
+    if cartridge.get_global_checksum() != cartridge.compute_global_checksum() {
+        println!("Warning: ROM global checksum doesn't match. The dump may be corrupt");
+    }
+
     let opengl = OpenGL::V3_2;
 
     let mut window: GlutinWindow = WindowSettings::new(
-        "Gameboy",
+        format!("Gameboy - {}", cartridge.get_title()),
         [
             gb_screen_width * WINDOW_SCALING,
             gb_screen_height * WINDOW_SCALING,
@@ -231,30 +440,64 @@ fn main() {
         .get_one::<PathBuf>("log")
         .map(|path| OpcodeWriter::new(path));
 
-    // Load the ROM
-    let rom_file_name = matches.get_one::<String>("ROM").unwrap();
-    let rom_data = load_rom(rom_file_name);
-
-    let rom_bytes = match rom_data {
-        Ok(data) => data,
-        Err(err) => {
-            println!("Failed to load ROM with error {}", err);
-            return;
-        }
-    };
-
     {
         let writer: Option<Box<dyn FnMut(u16, String)>> = opcode_writer.as_mut().map(|w| {
             Box::new(|address, opcode| w.store_opcode(address, opcode))
                 as Box<dyn FnMut(u16, String)>
         });
 
+        let rom_data = cartridge.get_rom_data();
+        let gb = if matches.get_flag("skip-boot") {
+            Gameboy::new_skip_boot(audio_callback, rom_data)
+        } else if let Some(boot_rom) = &boot_rom {
+            Gameboy::new_with_boot_rom(audio_callback, boot_rom, rom_data)
+        } else {
+            Gameboy::new_with_bootloader(audio_callback, rom_data)
+        };
+
+        let renderer = if matches.get_flag("pixel-fifo") {
+            Renderer::PixelFifo
+        } else {
+            Renderer::Scanline
+        };
+
+        let mut gb = match gb {
+            Ok(gb) => gb.with_renderer(renderer),
+            Err(err) => {
+                println!("Failed to load ROM with error {}", err);
+                return;
+            }
+        };
+
+        // Saves default to the ROM path with a .sav extension
+        let save_file = if cartridge.has_battery() && !matches.get_flag("no-save") {
+            let path = matches
+                .get_one::<PathBuf>("save")
+                .cloned()
+                .unwrap_or_else(|| PathBuf::from(rom_file_name).with_extension("sav"));
+            Some(SaveFile::new(path))
+        } else {
+            None
+        };
+
+        if let Some(save_file) = &save_file
+            && let Err(err) = save_file.load(&mut gb)
+        {
+            println!("Failed to load save with error {}", err);
+            return;
+        }
+
         let mut app = App {
             gl: GlGraphics::new(opengl),
-            gb: Gameboy::new_with_bootloader(audio_callback, &rom_bytes),
+            gb,
             is_debug,
             breakpoints: vec![],
             opcode_writer: writer,
+            save_file,
+            config,
+            paused: false,
+            fast_forward: false,
+            held_axes: HashSet::new(),
         };
 
         let stream; // in this scope to make sure this last through the event loop
@@ -272,12 +515,29 @@ fn main() {
                 app.render(&args);
             }
 
+            if let Some(button) = e.press_args() {
+                app.input(button, true);
+            }
+
+            if let Some(button) = e.release_args() {
+                app.input(button, false);
+            }
+
+            if let Some(args) = e.controller_axis_args() {
+                app.axis_moved(args.axis, args.position);
+            }
+
             if let Some(u) = e.update_args() {
                 if app.update(u) == AppResult::Finish {
                     break;
                 }
             }
         }
+
+        // Write the save on exit
+        if let Some(save_file) = &mut app.save_file {
+            save_file.flush(&app.gb);
+        }
     }
 
     // Write the log
@@ -0,0 +1,142 @@
+use crate::gameboy::Gameboy;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+/// How long to wait after writing the save before writing it again
+const FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Keeps battery backed cartridge RAM in a file
+pub struct SaveFile {
+    path: PathBuf,
+    dirty: bool,
+    last_flush: Instant,
+}
+
+impl SaveFile {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            dirty: false,
+            last_flush: Instant::now(),
+        }
+    }
+
+    /// Load the save into the Gameboy. It's fine for there not to be one yet.
+    pub fn load(&self, gb: &mut Gameboy) -> std::io::Result<()> {
+        match fs::read(&self.path) {
+            Ok(data) => {
+                gb.load_save_data(&data);
+                Ok(())
+            }
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(()),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Write the save if the cartridge RAM has changed.
+    /// This is limited so games which write RAM every frame don't hammer the disk.
+    pub fn update(&mut self, gb: &mut Gameboy) {
+        self.dirty |= gb.take_save_dirty();
+
+        if self.dirty && self.last_flush.elapsed() >= FLUSH_INTERVAL {
+            self.flush(gb);
+        }
+    }
+
+    pub fn flush(&mut self, gb: &Gameboy) {
+        // Write to a temporary file first so a crash can't leave half a save
+        let temp_path = self.path.with_extension("sav.tmp");
+        let result = fs::write(&temp_path, gb.get_save_data())
+            .and_then(|_| fs::rename(&temp_path, &self.path));
+
+        if let Err(err) = result {
+            println!(
+                "Failed to write save {} with error {}",
+                self.path.display(),
+                err
+            );
+        }
+
+        self.dirty = false;
+        self.last_flush = Instant::now();
+    }
+}
+
+/// A save path in a fresh directory under the system temp directory
+#[cfg(test)]
+fn test_save_path(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("rust-gb-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir.join("game.sav")
+}
+
+/// ROM + RAM + battery with 8KB of cartridge RAM
+#[cfg(test)]
+fn battery_gb<'a>() -> Gameboy<'a> {
+    let mut rom = vec![0x00; 0x8000];
+    rom[0x147] = 0x09;
+    rom[0x149] = 0x02;
+    Gameboy::new(rom)
+}
+
+#[test]
+fn a_missing_save_loads_as_ok() {
+    let path = test_save_path("missing-save");
+    let mut gb = battery_gb();
+
+    assert!(SaveFile::new(path.clone()).load(&mut gb).is_ok());
+    assert!(gb.get_save_data().iter().all(|&byte| byte == 0x00));
+
+    fs::remove_dir_all(path.parent().unwrap()).unwrap();
+}
+
+#[test]
+fn saves_round_trip_the_cartridge_ram() {
+    let path = test_save_path("round-trip");
+    let mut gb = battery_gb();
+    gb.set_memory_at(0xA000, 0x12);
+    gb.set_memory_at(0xBFFF, 0x34);
+
+    SaveFile::new(path.clone()).flush(&gb);
+
+    let mut loaded = battery_gb();
+    SaveFile::new(path.clone()).load(&mut loaded).unwrap();
+    assert_eq!(loaded.get_save_data(), gb.get_save_data());
+    assert_eq!(loaded.get_memory_at(0xA000), 0x12);
+
+    fs::remove_dir_all(path.parent().unwrap()).unwrap();
+}
+
+#[test]
+fn update_only_writes_once_the_flush_interval_has_passed() {
+    let path = test_save_path("flush-interval");
+    let mut gb = battery_gb();
+    let mut save_file = SaveFile::new(path.clone());
+
+    gb.set_memory_at(0xA000, 0x12);
+    save_file.update(&mut gb);
+    assert!(!path.exists());
+
+    // The change is remembered until the interval is up
+    save_file.last_flush = Instant::now().checked_sub(FLUSH_INTERVAL).unwrap();
+    save_file.update(&mut gb);
+    assert_eq!(fs::read(&path).unwrap()[0], 0x12);
+
+    fs::remove_dir_all(path.parent().unwrap()).unwrap();
+}
+
+#[test]
+fn flushing_leaves_no_temporary_file() {
+    let path = test_save_path("no-temp-file");
+    let gb = battery_gb();
+
+    SaveFile::new(path.clone()).flush(&gb);
+
+    assert!(path.exists());
+    assert!(!path.with_extension("sav.tmp").exists());
+
+    fs::remove_dir_all(path.parent().unwrap()).unwrap();
+}